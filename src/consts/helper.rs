@@ -1,17 +1,18 @@
-use sdl2::{
-    gfx::primitives::DrawRenderer,
-    pixels::Color,
-    rect::{Point, Rect},
-    render::{BlendMode, Canvas},
-    video::Window,
-};
+use sdl2::rect::Point;
 use vector2d::Vector2D;
 
 use crate::{
-    enums::{game_object::GameObject, unit_type::UnitType},
+    enums::{
+        command::Command, game_object::GameObject, structure_type::StructureType,
+        unit_type::UnitType,
+    },
     structs::{
+        damage::Defense,
         ent::{Ent, EntID, EntParentType, Owner},
-        order::{EntTarget, Order},
+        order::EntTarget,
+        ore::Ore,
+        ore_patch::OreType,
+        structure::{Structure, StructureParentType},
         unit::{Unit, UnitParentType},
         world::World,
         world_info::WorldInfo,
@@ -19,14 +20,16 @@ use crate::{
 };
 
 use super::values::{
-    BASE_COLLECTOR_MAX_HP, BASE_COLLECTOR_RECT_SIZE, BASE_MINER_MAX_HP, BASE_MINER_RECT_SIZE,
-    BASE_SCOUT_MAX_HP, BASE_SCOUT_RECT_SIZE, COLLECTOR_ENT_COLOR, MINER_ENT_COLOR, SCOUT_ENT_COLOR,
-    SELECTION_BORDER_SIZE,
+    BASE_COLLECTOR_MAX_HP, BASE_COLLECTOR_RECT_SIZE, BASE_MAINFRAME_MAX_HP,
+    BASE_MAINFRAME_RECT_SIZE, BASE_MINER_MAX_HP, BASE_MINER_RECT_SIZE, BASE_SCOUT_MAX_HP,
+    BASE_SCOUT_RECT_SIZE, BASE_SPAWNER_MAX_HP, BASE_SPAWNER_RECT_SIZE, BLUE_RGB, COLLECTOR_ARMOR,
+    COLLECTOR_BUILD_TICKS, COLLECTOR_COST, COLLECTOR_ENT_COLOR, COLLECTOR_MAX_SHIELD,
+    CONSTRUCTION_START_HP_RATIO, MAINFRAME_ARMOR, MAINFRAME_BUILD_TICKS, MAINFRAME_COST,
+    MAINFRAME_ENT_COLOR, MAINFRAME_MAX_SHIELD, MINER_ARMOR, MINER_BUILD_TICKS, MINER_COST,
+    MINER_ENT_COLOR, MINER_MAX_SHIELD, SCOUT_ARMOR, SCOUT_BUILD_TICKS, SCOUT_COST, SCOUT_ENT_COLOR,
+    SCOUT_MAX_SHIELD, SPAWNER_ARMOR, SPAWNER_ENT_COLOR, SPAWNER_MAX_SHIELD,
 };
 
-// Counter to guarantee a unique EntID
-pub static mut CURRENT_ENT_ID: EntID = EntID(0);
-
 // This method returns a normalized vector with size speed that points from, to
 pub fn get_direction_from_to(from: Vector2D<f32>, to: Vector2D<f32>, speed: f32) -> Vector2D<f32> {
     (to - from).normalise() * speed
@@ -49,73 +52,23 @@ pub fn find_selection_box_translation(curr_pos: Point, origin: Point) -> Point {
     )
 }
 
-// This method renders an order waypoint to the screen
-pub fn draw_waypoint(order: Order, canvas: &mut Canvas<Window>) {
-    let waypoint_rect: Rect = Rect::from_center(
-        Point::new(
-            order.current_move_target.x as i32,
-            order.current_move_target.y as i32,
-        ),
-        5,
-        5,
-    );
-    canvas.fill_rect(waypoint_rect).ok();
-}
-
-// Renders rect selection border behind selected entity
-pub fn draw_rect_selection_border(canvas: &mut Canvas<Window>, ent_rect: &Rect, color: Color) {
-    canvas.set_blend_mode(BlendMode::Blend);
-    canvas.set_draw_color(color);
-    let selection_border_rect: Rect = Rect::new(
-        (ent_rect.x as f32 - (SELECTION_BORDER_SIZE / 2.0)) as i32,
-        (ent_rect.y as f32 - (SELECTION_BORDER_SIZE / 2.0)) as i32,
-        ent_rect.width() + SELECTION_BORDER_SIZE as u32,
-        ent_rect.height() + SELECTION_BORDER_SIZE as u32,
-    );
-    canvas.fill_rect(selection_border_rect).ok();
-    canvas.set_blend_mode(BlendMode::None);
-}
-
-// Renders circle selection border behind selected entity
-pub fn draw_circle_selection_border(
-    canvas: &mut Canvas<Window>,
-    ent_position: Vector2D<f32>,
-    ent_radius: i16,
-    color: Color,
-) {
-    canvas.set_blend_mode(BlendMode::Blend);
-    canvas.set_draw_color(color);
-    canvas
-        .filled_circle(
-            ent_position.x as i16,
-            ent_position.y as i16,
-            ent_radius + 3 as i16,
-            color,
-        )
-        .ok();
-    canvas.set_blend_mode(BlendMode::None);
-}
-
-// Selects all (if any) player owned army units
+// Builds a command selecting all (if any) player owned army units
 // Note: replaces current selection)
-pub fn select_all_army(world: &mut World) {
-    for game_object in &mut world.game_objects {
+pub fn select_all_army(world: &World) -> Command {
+    let mut army: Vec<EntID> = Vec::<EntID>::new();
+    for game_object in &world.game_objects {
         match game_object {
-            GameObject::Unit(ent, unit_type) => {
-                if ent.owner == Owner::Player {
-                    match unit_type {
-                        UnitType::Scout(_) => ent.select(),
-                        UnitType::Miner(_) => ent.deselect(),
-                        UnitType::Collector(_) => ent.deselect(),
-                    }
-                } else {
-                    ent.deselect();
-                }
+            GameObject::Unit(ent, UnitType::Scout(_)) if ent.owner == Owner::Player => {
+                army.push(ent.id);
             }
             _ => {}
         }
     }
-    world.selection.open = false;
+    Command::Select {
+        issuer: Owner::Player,
+        ents: army,
+        add: false,
+    }
 }
 
 // Returns an emtpy EntTarget object
@@ -128,16 +81,40 @@ pub fn empty_ent_target() -> EntTarget {
     }
 }
 
+// Returns a new GameObject for a ball of ore holding the given amount
+pub fn new_ore(
+    world_info: &mut WorldInfo,
+    ent_id: EntID,
+    ore_type: OreType,
+    amount: f32,
+    position: Vector2D<f32>,
+) -> GameObject {
+    let mut new_ent = Ent::new(
+        ent_id,
+        EntParentType::Ore,
+        Owner::Nature,
+        amount.ceil() as u32,
+        position,
+        Point::new(amount as i32, amount as i32),
+        BLUE_RGB,
+    );
+    new_ent.hp = amount;
+    world_info.add_ore(&new_ent, ore_type);
+    GameObject::Ore(new_ent, Ore::new(ore_type, amount / 100.0))
+}
+
 // Returns a new GameObject with the appropriate unit stats
 pub fn new_unit(
     world_info: &mut WorldInfo,
+    ent_id: EntID,
     unit_type: UnitParentType,
     owner: Owner,
     position: Vector2D<f32>,
 ) -> GameObject {
     match unit_type {
         UnitParentType::Miner => {
-            let new_ent = Ent::new(
+            let mut new_ent = Ent::new(
+                ent_id,
                 EntParentType::Unit,
                 owner,
                 BASE_MINER_MAX_HP,
@@ -145,11 +122,13 @@ pub fn new_unit(
                 Point::new(BASE_MINER_RECT_SIZE, BASE_MINER_RECT_SIZE),
                 MINER_ENT_COLOR,
             );
+            new_ent.set_defense(Defense::combat(MINER_ARMOR, MINER_MAX_SHIELD));
             world_info.add_ent(&new_ent);
             GameObject::Unit(new_ent, UnitType::Miner(Unit::new(unit_type)))
         }
         UnitParentType::Scout => {
-            let new_ent = Ent::new(
+            let mut new_ent = Ent::new(
+                ent_id,
                 EntParentType::Unit,
                 owner,
                 BASE_SCOUT_MAX_HP,
@@ -157,11 +136,13 @@ pub fn new_unit(
                 Point::new(BASE_SCOUT_RECT_SIZE, BASE_SCOUT_RECT_SIZE),
                 SCOUT_ENT_COLOR,
             );
+            new_ent.set_defense(Defense::combat(SCOUT_ARMOR, SCOUT_MAX_SHIELD));
             world_info.add_ent(&new_ent);
             GameObject::Unit(new_ent, UnitType::Scout(Unit::new(unit_type)))
         }
         UnitParentType::Collector => {
-            let new_ent = Ent::new(
+            let mut new_ent = Ent::new(
+                ent_id,
                 EntParentType::Unit,
                 owner,
                 BASE_COLLECTOR_MAX_HP,
@@ -169,8 +150,142 @@ pub fn new_unit(
                 Point::new(BASE_COLLECTOR_RECT_SIZE, BASE_COLLECTOR_RECT_SIZE),
                 COLLECTOR_ENT_COLOR,
             );
+            new_ent.set_defense(Defense::combat(COLLECTOR_ARMOR, COLLECTOR_MAX_SHIELD));
             world_info.add_ent(&new_ent);
             GameObject::Unit(new_ent, UnitType::Collector(Unit::new(unit_type)))
         }
     }
 }
+
+// Returns what producing a unit costs
+pub fn unit_cost(unit_type: UnitParentType) -> Vec<(OreType, f32)> {
+    match unit_type {
+        UnitParentType::Miner => vec![(OreType::Blue, MINER_COST)],
+        UnitParentType::Scout => vec![(OreType::Blue, SCOUT_COST)],
+        UnitParentType::Collector => vec![(OreType::Blue, COLLECTOR_COST)],
+    }
+}
+
+// Returns how many ticks producing a unit takes
+pub fn unit_build_ticks(unit_type: UnitParentType) -> u32 {
+    match unit_type {
+        UnitParentType::Miner => MINER_BUILD_TICKS,
+        UnitParentType::Scout => SCOUT_BUILD_TICKS,
+        UnitParentType::Collector => COLLECTOR_BUILD_TICKS,
+    }
+}
+
+// Returns the size of a unit's rect
+pub fn unit_rect_size(unit_type: UnitParentType) -> i32 {
+    match unit_type {
+        UnitParentType::Miner => BASE_MINER_RECT_SIZE,
+        UnitParentType::Scout => BASE_SCOUT_RECT_SIZE,
+        UnitParentType::Collector => BASE_COLLECTOR_RECT_SIZE,
+    }
+}
+
+// Returns what building a structure costs
+pub fn structure_cost(structure_type: StructureParentType) -> Vec<(OreType, f32)> {
+    match structure_type {
+        StructureParentType::Mainframe => vec![(OreType::Blue, MAINFRAME_COST)],
+        // Not buildable
+        StructureParentType::Spawner => Vec::<(OreType, f32)>::new(),
+    }
+}
+
+// Returns how many ticks of work building a structure takes
+pub fn structure_build_ticks(structure_type: StructureParentType) -> u32 {
+    match structure_type {
+        StructureParentType::Mainframe => MAINFRAME_BUILD_TICKS,
+        // Not buildable
+        StructureParentType::Spawner => 1,
+    }
+}
+
+// Returns the size of a structure's rect
+pub fn structure_rect_size(structure_type: StructureParentType) -> i32 {
+    match structure_type {
+        StructureParentType::Mainframe => BASE_MAINFRAME_RECT_SIZE,
+        StructureParentType::Spawner => BASE_SPAWNER_RECT_SIZE,
+    }
+}
+
+// Returns a new GameObject with the appropriate structure stats
+pub fn new_structure(
+    world_info: &mut WorldInfo,
+    ent_id: EntID,
+    structure_type: StructureParentType,
+    owner: Owner,
+    position: Vector2D<f32>,
+) -> GameObject {
+    let new_ent = new_structure_ent(ent_id, structure_type, owner, position);
+    world_info.add_structure(&new_ent, structure_type);
+    match structure_type {
+        StructureParentType::Mainframe => GameObject::Structure(
+            new_ent,
+            StructureType::Mainframe(Structure::new(structure_type)),
+        ),
+        StructureParentType::Spawner => GameObject::Structure(
+            new_ent,
+            StructureType::Spawner(Structure::new(structure_type)),
+        ),
+    }
+}
+
+// Returns a new GameObject for a structure that still has to be built
+// It only starts counting as a structure of its type once done
+pub fn new_construction_site(
+    world_info: &mut WorldInfo,
+    ent_id: EntID,
+    structure_type: StructureParentType,
+    owner: Owner,
+    position: Vector2D<f32>,
+) -> GameObject {
+    let mut new_ent = new_structure_ent(ent_id, structure_type, owner, position);
+    new_ent.hp = new_ent.max_hp as f32 * CONSTRUCTION_START_HP_RATIO;
+    new_ent.shield = 0.0;
+    world_info.add_ent(&new_ent);
+    match structure_type {
+        StructureParentType::Mainframe => GameObject::Structure(
+            new_ent,
+            StructureType::Mainframe(Structure::new_under_construction(structure_type)),
+        ),
+        StructureParentType::Spawner => GameObject::Structure(
+            new_ent,
+            StructureType::Spawner(Structure::new_under_construction(structure_type)),
+        ),
+    }
+}
+
+fn new_structure_ent(
+    ent_id: EntID,
+    structure_type: StructureParentType,
+    owner: Owner,
+    position: Vector2D<f32>,
+) -> Ent {
+    let mut new_ent = match structure_type {
+        StructureParentType::Mainframe => Ent::new(
+            ent_id,
+            EntParentType::Structure,
+            owner,
+            BASE_MAINFRAME_MAX_HP,
+            position,
+            Point::new(BASE_MAINFRAME_RECT_SIZE, BASE_MAINFRAME_RECT_SIZE),
+            MAINFRAME_ENT_COLOR,
+        ),
+        StructureParentType::Spawner => Ent::new(
+            ent_id,
+            EntParentType::Structure,
+            owner,
+            BASE_SPAWNER_MAX_HP,
+            position,
+            Point::new(BASE_SPAWNER_RECT_SIZE, BASE_SPAWNER_RECT_SIZE),
+            SPAWNER_ENT_COLOR,
+        ),
+    };
+    new_ent.set_defense(match structure_type {
+        StructureParentType::Mainframe => Defense::combat(MAINFRAME_ARMOR, MAINFRAME_MAX_SHIELD),
+        StructureParentType::Spawner => Defense::combat(SPAWNER_ARMOR, SPAWNER_MAX_SHIELD),
+    });
+    new_ent
+}
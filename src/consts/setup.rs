@@ -1,12 +1,14 @@
-use rand::Rng;
+use rand::{Rng, RngCore};
 use sdl2::rect::Point;
 use vector2d::Vector2D;
 
 use crate::{
     enums::game_object::GameObject,
     structs::{
+        ai_player::{AiDifficulty, AiPlayer},
         ent::{Ent, EntParentType, Owner},
         ore_patch::{OrePatch, OreType},
+        structure::StructureParentType,
         unit::UnitParentType,
         world::World,
         world_info::WorldInfo,
@@ -14,13 +16,46 @@ use crate::{
 };
 
 use super::{
-    helper::new_unit,
+    helper::{new_structure, new_unit},
     values::{BLUE_RGB, MAP_HEIGHT, MAP_WIDTH, SCREEN_HEIGHT, SCREEN_WIDTH},
 };
 
+// Describes how a match starts
+// Shared by the windowed game and headless runs, so both start from the exact same world
+pub struct Scenario {
+    pub seed: u64,            // Seed for the world's rng; Same seed, same match
+    pub debug_ent_count: i32, // How many debug units to spawn
+    pub ai_difficulty: Option<AiDifficulty>, // How well the computer plays Owner::Cpu; None leaves it to its units
+}
+
+impl Scenario {
+    pub fn new(seed: u64, debug_ent_count: i32, ai_difficulty: Option<AiDifficulty>) -> Self {
+        Self {
+            seed,
+            debug_ent_count,
+            ai_difficulty,
+        }
+    }
+
+    // Builds a fresh World and WorldInfo pair for this scenario
+    pub fn setup(&self) -> (World, WorldInfo) {
+        let mut world = World::new(self.seed);
+        let mut world_info = WorldInfo::new();
+        spawn_debug_ents(self.debug_ent_count, &mut world, &mut world_info);
+        if let Some(ai_difficulty) = self.ai_difficulty {
+            spawn_ai_base(&mut world, &mut world_info);
+            let ai_seed = world.rng.next_u64();
+            world.ai_players.insert(
+                Owner::Cpu,
+                AiPlayer::new(Owner::Cpu, ai_difficulty, ai_seed),
+            );
+        }
+        (world, world_info)
+    }
+}
+
 // Debug method; spawns some ents for testing
 pub fn spawn_debug_ents(n: i32, world: &mut World, world_info: &mut WorldInfo) {
-    let mut rng = rand::thread_rng();
     let mut game_objects_to_add = Vec::<GameObject>::new();
     for i in 0..n {
         let unit_type = if i < n / 3 {
@@ -31,29 +66,105 @@ pub fn spawn_debug_ents(n: i32, world: &mut World, world_info: &mut WorldInfo) {
             UnitParentType::Collector
         };
         let position = Vector2D::<f32>::new(
-            rng.gen_range(MAP_WIDTH / 2 + 25..MAP_WIDTH / 2 + SCREEN_WIDTH) as f32,
-            rng.gen_range(MAP_HEIGHT / 2 + 25..MAP_HEIGHT / 2 + SCREEN_HEIGHT) as f32,
+            world
+                .rng
+                .gen_range(MAP_WIDTH / 2 + 25..MAP_WIDTH / 2 + SCREEN_WIDTH) as f32,
+            world
+                .rng
+                .gen_range(MAP_HEIGHT / 2 + 25..MAP_HEIGHT / 2 + SCREEN_HEIGHT) as f32,
         );
         let owner = if i > n - 3 { Owner::Cpu } else { Owner::Player };
-        game_objects_to_add.push(new_unit(world_info, unit_type, owner, position));
+        let ent_id = world.ent_ids.next_id();
+        game_objects_to_add.push(new_unit(world_info, ent_id, unit_type, owner, position));
     }
 
     world.game_objects.append(&mut game_objects_to_add);
 
     let new_ent = Ent::new(
+        world.ent_ids.next_id(),
         EntParentType::OrePatch,
         Owner::Nature,
         100,
         Vector2D::<f32>::new(
-            rng.gen_range(500..750) as f32,
-            rng.gen_range(650..850) as f32,
+            world.rng.gen_range(500..750) as f32,
+            world.rng.gen_range(650..850) as f32,
         ),
-        Point::new(rng.gen_range(50..100), rng.gen_range(50..100)),
+        Point::new(world.rng.gen_range(50..100), world.rng.gen_range(50..100)),
         BLUE_RGB,
     );
+    let ore_patch_position = new_ent.position;
     world_info.add_ent(&new_ent);
     world.game_objects.push(GameObject::OrePatch(
         new_ent,
         OrePatch::new(OreType::Blue, 10, 0.1),
     ));
+
+    // Cpu spawner guarding the ore patch, a bit up and to the left of it
+    let ent_id = world.ent_ids.next_id();
+    world.game_objects.push(new_structure(
+        world_info,
+        ent_id,
+        StructureParentType::Spawner,
+        Owner::Cpu,
+        ore_patch_position - Vector2D::<f32>::new(250.0, 250.0),
+    ));
+
+    // Player's main base, right next to where the units spawn
+    let ent_id = world.ent_ids.next_id();
+    world.game_objects.push(new_structure(
+        world_info,
+        ent_id,
+        StructureParentType::Mainframe,
+        Owner::Player,
+        Vector2D::<f32>::new((MAP_WIDTH / 2 - 250) as f32, (MAP_HEIGHT / 2 - 250) as f32),
+    ));
+}
+
+// Gives the computer player a base to start from, across the map from the player's:
+// a mainframe, an ore patch of its own, and a couple of units to work it
+pub fn spawn_ai_base(world: &mut World, world_info: &mut WorldInfo) {
+    let base_position = Vector2D::<f32>::new((MAP_WIDTH - 700) as f32, 400.0);
+
+    let ent_id = world.ent_ids.next_id();
+    world.game_objects.push(new_structure(
+        world_info,
+        ent_id,
+        StructureParentType::Mainframe,
+        Owner::Cpu,
+        base_position,
+    ));
+
+    let new_ent = Ent::new(
+        world.ent_ids.next_id(),
+        EntParentType::OrePatch,
+        Owner::Nature,
+        100,
+        base_position + Vector2D::<f32>::new(-350.0, 300.0),
+        Point::new(75, 75),
+        BLUE_RGB,
+    );
+    world_info.add_ent(&new_ent);
+    world.game_objects.push(GameObject::OrePatch(
+        new_ent,
+        OrePatch::new(OreType::Blue, 10, 0.1),
+    ));
+
+    for (i, unit_type) in [
+        UnitParentType::Miner,
+        UnitParentType::Miner,
+        UnitParentType::Collector,
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        let ent_id = world.ent_ids.next_id();
+        let position = base_position + Vector2D::<f32>::new(i as f32 * 50.0, 150.0);
+        world.game_objects.push(new_unit(
+            world_info,
+            ent_id,
+            unit_type,
+            Owner::Cpu,
+            position,
+        ));
+    }
 }
@@ -21,9 +21,12 @@ pub const MAP_PADDING: u32 = 100;
 // Entity health bar dimensions
 pub const HEALTH_BAR_WIDTH: f32 = 100.0;
 pub const HEALTH_BAR_HEIGHT: f32 = 8.0;
+pub const SHIELD_BAR_HEIGHT: f32 = 4.0;
 // How far above an entity it's health bar will sit (might wanna automate this later based on zoom scale?)
 pub const HEALTH_BAR_Y_FLOAT: f32 = 35.0;
 
+pub const PROJECTILE_RENDER_LENGTH: f32 = 10.0; // Length of the segment drawn for a projectile in flight
+
 // Base unit stats
 pub const BASE_MINER_MAX_HP: u32 = 100;
 pub const BASE_MINER_RECT_SIZE: i32 = 25;
@@ -34,21 +37,153 @@ pub const SCOUT_ENT_COLOR: Color = Color::RGB(78, 155, 93);
 pub const BASE_COLLECTOR_MAX_HP: u32 = 100;
 pub const BASE_COLLECTOR_RECT_SIZE: i32 = 25;
 pub const COLLECTOR_ENT_COLOR: Color = Color::RGB(93, 78, 155);
+pub const BASE_COLLECTOR_DEPOSIT_RATE: f32 = 25.0; // How much ore a collector beams into a mainframe per second
 pub const BASE_UNIT_SPEED: f32 = 150.0; // How fast it can move
-pub const BASE_UNIT_DAMAGE: f32 = 3.0; // How much damage it deals when attacking
-pub const BASE_UNIT_RANGE: f32 = 125.0; // How far away can it attack
+pub const BASE_UNIT_RANGE: f32 = 125.0; // How far away can it interact with things (attack, mine, collect, ...)
+pub const BASE_COLLECTOR_COLLECT_RATE: f32 = 3.0; // How much ore a collector picks up per second
 pub const BASE_UNIT_MASS: f32 = 8.0; // How heavy a unit is; Impacts steering
+pub const SOFT_COLLISION_SEPARATION_SPEED: f32 = 60.0; // How fast overlapping soft colliding units get pushed apart
 pub const ATTACKER_SPEED_PENALTY: f32 = 0.35; // A scalar that gets applied to unit speed while it is attacking
 
+// Base weapon stats
+pub const SCOUT_WEAPON_DAMAGE: f32 = 3.0; // How much damage a single shot deals
+pub const SCOUT_WEAPON_COOLDOWN_TICKS: u32 = 60; // How long (in ticks) it takes to shoot again
+pub const SCOUT_WEAPON_WINDUP_TICKS: u32 = 10; // How long (in ticks) it takes to aim a shot
+pub const SCOUT_PROJECTILE_SPEED: f32 = 450.0; // How fast its shots fly over to the target
+pub const SCOUT_WEAPON_SPLASH_RADIUS: f32 = 30.0; // Shots also hurt anything else this close to where they land
+pub const MINER_WEAPON_DAMAGE: f32 = 1.0;
+pub const MINER_WEAPON_COOLDOWN_TICKS: u32 = 20;
+pub const MINER_WEAPON_WINDUP_TICKS: u32 = 0;
+pub const SPLASH_EDGE_DAMAGE_RATIO: f32 = 0.25; // How much of a shot's damage splash still deals at the very edge of its radius
+
+// Base defense stats
+pub const SCOUT_ARMOR: f32 = 0.0; // Flat damage reduction per hit
+pub const SCOUT_MAX_SHIELD: f32 = 20.0; // How much damage the shield soaks up before hp starts getting hit
+pub const MINER_ARMOR: f32 = 1.0;
+pub const MINER_MAX_SHIELD: f32 = 0.0;
+pub const COLLECTOR_ARMOR: f32 = 0.0;
+pub const COLLECTOR_MAX_SHIELD: f32 = 0.0;
+pub const MAINFRAME_ARMOR: f32 = 1.0;
+pub const MAINFRAME_MAX_SHIELD: f32 = 100.0;
+pub const SPAWNER_ARMOR: f32 = 2.0;
+pub const SPAWNER_MAX_SHIELD: f32 = 0.0;
+pub const SHIELD_REGEN_RATE: f32 = 2.0; // How much shield comes back per second
+pub const SHIELD_REGEN_DELAY_TICKS: u32 = 300; // How long (in ticks) a shield waits after taking damage before coming back
+pub const MIN_ARMORED_DAMAGE_RATIO: f32 = 0.5; // Armor can never block more than this much of a hit
+
+// Base structure stats
+pub const BASE_MAINFRAME_MAX_HP: u32 = 1000;
+pub const BASE_MAINFRAME_RECT_SIZE: i32 = 100;
+pub const MAINFRAME_ENT_COLOR: Color = Color::RGB(120, 120, 140);
+pub const BASE_SPAWNER_MAX_HP: u32 = 600;
+pub const BASE_SPAWNER_RECT_SIZE: i32 = 75;
+pub const SPAWNER_ENT_COLOR: Color = Color::RGB(140, 40, 60);
+
+// Economy constants
+pub const UNIT_SUPPLY_COST: u32 = 1; // How much supply a single unit takes up
+pub const MAINFRAME_SUPPLY: u32 = 20; // How much supply a mainframe provides
+pub const PLAYER_INCOME_WINDOW_TICKS: u64 = 600; // How long (in ticks) income gets measured over, before being turned into a rate
+
+// Production constants
+pub const SCOUT_COST: f32 = 50.0;
+pub const SCOUT_BUILD_TICKS: u32 = 300;
+pub const MINER_COST: f32 = 75.0;
+pub const MINER_BUILD_TICKS: u32 = 420;
+pub const COLLECTOR_COST: f32 = 50.0;
+pub const COLLECTOR_BUILD_TICKS: u32 = 360;
+pub const MAX_PRODUCTION_QUEUE_LENGTH: usize = 5; // How many units a structure can have queued up at once
+pub const PRODUCTION_SPAWN_GAP: i32 = 10; // How far away from its structure a freshly produced unit pops out
+pub const PRODUCTION_SPAWN_SEARCH_RINGS: i32 = 3; // How many rings of spots around a structure get checked for room to spawn a unit
+pub const PRODUCTION_BAR_HEIGHT: u32 = 8; // Height of the production progress bar drawn under structures
+
+// Spawner constants (defaults; Every spawner can be tuned on its own)
+pub const SPAWNER_AGGRESSION_THRESHOLD: f32 = 20.0; // How much damage to nearby ore patches it takes to send out a wave
+pub const SPAWNER_AGGRESSION_DECAY: f32 = 0.005; // How much aggression wears off every tick
+pub const SPAWNER_COOLDOWN_TICKS: u32 = 1200; // How long (in ticks) a spawner waits between waves
+pub const SPAWNER_WAVE_SIZE: u32 = 3; // How many units a wave is made of
+pub const SPAWNER_AGGRO_RADIUS: f32 = 600.0; // How far away from a spawner ore patches get watched over
+
+// Construction constants
+pub const MAINFRAME_COST: f32 = 400.0;
+pub const MAINFRAME_BUILD_TICKS: u32 = 1800;
+pub const CONSTRUCTION_START_HP_RATIO: f32 = 0.1; // How much of its max hp a structure starts out with when placed
+pub const CONSTRUCTION_CANCEL_REFUND_RATIO: f32 = 0.75; // How much of its cost a cancelled construction site gives back
+pub const CONSTRUCTION_SITE_ALPHA: u8 = 120; // How see-through a structure is while under construction
+
+// Computer player constants
+pub const AI_THINK_INTERVAL_TICKS_EASY: u64 = 180; // How long (in ticks) an easy AI waits between looks at the world
+pub const AI_THINK_INTERVAL_TICKS_NORMAL: u64 = 90;
+pub const AI_THINK_INTERVAL_TICKS_HARD: u64 = 30;
+pub const AI_BLUNDER_CHANCE_EASY: f32 = 0.4; // Chance for an easy AI to skip any single decision
+pub const AI_BLUNDER_CHANCE_NORMAL: f32 = 0.15;
+pub const AI_BLUNDER_CHANCE_HARD: f32 = 0.0;
+pub const AI_ATTACK_ARMY_SIZE_EASY: usize = 4; // How many army units an easy AI gathers before attacking
+pub const AI_ATTACK_ARMY_SIZE_NORMAL: usize = 8;
+pub const AI_ATTACK_ARMY_SIZE_HARD: usize = 12;
+pub const AI_RETREAT_HP_RATIO_NORMAL: f32 = 0.4; // Share of its starting hp a normal AI's attack group falls back at
+pub const AI_RETREAT_HP_RATIO_HARD: f32 = 0.6;
+pub const AI_MINERS_PER_MAINFRAME: usize = 4; // How many miners the AI wants for every mainframe it has
+pub const AI_COLLECTORS_PER_MINER: f32 = 1.0; // How many collectors the AI wants for every miner it has
+pub const AI_SUPPLY_HEADROOM: u32 = 4; // How much free supply the AI wants before it puts down another mainframe
+pub const AI_GATHER_RADIUS: f32 = 250.0; // How far away from its base idle army units are allowed to wander
+pub const AI_EXPANSION_GAP: i32 = 50; // Space left between the AI's mainframes
+pub const AI_EXPANSION_SEARCH_RINGS: i32 = 4; // How many rings of spots around its base the AI checks for room to build
+
 // Order constants
 pub const FOLLOW_ORDER_HOVER_DISTANCE: f32 = 100.0; // Scalar distance that representes how far away from its target a following unit will halt
 pub const MAX_MOVE_ORDER_ERROR: f32 = 5.0; // Maximum distance that a unit can be off from it's move target for it to be considered done with the move order
 
+// Formation constants
+pub const FORMATION_SPACING: f32 = 35.0; // Distance between neighbouring spots in a formation; A unit plus some breathing room
+pub const FORMATION_ROW_LENGTH: usize = 8; // Most units a formation puts side by side; Any more start a new row behind
+pub const FORMATION_MAX_SPREAD: f32 = 300.0; // Groups spread out further than this get lined up, rather than keeping their shape
+
+// Pathfinding constants
+pub const NAV_CELL_SIZE: i32 = 25; // Size (in pixels) of a navigation grid cell
+pub const NAV_OBSTACLE_CLEARANCE: i32 = 13; // How much static obstacles get inflated on the navigation grid; About half a unit
+pub const NAV_FREE_CELL_SEARCH_RADIUS: i32 = 20; // How far (in cells) to look for a free cell around a blocked one
+pub const NAV_WAYPOINT_REACHED_DISTANCE: f32 = 15.0; // How close a unit must get to a path waypoint to move on to the next one
+pub const NAV_REPLAN_DISTANCE: f32 = 50.0; // How far a destination can move (i.e. a followed unit) before its path gets planned again
+
+// Spatial hash constants
+pub const SPATIAL_HASH_CELL_SIZE: i32 = 100; // Size (in pixels) of a spatial hash cell; A few units wide, and about a unit's attack range
+
 // Defines a size for selection borders
 pub const SELECTION_BORDER_SIZE: f32 = 8.0;
 
+// How far (in pixels) the mouse can be dragged while selecting for it to still count as a click
+pub const CLICK_SELECT_MAX_DRAG: u32 = 4;
+
+// Control groups
+pub const CONTROL_GROUP_COUNT: usize = 10; // One for every digit key
+pub const CONTROL_GROUP_DOUBLE_TAP_MS: u32 = 300; // How quickly (in milliseconds) a group has to be recalled twice for the camera to jump to it
+
+// Default scenario values
+pub const DEFAULT_SEED: u64 = 0x6D69_6372_6F6E; // Seed used for the world's rng when none is given
+pub const DEFAULT_DEBUG_ENT_COUNT: i32 = 500; // How many debug units get spawned at startup
+pub const DEFAULT_HEADLESS_TICKS: u32 = 3600; // How many ticks a headless run simulates when not told otherwise
+
+// Save files
+pub const SAVE_FILE_HEADER: &str = "micron-save"; // First token of every save file
+pub const SAVE_FILE_VERSION: u32 = 16; // Bump whenever the save format changes
+pub const QUICKSAVE_PATH: &str = "quicksave.micron"; // Where quicksave / quickload read and write
+
+// Replays
+pub const REPLAY_FILE_HEADER: &str = "micron-replay"; // First token of every replay file
+pub const REPLAY_FILE_VERSION: u32 = 5; // Bump whenever the replay format changes
+pub const REPLAY_STATE_HASH_INTERVAL: u64 = 60; // How often (in ticks) a recording stores a state hash
+pub const REPLAY_SEEK_TICKS: u64 = 600; // How far a single seek jumps
+pub const REPLAY_MAX_SPEED: u32 = 8; // Maximum playback speed multiplier
+
+// Size (in pixels) of the rect used to check what the mouse is pointing at
+pub const MOUSE_RECT_SIZE: u32 = 2;
+
 // Defines the amount of time that should elapse between each physics step.
 pub const TIME_STEP: f32 = 1.0 / 60.0;
+// Maximum amount of fixed steps that can run in a single frame; Any remaining backlog gets dropped
+pub const MAX_TICKS_PER_FRAME: u32 = 5;
+// Maximum amount of real time (in seconds) that a single frame can feed into the simulation
+pub const MAX_FRAME_TIME: f32 = 0.25;
 
 // Color to clear the screen with
 pub const SCREEN_BACKGROUND_COLOR: Color = Color::RGB(64, 192, 255);
@@ -63,6 +198,17 @@ pub const SELECTION_FOLLOW_TARGET_BORDER_COLOR: Color = Color::RGBA(255, 255, 15
 pub const SELECTION_MINE_TARGET_BORDER_COLOR: Color = Color::RGBA(255, 255, 255, 200);
 // Color of the entity selection collect target borderm with alpha
 pub const SELECTION_COLLECT_TARGET_BORDER_COLOR: Color = Color::RGBA(175, 25, 55, 200);
+// Color of the entity selection deposit target border, with alpha
+pub const SELECTION_DEPOSIT_TARGET_BORDER_COLOR: Color = Color::RGBA(25, 175, 175, 200);
+// Color of the entity selection build target border, with alpha
+pub const SELECTION_BUILD_TARGET_BORDER_COLOR: Color = Color::RGBA(225, 175, 25, 200);
+// Color of patrol routes, with alpha
+pub const PATROL_ROUTE_COLOR: Color = Color::RGBA(100, 100, 255, 225);
+// Color of the line showing which way a formation will face, with alpha
+pub const FORMATION_FACING_COLOR: Color = Color::RGBA(0, 150, 0, 200);
+// Color of a structure's placement footprint, with alpha
+pub const PLACEMENT_VALID_COLOR: Color = Color::RGBA(50, 225, 50, 100);
+pub const PLACEMENT_INVALID_COLOR: Color = Color::RGBA(225, 50, 50, 100);
 // Color of the entity selection border, with alpha
 pub const SELECTION_BORDER_COLOR: Color = Color::RGBA(50, 225, 50, 225);
 
@@ -0,0 +1,254 @@
+use vector2d::Vector2D;
+
+use crate::structs::{
+    ent::{EntID, Owner},
+    order::Order,
+    save_file::{Persist, SaveReader, SaveWriter},
+    structure::StructureParentType,
+    unit::UnitParentType,
+};
+
+// Everything that can be asked of the simulation, by anyone (mouse & keyboard, AI, replays, scripts, ...)
+// Every command gets applied (and validated) through World::apply_command
+// The issuer is whoever sent the command; It can only control its own ents
+#[derive(Clone)]
+pub enum Command {
+    // Gives the same order to a group of units
+    // Units that do not belong to the issuer, or that cannot perform the order, are skipped
+    IssueOrder {
+        issuer: Owner,
+        units: Vec<EntID>,
+        order: Order,
+        queued: bool,
+    },
+    // Moves a group of units, giving each one its own spot around the destination
+    // Without a facing the group keeps its shape, with one it lines up in rows facing that way
+    // The whole group moves at the pace of its slowest member
+    FormationMove {
+        issuer: Owner,
+        units: Vec<EntID>,
+        destination: Vector2D<f32>,
+        facing: Option<Vector2D<f32>>,
+        queued: bool,
+    },
+    Stop {
+        issuer: Owner,
+        units: Vec<EntID>,
+    },
+    HoldPosition {
+        issuer: Owner,
+        units: Vec<EntID>,
+        queued: bool,
+    },
+    // Selects the given ents; Replaces the current selection, unless adding to it
+    // An empty selection leaves the current one untouched
+    Select {
+        issuer: Owner,
+        ents: Vec<EntID>,
+        add: bool,
+    },
+    ClearSelection {
+        issuer: Owner,
+    },
+    // Pays for a unit and adds it to a structure's production queue
+    // Skipped if the issuer cannot afford it, or if the queue is full
+    EnqueueProduction {
+        issuer: Owner,
+        structure: EntID,
+        unit_type: UnitParentType,
+    },
+    // Takes a unit out of a structure's production queue and refunds it
+    CancelProduction {
+        issuer: Owner,
+        structure: EntID,
+        index: usize,
+    },
+    // Sets the order every unit produced by these structures starts out with; None clears it
+    SetRallyPoint {
+        issuer: Owner,
+        structures: Vec<EntID>,
+        rally_point: Option<Order>,
+    },
+    // Pays for a structure, lays down its construction site (top left corner at position), and sends a builder over
+    // Skipped if the spot is taken, if the issuer cannot afford it, or if the builder cannot build
+    PlaceStructure {
+        issuer: Owner,
+        builder: EntID,
+        structure_type: StructureParentType,
+        position: Vector2D<f32>,
+        queued: bool,
+    },
+    // Tears down a construction site, refunding part of its cost
+    CancelConstruction {
+        issuer: Owner,
+        structure: EntID,
+    },
+}
+
+impl Persist for Command {
+    fn save(&self, writer: &mut SaveWriter) {
+        match self {
+            Command::IssueOrder {
+                issuer,
+                units,
+                order,
+                queued,
+            } => {
+                0u8.save(writer);
+                issuer.save(writer);
+                units.save(writer);
+                order.save(writer);
+                queued.save(writer);
+            }
+            Command::Stop { issuer, units } => {
+                1u8.save(writer);
+                issuer.save(writer);
+                units.save(writer);
+            }
+            Command::HoldPosition {
+                issuer,
+                units,
+                queued,
+            } => {
+                2u8.save(writer);
+                issuer.save(writer);
+                units.save(writer);
+                queued.save(writer);
+            }
+            Command::Select { issuer, ents, add } => {
+                3u8.save(writer);
+                issuer.save(writer);
+                ents.save(writer);
+                add.save(writer);
+            }
+            Command::ClearSelection { issuer } => {
+                4u8.save(writer);
+                issuer.save(writer);
+            }
+            Command::EnqueueProduction {
+                issuer,
+                structure,
+                unit_type,
+            } => {
+                5u8.save(writer);
+                issuer.save(writer);
+                structure.save(writer);
+                unit_type.save(writer);
+            }
+            Command::CancelProduction {
+                issuer,
+                structure,
+                index,
+            } => {
+                6u8.save(writer);
+                issuer.save(writer);
+                structure.save(writer);
+                index.save(writer);
+            }
+            Command::SetRallyPoint {
+                issuer,
+                structures,
+                rally_point,
+            } => {
+                7u8.save(writer);
+                issuer.save(writer);
+                structures.save(writer);
+                rally_point.save(writer);
+            }
+            Command::PlaceStructure {
+                issuer,
+                builder,
+                structure_type,
+                position,
+                queued,
+            } => {
+                8u8.save(writer);
+                issuer.save(writer);
+                builder.save(writer);
+                structure_type.save(writer);
+                position.save(writer);
+                queued.save(writer);
+            }
+            Command::CancelConstruction { issuer, structure } => {
+                9u8.save(writer);
+                issuer.save(writer);
+                structure.save(writer);
+            }
+            Command::FormationMove {
+                issuer,
+                units,
+                destination,
+                facing,
+                queued,
+            } => {
+                10u8.save(writer);
+                issuer.save(writer);
+                units.save(writer);
+                destination.save(writer);
+                facing.save(writer);
+                queued.save(writer);
+            }
+        }
+    }
+
+    fn load(reader: &mut SaveReader) -> Result<Self, String> {
+        Ok(match reader.read_variant(11)? {
+            0 => Command::IssueOrder {
+                issuer: Owner::load(reader)?,
+                units: Vec::<EntID>::load(reader)?,
+                order: Order::load(reader)?,
+                queued: bool::load(reader)?,
+            },
+            1 => Command::Stop {
+                issuer: Owner::load(reader)?,
+                units: Vec::<EntID>::load(reader)?,
+            },
+            2 => Command::HoldPosition {
+                issuer: Owner::load(reader)?,
+                units: Vec::<EntID>::load(reader)?,
+                queued: bool::load(reader)?,
+            },
+            3 => Command::Select {
+                issuer: Owner::load(reader)?,
+                ents: Vec::<EntID>::load(reader)?,
+                add: bool::load(reader)?,
+            },
+            4 => Command::ClearSelection {
+                issuer: Owner::load(reader)?,
+            },
+            5 => Command::EnqueueProduction {
+                issuer: Owner::load(reader)?,
+                structure: EntID::load(reader)?,
+                unit_type: UnitParentType::load(reader)?,
+            },
+            6 => Command::CancelProduction {
+                issuer: Owner::load(reader)?,
+                structure: EntID::load(reader)?,
+                index: usize::load(reader)?,
+            },
+            7 => Command::SetRallyPoint {
+                issuer: Owner::load(reader)?,
+                structures: Vec::<EntID>::load(reader)?,
+                rally_point: Option::<Order>::load(reader)?,
+            },
+            8 => Command::PlaceStructure {
+                issuer: Owner::load(reader)?,
+                builder: EntID::load(reader)?,
+                structure_type: StructureParentType::load(reader)?,
+                position: Vector2D::<f32>::load(reader)?,
+                queued: bool::load(reader)?,
+            },
+            9 => Command::CancelConstruction {
+                issuer: Owner::load(reader)?,
+                structure: EntID::load(reader)?,
+            },
+            _ => Command::FormationMove {
+                issuer: Owner::load(reader)?,
+                units: Vec::<EntID>::load(reader)?,
+                destination: Vector2D::<f32>::load(reader)?,
+                facing: Option::<Vector2D<f32>>::load(reader)?,
+                queued: bool::load(reader)?,
+            },
+        })
+    }
+}
@@ -1,10 +1,53 @@
-use crate::structs::{ent::Ent, ore::Ore, ore_patch::OrePatch, structure::Structure};
+use crate::structs::{
+    ent::Ent,
+    ore::Ore,
+    ore_patch::OrePatch,
+    save_file::{Persist, SaveReader, SaveWriter},
+};
 
-use super::unit_type::UnitType;
+use super::{structure_type::StructureType, unit_type::UnitType};
 
 pub enum GameObject {
     Unit(Ent, UnitType),
-    Structure(Ent, Structure),
+    Structure(Ent, StructureType),
     OrePatch(Ent, OrePatch),
     Ore(Ent, Ore),
 }
+
+impl Persist for GameObject {
+    fn save(&self, writer: &mut SaveWriter) {
+        writer.write_label("game_object");
+        match self {
+            GameObject::Unit(ent, unit_type) => {
+                0u8.save(writer);
+                ent.save(writer);
+                unit_type.save(writer);
+            }
+            GameObject::Structure(ent, structure) => {
+                1u8.save(writer);
+                ent.save(writer);
+                structure.save(writer);
+            }
+            GameObject::OrePatch(ent, ore_patch) => {
+                2u8.save(writer);
+                ent.save(writer);
+                ore_patch.save(writer);
+            }
+            GameObject::Ore(ent, ore) => {
+                3u8.save(writer);
+                ent.save(writer);
+                ore.save(writer);
+            }
+        }
+    }
+
+    fn load(reader: &mut SaveReader) -> Result<Self, String> {
+        reader.expect_label("game_object")?;
+        Ok(match reader.read_variant(4)? {
+            0 => GameObject::Unit(Ent::load(reader)?, UnitType::load(reader)?),
+            1 => GameObject::Structure(Ent::load(reader)?, StructureType::load(reader)?),
+            2 => GameObject::OrePatch(Ent::load(reader)?, OrePatch::load(reader)?),
+            _ => GameObject::Ore(Ent::load(reader)?, Ore::load(reader)?),
+        })
+    }
+}
@@ -1,3 +1,5 @@
+pub mod command;
 pub mod game_object;
+pub mod structure_type;
 pub mod ui_object;
 pub mod unit_type;
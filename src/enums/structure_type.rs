@@ -0,0 +1,28 @@
+use crate::structs::{
+    save_file::{Persist, SaveReader, SaveWriter},
+    structure::{Structure, StructureParentType},
+};
+
+pub enum StructureType {
+    Mainframe(Structure),
+    Spawner(Structure),
+}
+
+impl Persist for StructureType {
+    fn save(&self, writer: &mut SaveWriter) {
+        match self {
+            StructureType::Mainframe(structure) | StructureType::Spawner(structure) => {
+                structure.save(writer)
+            }
+        }
+    }
+
+    // The structure's parent type tells which variant it belongs to
+    fn load(reader: &mut SaveReader) -> Result<Self, String> {
+        let structure = Structure::load(reader)?;
+        Ok(match structure.parent_type() {
+            StructureParentType::Mainframe => StructureType::Mainframe(structure),
+            StructureParentType::Spawner => StructureType::Spawner(structure),
+        })
+    }
+}
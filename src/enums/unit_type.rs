@@ -1,7 +1,47 @@
-use crate::structs::unit::Unit;
+use crate::structs::{
+    order::OrderType,
+    save_file::{Persist, SaveReader, SaveWriter},
+    unit::{Unit, UnitParentType},
+};
 
 pub enum UnitType {
     Scout(Unit),
     Miner(Unit),
     Collector(Unit),
 }
+
+impl UnitType {
+    // Whether this kind of unit is able to carry out a given order
+    pub fn can_perform(&self, order_type: OrderType) -> bool {
+        match order_type {
+            OrderType::Move
+            | OrderType::Follow
+            | OrderType::ActionMove
+            | OrderType::HoldPosition
+            | OrderType::Patrol => true,
+            OrderType::Attack | OrderType::LazyAttack => matches!(self, UnitType::Scout(_)),
+            OrderType::Mine | OrderType::Build => matches!(self, UnitType::Miner(_)),
+            OrderType::Collect | OrderType::Deposit => matches!(self, UnitType::Collector(_)),
+        }
+    }
+}
+
+impl Persist for UnitType {
+    fn save(&self, writer: &mut SaveWriter) {
+        match self {
+            UnitType::Scout(unit) | UnitType::Miner(unit) | UnitType::Collector(unit) => {
+                unit.save(writer)
+            }
+        }
+    }
+
+    // The unit's parent type tells which variant it belongs to
+    fn load(reader: &mut SaveReader) -> Result<Self, String> {
+        let unit = Unit::load(reader)?;
+        Ok(match unit.parent_type() {
+            UnitParentType::Scout => UnitType::Scout(unit),
+            UnitParentType::Miner => UnitType::Miner(unit),
+            UnitParentType::Collector => UnitType::Collector(unit),
+        })
+    }
+}
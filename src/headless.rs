@@ -0,0 +1,161 @@
+use std::fmt;
+
+use crate::{
+    consts::setup::Scenario,
+    enums::{game_object::GameObject, unit_type::UnitType},
+    structs::{
+        damage::DamageType, ent::Owner, ore_patch::OreType, world::World, world_info::WorldInfo,
+    },
+};
+
+// A summary of the world state at the end of a headless run
+pub struct HeadlessReport {
+    pub ticks: u32,
+    pub game_object_count: usize,
+    pub player_unit_count: usize,
+    pub cpu_unit_count: usize,
+    pub scout_count: usize,
+    pub miner_count: usize,
+    pub collector_count: usize,
+    pub structure_count: usize,
+    pub ore_patch_count: usize,
+    pub ore_count: usize,
+    pub total_unit_hp: f32,
+    pub combat_damage: f32, // Damage dealt by anyone to anything over the whole run, mining aside
+    pub mining_damage: f32,
+    pub kills: u32,
+    pub player_resources: f32,
+    pub player_gathered: f32,
+    pub player_spent: f32,
+    pub player_refunded: f32,
+    pub player_supply_used: u32,
+    pub player_supply_cap: u32,
+    pub state_hash: u64,
+}
+
+impl HeadlessReport {
+    pub fn from_world(ticks: u32, world: &World, world_info: &WorldInfo) -> Self {
+        let mut report = Self {
+            ticks,
+            game_object_count: world.game_objects.len(),
+            player_unit_count: 0,
+            cpu_unit_count: 0,
+            scout_count: 0,
+            miner_count: 0,
+            collector_count: 0,
+            structure_count: 0,
+            ore_patch_count: 0,
+            ore_count: 0,
+            total_unit_hp: 0.0,
+            combat_damage: 0.0,
+            mining_damage: 0.0,
+            kills: 0,
+            player_resources: 0.0,
+            player_gathered: 0.0,
+            player_spent: 0.0,
+            player_refunded: 0.0,
+            player_supply_used: 0,
+            player_supply_cap: 0,
+            state_hash: world.state_hash(world_info),
+        };
+        for game_object in &world.game_objects {
+            match game_object {
+                GameObject::Unit(ent, unit_type) => {
+                    match ent.owner {
+                        Owner::Player => report.player_unit_count += 1,
+                        Owner::Cpu => report.cpu_unit_count += 1,
+                        Owner::Nature => (),
+                    }
+                    match unit_type {
+                        UnitType::Scout(_) => report.scout_count += 1,
+                        UnitType::Miner(_) => report.miner_count += 1,
+                        UnitType::Collector(_) => report.collector_count += 1,
+                    }
+                    report.total_unit_hp += ent.hp;
+                }
+                GameObject::Structure(_, _) => report.structure_count += 1,
+                GameObject::OrePatch(_, _) => report.ore_patch_count += 1,
+                GameObject::Ore(_, _) => report.ore_count += 1,
+            }
+        }
+        if let Some(player_state) = world.player_state(Owner::Player) {
+            report.player_resources = player_state.resources(OreType::Blue);
+            report.player_gathered = player_state.gathered(OreType::Blue);
+            report.player_spent = player_state.spent(OreType::Blue);
+            report.player_refunded = player_state.refunded(OreType::Blue);
+            report.player_supply_used = player_state.supply_used;
+            report.player_supply_cap = player_state.supply_cap;
+        }
+        report
+    }
+}
+
+impl fmt::Display for HeadlessReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "ticks: {}", self.ticks)?;
+        writeln!(f, "game objects: {}", self.game_object_count)?;
+        writeln!(
+            f,
+            "units: {} player, {} cpu ({} scouts, {} miners, {} collectors)",
+            self.player_unit_count,
+            self.cpu_unit_count,
+            self.scout_count,
+            self.miner_count,
+            self.collector_count
+        )?;
+        writeln!(f, "structures: {}", self.structure_count)?;
+        writeln!(
+            f,
+            "ore patches: {}, ore: {}",
+            self.ore_patch_count, self.ore_count
+        )?;
+        writeln!(f, "total unit hp: {:.2}", self.total_unit_hp)?;
+        writeln!(
+            f,
+            "damage: {:.2} combat ({} kills), {:.2} mining",
+            self.combat_damage, self.kills, self.mining_damage
+        )?;
+        writeln!(
+            f,
+            "player ore: {:.2} ({:.2} gathered, {:.2} spent, {:.2} refunded)",
+            self.player_resources, self.player_gathered, self.player_spent, self.player_refunded
+        )?;
+        writeln!(
+            f,
+            "player supply: {}/{}",
+            self.player_supply_used, self.player_supply_cap
+        )?;
+        write!(f, "state hash: {:016x}", self.state_hash)
+    }
+}
+
+// Ticks the world described by a scenario without ever touching SDL video or ttf
+pub fn run(scenario: &Scenario, ticks: u32) -> HeadlessReport {
+    let (mut world, mut world_info) = scenario.setup();
+    run_world(&mut world, &mut world_info, ticks)
+}
+
+// Ticks an already built world for a given amount of ticks
+pub fn run_world(world: &mut World, world_info: &mut WorldInfo, ticks: u32) -> HeadlessReport {
+    let mut combat_damage = 0.0;
+    let mut mining_damage = 0.0;
+    let mut kills = 0;
+    for _ in 0..ticks {
+        world.tick(world_info);
+        for damage_event in &world.damage_events {
+            if damage_event.damage_type == DamageType::Mining {
+                mining_damage += damage_event.amount;
+                continue;
+            }
+            combat_damage += damage_event.amount;
+            if damage_event.killed {
+                kills += 1;
+            }
+        }
+    }
+    let mut report = HeadlessReport::from_world(ticks, world, world_info);
+    report.combat_damage = combat_damage;
+    report.mining_damage = mining_damage;
+    report.kills = kills;
+    report
+}
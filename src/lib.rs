@@ -0,0 +1,5 @@
+pub mod consts;
+pub mod enums;
+pub mod headless;
+pub mod render;
+pub mod structs;
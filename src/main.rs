@@ -4,56 +4,126 @@
 // TODO:
 //  Some important backlog stuff
 //  ??. Change Selection struct bools to a enum State type of deal (check clipy::pedantic)
-//  ??. Limit framerate somehow (try using sdl2_timing)?
 //  ??. Figure out a way to only draw required orders (i.e. a selection of units gets shift moved around; all but the line from unit to first waypoint will be redrawn uselessly)
 //      ==> This MASSIVELY boosts performace, not drawing orders for 1k units eliminates all lag when queuing. this would effectively cut 90% of the orders to draw out
 
 // Some current stuff
 
-//  1. Get creative with structures
-//      0. Add Mainframe Structure Type
-
-//  2. Get creative with enemies & enemy spawners
-//      0. Add enemy spawner, triggered by ore patch damage (initially)
-
-//  3. Get creative with combat
-//      0. Figure out proper combat (attack speed (maybe not? check next list #))
-//      1. Add nice beam animation to current attack (several small boxes or circles travelling from one end of the line to the other)
-
 //  4. Refactor game system
 //      0. Change all pair data types on structs to Vector2D<f32>; Then convert back to point as needed for drawing (might be better then current way of things)
 
-//  5. Test collision feel & benchmark
-//      0. Maybe try a Mutalisk style thing - can overlap freely while moving, but slowly unbunch until completely separated when resting
-//      1. If not, will definitely need to implement pathfinding (could give A* a try)
-
 //  Some less important backlog stuff
-//  ??. Add some logic to allow a unit to move while attacking (would need some sort of anchor target system; maintain target while in range, lose it when out of range)
-//  ??. Add patrol order (R) ?
 //  ??. Fix zoom out jankiness (would like it for the zoom behaviour to be reversed when zooming out...)
 
-mod consts;
-mod enums;
-mod structs;
+use std::env;
+
+use micron::consts::setup::Scenario;
+use micron::consts::values::{
+    BLACK_RGB, DEFAULT_DEBUG_ENT_COUNT, DEFAULT_HEADLESS_TICKS, DEFAULT_SEED,
+    SCREEN_BACKGROUND_COLOR, SCREEN_HEIGHT, SCREEN_WIDTH,
+};
+use micron::enums::ui_object::UIObject;
+use micron::headless;
+
+use micron::structs::game_clock::GameClock;
+use micron::structs::input::Input;
+use micron::structs::replay::{Replay, ReplayPlayer};
+use micron::structs::save_file::{load_game, save_game};
+use micron::structs::ui::UI;
+use micron::structs::ui_element::{UIElement, UIElementID};
+use micron::structs::{camera::Camera, text_label::TextLabel};
+use sdl2::rect::Rect;
+use sdl2_timing::Sdl2Timing;
+
+// Grabs the value that follows a given flag in the command line arguments (if any)
+fn get_arg_value<T: std::str::FromStr>(args: &[String], flag: &str) -> Option<T> {
+    args.iter()
+        .position(|arg| arg == flag)
+        .and_then(|i| args.get(i + 1))
+        .and_then(|value| value.parse::<T>().ok())
+}
 
-use consts::values::{BLACK_RGB, SCREEN_HEIGHT, SCREEN_WIDTH};
-use enums::ui_object::UIObject;
+fn main() -> Result<(), String> {
+    let args: Vec<String> = env::args().collect();
+    let scenario = Scenario::new(
+        get_arg_value(&args, "--seed").unwrap_or(DEFAULT_SEED),
+        get_arg_value(&args, "--ents").unwrap_or(DEFAULT_DEBUG_ENT_COUNT),
+        get_arg_value(&args, "--ai"),
+    );
+
+    let replay_path = get_arg_value::<String>(&args, "--replay");
+
+    // Headless mode: simulate without ever opening a window, then report
+    if args.iter().any(|arg| arg == "--headless") {
+        // Replay verification: re-simulate the whole replay and check it against its recorded state hashes
+        if let Some(path) = replay_path {
+            let mut replay_player = ReplayPlayer::new(Replay::load(&path)?);
+            let (mut world, mut world_info) = replay_player.start();
+            while !replay_player.is_finished(&world) {
+                replay_player.step(&mut world, &mut world_info);
+            }
+            println!(
+                "{}",
+                headless::HeadlessReport::from_world(
+                    world.current_tick as u32,
+                    &world,
+                    &world_info
+                )
+            );
+            return match replay_player.divergence {
+                Some(tick) => Err(format!(">> Replay diverged at tick {tick}")),
+                None => {
+                    println!("replay: ok");
+                    Ok(())
+                }
+            };
+        }
 
-use sdl2::rect::Rect;
-use structs::ui::UI;
-use structs::ui_element::{UIElement, UIElementID};
-use structs::{camera::Camera, text_label::TextLabel};
+        let ticks = get_arg_value(&args, "--ticks").unwrap_or(DEFAULT_HEADLESS_TICKS);
+        // Either start from a save file or from the scenario
+        let (mut world, mut world_info, camera) = match get_arg_value::<String>(&args, "--load") {
+            Some(path) => load_game(&path)?,
+            None => {
+                let (world, world_info) = scenario.setup();
+                (world, world_info, Camera::new())
+            }
+        };
+        println!(
+            "{}",
+            headless::run_world(&mut world, &mut world_info, ticks)
+        );
+        if let Some(path) = get_arg_value::<String>(&args, "--save") {
+            save_game(&path, &world, &world_info, &camera)?;
+        }
+        return Ok(());
+    }
 
-use structs::input::Input;
-use structs::world_info::WorldInfo;
+    // Either watch a replay, or play (possibly recording a replay of the match)
+    let mut replay_player = match replay_path {
+        Some(path) => Some(ReplayPlayer::new(Replay::load(&path)?)),
+        None => None,
+    };
+    let record_path = get_arg_value::<String>(&args, "--record");
+    let mut recording = record_path.as_ref().map(|_| Replay::new(&scenario));
 
-use crate::{consts::*, structs::*};
+    run_windowed(&scenario, replay_player.as_mut(), recording.as_mut())?;
 
-use structs::world::*;
+    if let Some(tick) = replay_player.and_then(|replay_player| replay_player.divergence) {
+        println!(">> Replay diverged at tick {tick}");
+    }
 
-use crate::setup::*;
+    if let (Some(path), Some(replay)) = (record_path, recording) {
+        replay.save(&path)?;
+        println!(">> Saved replay to {path}");
+    }
+    Ok(())
+}
 
-fn main() -> Result<(), String> {
+fn run_windowed(
+    scenario: &Scenario,
+    mut replay_player: Option<&mut ReplayPlayer>,
+    mut recording: Option<&mut Replay>,
+) -> Result<(), String> {
     let sdl_context = sdl2::init()?;
     let ttf_context = sdl2::ttf::init().map_err(|e| e.to_string())?;
     let video_subsystem = sdl_context.video()?;
@@ -64,6 +134,9 @@ fn main() -> Result<(), String> {
         .build()
         .expect(">> Could not load window");
 
+    // Keeps track of frame timing; Presents the canvas and sleeps if vsync is not available
+    let mut timing = Sdl2Timing::new_for(&video_subsystem, &window)?;
+
     let mut canvas = window
         .into_canvas()
         .accelerated()
@@ -79,10 +152,13 @@ fn main() -> Result<(), String> {
         .event_pump()
         .expect(">> Coult not instantiate event_queue");
 
-    let mut world = World::new();
-    let mut world_info = WorldInfo::new();
+    let (mut world, mut world_info) = match replay_player.as_deref_mut() {
+        Some(replay_player) => replay_player.start(),
+        None => scenario.setup(),
+    };
     let mut camera = Camera::new();
     let mut ui = UI::new(&mut canvas);
+    let mut clock = GameClock::new();
 
     ui.add_ui_object(&UIObject::TextLabel(
         UIElement::new(
@@ -93,21 +169,62 @@ fn main() -> Result<(), String> {
         ),
         TextLabel::new(),
     ));
-    spawn_debug_ents(500, &mut world, &mut world_info);
+    ui.add_ui_object(&UIObject::TextLabel(
+        UIElement::new(
+            UIElementID::PlayerResources,
+            "Ore: {ore} Supply: {used}/{cap}".to_owned(),
+            BLACK_RGB,
+            Rect::new(10, 85, 400, 75),
+        ),
+        TextLabel::new(),
+    ));
 
     loop {
         //////////////////////// USER INPUT /////////////////////////
 
-        // Process player input
+        // Process player input (or replay controls when watching a replay)
         // If this method returns false, the window was closed; exit loop
-        if !Input::process_input(&mut event_queue, &mut camera, &mut world) {
+        let keep_running = match replay_player.as_deref_mut() {
+            Some(replay_player) => Input::process_replay_input(
+                &mut event_queue,
+                &mut camera,
+                replay_player,
+                &mut world,
+                &mut world_info,
+            ),
+            // A failed quicksave or quickload gets reported, but the match goes on
+            None => Input::process_input(
+                &mut event_queue,
+                &mut camera,
+                &mut world,
+                &mut world_info,
+                recording.as_deref_mut(),
+            )
+            .unwrap_or_else(|e| {
+                println!("{e}");
+                true
+            }),
+        };
+        if !keep_running {
             break;
         }
 
         //////////////////////// UPDATE GAME STATE /////////////////////////
 
         // Tick World
-        world.tick(&mut world_info);
+        // Runs as many fixed steps as needed to catch up with real time (possibly none)
+        let ticks = clock.advance();
+        match replay_player.as_deref_mut() {
+            Some(replay_player) => replay_player.advance(&mut world, &mut world_info, ticks),
+            None => {
+                for _ in 0..ticks {
+                    world.tick(&mut world_info);
+                    if let Some(replay) = recording.as_deref_mut() {
+                        replay.record_tick(&world, &world_info);
+                    }
+                }
+            }
+        }
 
         // Tick UI
         ui.tick(&world, &world_info);
@@ -115,13 +232,18 @@ fn main() -> Result<(), String> {
         //////////////////////// RENDER GAME STATE /////////////////////////
 
         // Draw World
-        world.draw(&mut canvas, &mut world_info, &mut camera);
+        // Ents get drawn in between their last two ticked positions
+        let alpha = match replay_player.as_deref() {
+            Some(replay_player) => replay_player.render_alpha(clock.alpha()),
+            None => clock.alpha(),
+        };
+        world.draw(&mut canvas, &mut camera, &world_info, alpha);
 
         // Draw UI
         ui.draw(&mut canvas, &font, &camera);
 
         // Refresh screen
-        canvas.present();
+        timing.canvas_present_then_clear(&mut canvas, SCREEN_BACKGROUND_COLOR);
     }
 
     Ok(())
@@ -0,0 +1,109 @@
+use sdl2::{
+    gfx::primitives::DrawRenderer,
+    pixels::Color,
+    rect::{Point, Rect},
+    render::{BlendMode, Canvas},
+    video::Window,
+};
+use vector2d::Vector2D;
+
+use crate::{
+    consts::values::{
+        BLACK_RGB, BLUE_RGB, GREEN_RGB, HEALTH_BAR_HEIGHT, HEALTH_BAR_WIDTH, HEALTH_BAR_Y_FLOAT,
+        RED_RGB, SELECTION_BORDER_SIZE, SHIELD_BAR_HEIGHT,
+    },
+    structs::{ent::Ent, order::Order},
+};
+
+// This method renders an order waypoint to the screen
+pub fn draw_waypoint(order: Order, canvas: &mut Canvas<Window>) {
+    let waypoint_rect: Rect = Rect::from_center(
+        Point::new(
+            order.current_move_target.x as i32,
+            order.current_move_target.y as i32,
+        ),
+        5,
+        5,
+    );
+    canvas.fill_rect(waypoint_rect).ok();
+}
+
+// Renders rect selection border behind selected entity
+pub fn draw_rect_selection_border(canvas: &mut Canvas<Window>, ent_rect: &Rect, color: Color) {
+    canvas.set_blend_mode(BlendMode::Blend);
+    canvas.set_draw_color(color);
+    let selection_border_rect: Rect = Rect::new(
+        (ent_rect.x as f32 - (SELECTION_BORDER_SIZE / 2.0)) as i32,
+        (ent_rect.y as f32 - (SELECTION_BORDER_SIZE / 2.0)) as i32,
+        ent_rect.width() + SELECTION_BORDER_SIZE as u32,
+        ent_rect.height() + SELECTION_BORDER_SIZE as u32,
+    );
+    canvas.fill_rect(selection_border_rect).ok();
+    canvas.set_blend_mode(BlendMode::None);
+}
+
+// Renders circle selection border behind selected entity
+pub fn draw_circle_selection_border(
+    canvas: &mut Canvas<Window>,
+    ent_position: Vector2D<f32>,
+    ent_radius: i16,
+    color: Color,
+) {
+    canvas.set_blend_mode(BlendMode::Blend);
+    canvas.set_draw_color(color);
+    canvas
+        .filled_circle(
+            ent_position.x as i16,
+            ent_position.y as i16,
+            ent_radius + 3 as i16,
+            color,
+        )
+        .ok();
+    canvas.set_blend_mode(BlendMode::None);
+}
+
+// Renders a health bar floating above an entity
+pub fn draw_health_bar(canvas: &mut Canvas<Window>, ent: &Ent, alpha: f32) {
+    // Dead ents get no health bar
+    if ent.hp <= 0.0 {
+        return;
+    }
+    let pos = ent.get_render_rect(alpha).center();
+    let health_ratio = ent.hp / ent.max_hp as f32;
+    let empty_health_bar_rec = Rect::from_center(
+        Point::new(pos.x, pos.y - HEALTH_BAR_Y_FLOAT as i32),
+        HEALTH_BAR_WIDTH as u32,
+        HEALTH_BAR_HEIGHT as u32,
+    );
+    let full_health_bar_rec = Rect::from_center(
+        Point::new(
+            (pos.x as f32 - ((1.0 - health_ratio) * HEALTH_BAR_WIDTH / 2.0)) as i32,
+            pos.y - HEALTH_BAR_Y_FLOAT as i32,
+        ),
+        (health_ratio * HEALTH_BAR_WIDTH) as u32,
+        HEALTH_BAR_HEIGHT as u32,
+    );
+
+    canvas.set_draw_color(RED_RGB);
+    canvas.fill_rect(empty_health_bar_rec).ok();
+    canvas.set_draw_color(GREEN_RGB);
+    canvas.fill_rect(full_health_bar_rec).ok();
+    canvas.set_draw_color(BLACK_RGB);
+    canvas.draw_rect(empty_health_bar_rec).ok();
+
+    // Shields sit right above the health bar, for ents that have any
+    if ent.defense.max_shield <= 0.0 || ent.shield <= 0.0 {
+        return;
+    }
+    let shield_ratio = ent.shield / ent.defense.max_shield;
+    let shield_bar_rec = Rect::from_center(
+        Point::new(
+            (pos.x as f32 - ((1.0 - shield_ratio) * HEALTH_BAR_WIDTH / 2.0)) as i32,
+            pos.y - HEALTH_BAR_Y_FLOAT as i32 - (HEALTH_BAR_HEIGHT + SHIELD_BAR_HEIGHT) as i32 / 2,
+        ),
+        (shield_ratio * HEALTH_BAR_WIDTH) as u32,
+        SHIELD_BAR_HEIGHT as u32,
+    );
+    canvas.set_draw_color(BLUE_RGB);
+    canvas.fill_rect(shield_bar_rec).ok();
+}
@@ -0,0 +1,10 @@
+// Everything that needs a Canvas<Window> to run lives in here,
+// so that the simulation in structs can be ticked without SDL video / ttf
+pub mod helper;
+pub mod ore;
+pub mod ore_patch;
+pub mod projectile;
+pub mod selection;
+pub mod structure;
+pub mod unit;
+pub mod world;
@@ -0,0 +1,50 @@
+use sdl2::{gfx::primitives::DrawRenderer, render::Canvas, video::Window};
+use vector2d::Vector2D;
+
+use crate::{
+    consts::values::{BLACK_RGB, WHITE_RGB},
+    structs::{ent::Ent, ore::Ore},
+};
+
+use super::helper::draw_circle_selection_border;
+
+impl Ore {
+    pub fn draw(&self, ent: &mut Ent, canvas: &mut Canvas<Window>) {
+        // If dead, return early
+        if ent.hp <= 0.0 {
+            return {};
+        }
+        // If selected, draw selection border
+        if ent.selected() {
+            let ent_rect_center = ent.get_rect().center();
+            draw_circle_selection_border(
+                canvas,
+                Vector2D::<f32>::new(ent_rect_center.x as f32, ent_rect_center.y as f32),
+                self.get_radius(ent),
+                WHITE_RGB,
+            );
+        }
+
+        let ent_rect_center = ent.get_rect().center();
+
+        // Draw self (if alive)
+        canvas.set_draw_color(ent.color);
+        canvas
+            .filled_circle(
+                ent_rect_center.x as i16,
+                ent_rect_center.y as i16,
+                self.get_radius(ent),
+                ent.color,
+            )
+            .ok();
+        canvas.set_draw_color(BLACK_RGB);
+        canvas
+            .circle(
+                ent_rect_center.x as i16,
+                ent_rect_center.y as i16,
+                self.get_radius(ent),
+                ent.color,
+            )
+            .ok();
+    }
+}
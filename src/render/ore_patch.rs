@@ -0,0 +1,29 @@
+use sdl2::{render::Canvas, video::Window};
+
+use crate::{
+    consts::values::{BLACK_RGB, YELLOW_RGBA_WEAK},
+    structs::{ent::Ent, ore_patch::OrePatch},
+};
+
+use super::helper::draw_rect_selection_border;
+
+impl OrePatch {
+    pub fn draw(&self, ent: &mut Ent, canvas: &mut Canvas<Window>) {
+        // If dead, return early
+        if ent.hp <= 0.0 {
+            return {};
+        }
+        // If selected, draw selection border
+        if ent.selected() {
+            let border_color = YELLOW_RGBA_WEAK;
+            draw_rect_selection_border(canvas, &ent.get_rect(), border_color);
+        }
+
+        // Draw self (if alive)
+        canvas.set_draw_color(ent.color);
+        let rect = ent.get_rect();
+        canvas.fill_rect(rect).ok();
+        canvas.set_draw_color(BLACK_RGB);
+        canvas.draw_rect(rect).ok();
+    }
+}
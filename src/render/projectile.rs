@@ -0,0 +1,31 @@
+use sdl2::{rect::Point, render::Canvas, video::Window};
+
+use crate::{
+    consts::values::{BLACK_RGB, PROJECTILE_RENDER_LENGTH, WHITE_RGB},
+    structs::{ent::Owner, projectile::Projectile},
+};
+
+impl Projectile {
+    pub fn draw(&self, canvas: &mut Canvas<Window>, alpha: f32) {
+        // Draw a short segment trailing behind it, along the line it got fired on
+        let position = self.get_render_position(alpha);
+        let travelled = position - self.origin;
+        let travelled_distance = travelled.length();
+        if travelled_distance <= 0.0 {
+            return;
+        }
+        let tail = position
+            - travelled / travelled_distance * travelled_distance.min(PROJECTILE_RENDER_LENGTH);
+
+        canvas.set_draw_color(WHITE_RGB);
+        if self.shot.owner == Owner::Cpu {
+            canvas.set_draw_color(BLACK_RGB);
+        }
+        canvas
+            .draw_line(
+                Point::new(tail.x as i32, tail.y as i32),
+                Point::new(position.x as i32, position.y as i32),
+            )
+            .ok();
+    }
+}
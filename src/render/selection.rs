@@ -0,0 +1,47 @@
+use sdl2::{
+    render::{BlendMode, Canvas},
+    video::Window,
+};
+
+use crate::{
+    consts::values::{
+        FORMATION_FACING_COLOR, PLACEMENT_INVALID_COLOR, PLACEMENT_VALID_COLOR, SELECTION_BOX_COLOR,
+    },
+    structs::{selection::Selection, world_info::WorldInfo},
+};
+
+impl Selection {
+    pub fn draw(&self, canvas: &mut Canvas<Window>, world_info: &WorldInfo) {
+        // Draw the footprint of the structure being placed (if any)
+        // Green if it fits there, red if it does not
+        if let Some((_, placement_rect)) = self.placement() {
+            canvas.set_blend_mode(BlendMode::Blend);
+            if world_info.can_place(placement_rect) {
+                canvas.set_draw_color(PLACEMENT_VALID_COLOR);
+            } else {
+                canvas.set_draw_color(PLACEMENT_INVALID_COLOR);
+            }
+            canvas.fill_rect(placement_rect).ok();
+            canvas.set_blend_mode(BlendMode::None);
+        }
+
+        // Draw which way the formation being dragged out (if any) will face
+        if let (Some(formation_origin), Some(_)) = (
+            self.formation_origin,
+            self.formation_facing(self.mouse_position),
+        ) {
+            canvas.set_blend_mode(BlendMode::Blend);
+            canvas.set_draw_color(FORMATION_FACING_COLOR);
+            canvas.draw_line(formation_origin, self.mouse_position).ok();
+            canvas.set_blend_mode(BlendMode::None);
+        }
+
+        if !self.open {
+            return {};
+        };
+        canvas.set_blend_mode(BlendMode::Blend);
+        canvas.set_draw_color(SELECTION_BOX_COLOR);
+        canvas.fill_rect(self.selection_box).ok();
+        canvas.set_blend_mode(BlendMode::None);
+    }
+}
@@ -0,0 +1,117 @@
+use sdl2::{
+    pixels::Color,
+    rect::{Point, Rect},
+    render::{BlendMode, Canvas},
+    video::Window,
+};
+
+use crate::{
+    consts::values::{
+        BLACK_RGB, CONSTRUCTION_SITE_ALPHA, GREY_RGB, PRODUCTION_BAR_HEIGHT, RED_RGBA_WEAK,
+        SELECTION_BORDER_COLOR, SELECTION_FOLLOW_TARGET_BORDER_COLOR,
+    },
+    structs::{
+        ent::{Ent, Owner},
+        order::OrderType,
+        structure::Structure,
+    },
+};
+
+use super::helper::draw_rect_selection_border;
+
+impl Structure {
+    pub fn draw(&self, ent: &mut Ent, canvas: &mut Canvas<Window>) {
+        // If dead, return early
+        if ent.hp <= 0.0 {
+            return;
+        }
+        // If selected, draw selection border
+        if ent.selected() {
+            let border_color = if ent.owner == Owner::Player {
+                SELECTION_BORDER_COLOR
+            } else {
+                RED_RGBA_WEAK
+            };
+            draw_rect_selection_border(canvas, &ent.get_rect(), border_color);
+            self.draw_rally_point(ent, canvas);
+        }
+
+        // Draw self (if alive)
+        // Structures never move, so there is nothing to interpolate
+        // Construction sites are see-through
+        let rect = ent.get_rect();
+        if self.is_under_construction() {
+            canvas.set_blend_mode(BlendMode::Blend);
+            canvas.set_draw_color(Color::RGBA(
+                ent.color.r,
+                ent.color.g,
+                ent.color.b,
+                CONSTRUCTION_SITE_ALPHA,
+            ));
+            canvas.fill_rect(rect).ok();
+            canvas.set_blend_mode(BlendMode::None);
+        } else {
+            canvas.set_draw_color(ent.color);
+            canvas.fill_rect(rect).ok();
+        }
+        canvas.set_draw_color(BLACK_RGB);
+        canvas.draw_rect(rect).ok();
+        // Inner frame, so structures stand out from ore patches
+        canvas
+            .draw_rect(Rect::from_center(
+                rect.center(),
+                rect.width() / 2,
+                rect.height() / 2,
+            ))
+            .ok();
+
+        // Construction or production bar, right below the structure
+        let bar_fraction = if self.is_under_construction() {
+            Some(self.construction_fraction())
+        } else if !self.production_queue().is_empty() {
+            Some(self.production_fraction())
+        } else {
+            None
+        };
+        if let Some(bar_fraction) = bar_fraction {
+            let bar = Rect::new(
+                rect.left(),
+                rect.bottom() + PRODUCTION_BAR_HEIGHT as i32,
+                rect.width(),
+                PRODUCTION_BAR_HEIGHT,
+            );
+            canvas.set_draw_color(GREY_RGB);
+            canvas.fill_rect(bar).ok();
+            canvas.set_draw_color(SELECTION_BORDER_COLOR);
+            canvas
+                .fill_rect(Rect::new(
+                    bar.left(),
+                    bar.top(),
+                    (bar.width() as f32 * bar_fraction) as u32,
+                    bar.height(),
+                ))
+                .ok();
+            canvas.set_draw_color(BLACK_RGB);
+            canvas.draw_rect(bar).ok();
+        }
+    }
+
+    // Draws a line from the structure to its rally point (if any)
+    fn draw_rally_point(&self, ent: &Ent, canvas: &mut Canvas<Window>) {
+        let Some(rally_point) = self.rally_point() else {
+            return;
+        };
+        match rally_point.order_type {
+            OrderType::Follow => canvas.set_draw_color(SELECTION_FOLLOW_TARGET_BORDER_COLOR),
+            _ => canvas.set_draw_color(Color::RGB(0, 150, 0)),
+        }
+        let rally_target = match rally_point.ent_target.ent_rect {
+            Some(ent_rect) => ent_rect.center(),
+            None => Point::new(
+                rally_point.current_move_target.x as i32,
+                rally_point.current_move_target.y as i32,
+            ),
+        };
+        canvas.draw_line(ent.get_rect().center(), rally_target).ok();
+    }
+}
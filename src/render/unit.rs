@@ -0,0 +1,263 @@
+use std::ops::Index;
+
+use sdl2::pixels::Color;
+use sdl2::rect::Point;
+use sdl2::render::Canvas;
+use sdl2::video::Window;
+use vector2d::Vector2D;
+
+use crate::consts::values::{
+    BLACK_RGB, GREY_RGB, ORANGE_RGB, PATROL_ROUTE_COLOR, RED_RGBA_WEAK,
+    SELECTION_ATTACK_TARGET_BORDER_COLOR, SELECTION_BORDER_COLOR,
+    SELECTION_BUILD_TARGET_BORDER_COLOR, SELECTION_COLLECT_TARGET_BORDER_COLOR,
+    SELECTION_DEPOSIT_TARGET_BORDER_COLOR, SELECTION_FOLLOW_TARGET_BORDER_COLOR,
+    SELECTION_MINE_TARGET_BORDER_COLOR,
+};
+use crate::structs::{
+    ent::{Ent, Owner, State},
+    order::OrderType,
+    unit::{Action, Unit, UnitParentType},
+};
+
+use super::helper::{draw_circle_selection_border, draw_rect_selection_border, draw_waypoint};
+
+impl Unit {
+    pub fn draw(&self, ent: &mut Ent, canvas: &mut Canvas<Window>, alpha: f32) {
+        // If dead, return early
+        if ent.hp <= 0.0 {
+            return {};
+        }
+        // If selected, draw selection border
+        if ent.selected() {
+            let border_color = if ent.owner == Owner::Player {
+                SELECTION_BORDER_COLOR
+            } else {
+                RED_RGBA_WEAK
+            };
+            draw_rect_selection_border(canvas, &ent.get_render_rect(alpha), border_color);
+        }
+
+        // Draw self (if alive)
+        canvas.set_draw_color(ent.color);
+        if ent.owner == Owner::Cpu {
+            canvas.set_draw_color(BLACK_RGB);
+        }
+        let rect = ent.get_render_rect(alpha);
+        canvas.fill_rect(rect).ok();
+        canvas.set_draw_color(BLACK_RGB);
+        canvas.draw_rect(rect).ok();
+        if ent.state == State::Stop {
+            canvas.set_draw_color(GREY_RGB);
+            canvas.draw_point(rect.center()).ok();
+        }
+        if ent.state == State::Hold {
+            canvas.set_draw_color(ORANGE_RGB);
+            canvas.draw_point(rect.center()).ok();
+        }
+    }
+
+    pub fn draw_orders(&self, ent: &mut Ent, canvas: &mut Canvas<Window>, alpha: f32) {
+        // Draw order waypoints, if selected
+        if !ent.selected() {
+            // Not selected, return early
+            return;
+        }
+
+        canvas.set_draw_color(Color::RGB(0, 150, 0));
+        for (i, order) in ent.orders.iter().enumerate() {
+            // Draw lines connecting order waypoints
+            // Set colors according to order type
+            match order.order_type {
+                OrderType::Move => canvas.set_draw_color(Color::RGB(0, 150, 0)),
+                OrderType::Attack | OrderType::LazyAttack => {
+                    canvas.set_draw_color(SELECTION_ATTACK_TARGET_BORDER_COLOR)
+                }
+                OrderType::Follow => canvas.set_draw_color(SELECTION_FOLLOW_TARGET_BORDER_COLOR),
+                OrderType::HoldPosition => canvas.set_draw_color(ORANGE_RGB),
+                OrderType::Mine => canvas.set_draw_color(SELECTION_MINE_TARGET_BORDER_COLOR),
+                OrderType::Collect => canvas.set_draw_color(SELECTION_COLLECT_TARGET_BORDER_COLOR),
+                OrderType::Deposit => canvas.set_draw_color(SELECTION_DEPOSIT_TARGET_BORDER_COLOR),
+                OrderType::Build => canvas.set_draw_color(SELECTION_BUILD_TARGET_BORDER_COLOR),
+                OrderType::Patrol => canvas.set_draw_color(PATROL_ROUTE_COLOR),
+                OrderType::ActionMove => match self.parent_type() {
+                    UnitParentType::Miner => {
+                        canvas.set_draw_color(SELECTION_MINE_TARGET_BORDER_COLOR)
+                    }
+                    UnitParentType::Scout => {
+                        canvas.set_draw_color(SELECTION_ATTACK_TARGET_BORDER_COLOR)
+                    }
+                    UnitParentType::Collector => {
+                        canvas.set_draw_color(SELECTION_COLLECT_TARGET_BORDER_COLOR)
+                    }
+                },
+            }
+            if i == 0 {
+                // If this is the next order, draw  a line from unit to waypoint
+                canvas
+                    .draw_line(
+                        ent.get_render_rect(alpha).center(),
+                        Point::new(
+                            order.current_move_target.x as i32,
+                            order.current_move_target.y as i32,
+                        ),
+                    )
+                    .ok();
+            }
+            // Else, draw line from last waypoint to this one
+            else {
+                let previous_order_target = ent.orders.index(i - 1).current_move_target;
+                canvas
+                    .draw_line(
+                        Point::new(
+                            previous_order_target.x as i32,
+                            previous_order_target.y as i32,
+                        ),
+                        Point::new(
+                            order.current_move_target.x as i32,
+                            order.current_move_target.y as i32,
+                        ),
+                    )
+                    .ok();
+            }
+            // Draw waypoint, if needed
+            match order.order_type {
+                // In case of attack order, draw red selection border on attacked ent
+                // (if target is still alive)
+                OrderType::Attack | OrderType::LazyAttack => {
+                    if let Some(attack_target_rect) = &order.ent_target.ent_rect {
+                        draw_rect_selection_border(
+                            canvas,
+                            attack_target_rect,
+                            SELECTION_ATTACK_TARGET_BORDER_COLOR,
+                        )
+                    }
+                }
+                // In case of move or attack move order, draw waypoint
+                OrderType::Move | OrderType::ActionMove => {
+                    draw_waypoint(*order, canvas);
+                }
+                // In case of follow order, draw yellow selection border on followed ent
+                // (if target is still alive)
+                OrderType::Follow => {
+                    if let Some(follow_target_rect) = &order.ent_target.ent_rect {
+                        draw_rect_selection_border(
+                            canvas,
+                            follow_target_rect,
+                            SELECTION_FOLLOW_TARGET_BORDER_COLOR,
+                        )
+                    }
+                }
+                // In case of hold position or patrol, draw waypoint
+                OrderType::HoldPosition | OrderType::Patrol => draw_waypoint(*order, canvas),
+                // In case of mining, draw white selection border on mine target
+                OrderType::Mine => {
+                    if let Some(mine_target_rect) = &order.ent_target.ent_rect {
+                        draw_rect_selection_border(
+                            canvas,
+                            mine_target_rect,
+                            SELECTION_MINE_TARGET_BORDER_COLOR,
+                        )
+                    }
+                }
+                // In case of deposit, draw selection border on the structure getting deposited into
+                OrderType::Deposit => {
+                    if let Some(deposit_target_rect) = &order.ent_target.ent_rect {
+                        draw_rect_selection_border(
+                            canvas,
+                            deposit_target_rect,
+                            SELECTION_DEPOSIT_TARGET_BORDER_COLOR,
+                        )
+                    }
+                }
+                // In case of build, draw selection border on the construction site
+                OrderType::Build => {
+                    if let Some(build_target_rect) = &order.ent_target.ent_rect {
+                        draw_rect_selection_border(
+                            canvas,
+                            build_target_rect,
+                            SELECTION_BUILD_TARGET_BORDER_COLOR,
+                        )
+                    }
+                }
+                OrderType::Collect => {
+                    if let Some(collect_target_rect) = &order.ent_target.ent_rect {
+                        draw_circle_selection_border(
+                            canvas,
+                            Vector2D::<f32>::new(
+                                collect_target_rect.center().x as f32,
+                                collect_target_rect.center().y as f32,
+                            ),
+                            collect_target_rect.width() as i16, // TODO: Get ent_target.radius up and going
+                            SELECTION_COLLECT_TARGET_BORDER_COLOR,
+                        )
+                    }
+                }
+            }
+        }
+        self.draw_patrol_route_loop(ent, canvas);
+    }
+
+    // Close the loop of the patrol route (if any), from its last point back to its first
+    fn draw_patrol_route_loop(&self, ent: &mut Ent, canvas: &mut Canvas<Window>) {
+        let Some(route_start) = ent
+            .orders
+            .iter()
+            .position(|order| order.order_type == OrderType::Patrol)
+        else {
+            return;
+        };
+        let route_len = ent.orders[route_start..]
+            .iter()
+            .take_while(|order| order.order_type == OrderType::Patrol)
+            .count();
+        if route_len < 2 {
+            return;
+        }
+        let first_point = ent.orders.index(route_start).current_move_target;
+        let last_point = ent
+            .orders
+            .index(route_start + route_len - 1)
+            .current_move_target;
+        canvas.set_draw_color(PATROL_ROUTE_COLOR);
+        canvas
+            .draw_line(
+                Point::new(last_point.x as i32, last_point.y as i32),
+                Point::new(first_point.x as i32, first_point.y as i32),
+            )
+            .ok();
+    }
+
+    pub fn draw_attack_lines(&self, ent: &mut Ent, canvas: &mut Canvas<Window>, alpha: f32) {
+        // Draw attack lines (if attacking), or deposit / build beams (if depositing or building)
+        // Weapons that fire projectiles get those drawn instead
+        let fires_projectiles = self
+            .weapon
+            .as_ref()
+            .is_some_and(|weapon| weapon.projectile.is_some());
+        if self.current_action() == Action::Attacking && fires_projectiles {
+            return;
+        }
+        if matches!(
+            self.current_action(),
+            Action::Attacking | Action::Depositing | Action::Building
+        ) {
+            let possible_attack_order = ent.orders.get(0);
+            if let Some(attack_order) = possible_attack_order {
+                if let Some(attack_target_rect) = attack_order.ent_target.ent_rect {
+                    canvas.set_draw_color(ent.color);
+                    if let Some(interaction_line_render_latch_point_delta) =
+                        self.interaction_line_render_latch_point_delta()
+                    {
+                        canvas
+                            .draw_line(
+                                ent.get_render_rect(alpha).center(),
+                                attack_target_rect.center()
+                                    + interaction_line_render_latch_point_delta,
+                            )
+                            .ok();
+                    }
+                }
+            }
+        }
+    }
+}
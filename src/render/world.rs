@@ -0,0 +1,107 @@
+use sdl2::{rect::Rect, render::Canvas, video::Window};
+
+use crate::{
+    consts::values::{MAP_HEIGHT, MAP_PADDING, MAP_WIDTH, SCREEN_BACKGROUND_COLOR},
+    enums::{game_object::GameObject, structure_type::StructureType, unit_type::UnitType},
+    structs::{camera::Camera, world::World, world_info::WorldInfo},
+};
+
+use super::helper::draw_health_bar;
+
+impl World {
+    pub fn draw(
+        &mut self,
+        canvas: &mut Canvas<Window>,
+        camera: &mut Camera,
+        world_info: &WorldInfo,
+        alpha: f32,
+    ) {
+        // Clear screen
+        canvas.set_draw_color(SCREEN_BACKGROUND_COLOR);
+        canvas.set_scale(camera.scale.x, camera.scale.y).ok();
+
+        // Set viewport to cover whole map
+        canvas.set_viewport(Rect::new(
+            0 - MAP_PADDING as i32,
+            0 - MAP_PADDING as i32,
+            MAP_WIDTH + MAP_PADDING * 2,
+            MAP_HEIGHT + MAP_PADDING * 2,
+        ));
+
+        // Clear it
+        canvas.fill_rect(camera.get_scaled_screen_area()).ok();
+
+        // Set viewport back to where the camera is
+        canvas.set_viewport(Rect::new(
+            camera.position.x,
+            camera.position.y,
+            canvas.viewport().width(),
+            canvas.viewport().height(),
+        ));
+
+        // Draw unit orders
+        for game_object in &mut self.game_objects {
+            match game_object {
+                GameObject::Unit(ent, unit) => match unit {
+                    UnitType::Scout(unit) | UnitType::Miner(unit) | UnitType::Collector(unit) => {
+                        unit.draw_orders(ent, canvas, alpha)
+                    }
+                },
+                GameObject::Structure(_ent, _structure) => (),
+                GameObject::OrePatch(_ent, _ore) => (),
+                GameObject::Ore(_ent, _ore) => (),
+            }
+        }
+
+        // Draw game_objects
+        for game_object in &mut self.game_objects {
+            match game_object {
+                GameObject::Unit(ent, unit) => match unit {
+                    UnitType::Scout(unit) | UnitType::Miner(unit) | UnitType::Collector(unit) => {
+                        unit.draw(ent, canvas, alpha)
+                    }
+                },
+                GameObject::OrePatch(ent, ore_patch) => ore_patch.draw(ent, canvas),
+                GameObject::Ore(ent, ore) => ore.draw(ent, canvas),
+                GameObject::Structure(ent, structure) => match structure {
+                    StructureType::Mainframe(structure) | StructureType::Spawner(structure) => {
+                        structure.draw(ent, canvas)
+                    }
+                },
+            }
+        }
+
+        // Draw attack lines
+        for game_object in &mut self.game_objects {
+            match game_object {
+                GameObject::Unit(ent, unit) => match unit {
+                    UnitType::Scout(unit) | UnitType::Miner(unit) | UnitType::Collector(unit) => {
+                        unit.draw_attack_lines(ent, canvas, alpha)
+                    }
+                },
+                GameObject::Structure(_ent, _structure) => (),
+                GameObject::OrePatch(_ent, _ore) => (),
+                GameObject::Ore(_ent, _) => (),
+            }
+        }
+
+        // Draw projectiles
+        for projectile in &self.projectiles {
+            projectile.draw(canvas, alpha);
+        }
+
+        // Draw Health Bars
+        for game_object in &self.game_objects {
+            match game_object {
+                GameObject::Unit(ent, _)
+                | GameObject::Structure(ent, _)
+                | GameObject::OrePatch(ent, _) => draw_health_bar(canvas, ent, alpha),
+                // No health bars for ore
+                GameObject::Ore(_, _) => (),
+            }
+        }
+
+        // Draw selection box, and placement footprint
+        self.selection.draw(canvas, world_info);
+    }
+}
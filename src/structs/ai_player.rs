@@ -0,0 +1,572 @@
+use std::{
+    hash::{Hash, Hasher},
+    str::FromStr,
+};
+
+use rand::Rng;
+use sdl2::rect::Rect;
+use vector2d::Vector2D;
+
+use crate::{
+    consts::{
+        helper::{empty_ent_target, structure_cost, structure_rect_size, unit_cost},
+        values::{
+            AI_ATTACK_ARMY_SIZE_EASY, AI_ATTACK_ARMY_SIZE_HARD, AI_ATTACK_ARMY_SIZE_NORMAL,
+            AI_BLUNDER_CHANCE_EASY, AI_BLUNDER_CHANCE_HARD, AI_BLUNDER_CHANCE_NORMAL,
+            AI_COLLECTORS_PER_MINER, AI_EXPANSION_GAP, AI_EXPANSION_SEARCH_RINGS, AI_GATHER_RADIUS,
+            AI_MINERS_PER_MAINFRAME, AI_RETREAT_HP_RATIO_HARD, AI_RETREAT_HP_RATIO_NORMAL,
+            AI_SUPPLY_HEADROOM, AI_THINK_INTERVAL_TICKS_EASY, AI_THINK_INTERVAL_TICKS_HARD,
+            AI_THINK_INTERVAL_TICKS_NORMAL, UNIT_SUPPLY_COST,
+        },
+    },
+    enums::{
+        command::Command, game_object::GameObject, structure_type::StructureType,
+        unit_type::UnitType,
+    },
+};
+
+use super::{
+    ent::{Ent, EntID, Owner},
+    order::{EntTarget, Order, OrderType},
+    save_file::{persist_enum, Persist, SaveReader, SaveWriter},
+    sim_rng::SimRng,
+    state_hash::StateHasher,
+    structure::{Structure, StructureParentType},
+    unit::UnitParentType,
+    world::World,
+    world_info::WorldInfo,
+};
+
+// How well a computer player plays
+// Harder AIs react faster, slip up less often, and know when to pull back
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum AiDifficulty {
+    Easy,
+    Normal,
+    Hard,
+}
+
+impl AiDifficulty {
+    // How long (in ticks) the AI waits between looks at the world
+    pub const fn think_interval_ticks(&self) -> u64 {
+        match self {
+            AiDifficulty::Easy => AI_THINK_INTERVAL_TICKS_EASY,
+            AiDifficulty::Normal => AI_THINK_INTERVAL_TICKS_NORMAL,
+            AiDifficulty::Hard => AI_THINK_INTERVAL_TICKS_HARD,
+        }
+    }
+
+    // Chance for any single decision to get skipped on a given look
+    pub const fn blunder_chance(&self) -> f32 {
+        match self {
+            AiDifficulty::Easy => AI_BLUNDER_CHANCE_EASY,
+            AiDifficulty::Normal => AI_BLUNDER_CHANCE_NORMAL,
+            AiDifficulty::Hard => AI_BLUNDER_CHANCE_HARD,
+        }
+    }
+
+    // How many army units get gathered up before going on the attack
+    pub const fn attack_army_size(&self) -> usize {
+        match self {
+            AiDifficulty::Easy => AI_ATTACK_ARMY_SIZE_EASY,
+            AiDifficulty::Normal => AI_ATTACK_ARMY_SIZE_NORMAL,
+            AiDifficulty::Hard => AI_ATTACK_ARMY_SIZE_HARD,
+        }
+    }
+
+    // Share of its starting hp an attacking group falls back at; Easy AIs fight to the death
+    pub const fn retreat_hp_ratio(&self) -> Option<f32> {
+        match self {
+            AiDifficulty::Easy => None,
+            AiDifficulty::Normal => Some(AI_RETREAT_HP_RATIO_NORMAL),
+            AiDifficulty::Hard => Some(AI_RETREAT_HP_RATIO_HARD),
+        }
+    }
+}
+
+impl FromStr for AiDifficulty {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name {
+            "easy" => Ok(AiDifficulty::Easy),
+            "normal" => Ok(AiDifficulty::Normal),
+            "hard" => Ok(AiDifficulty::Hard),
+            _ => Err(format!("unknown ai difficulty: {name}")),
+        }
+    }
+}
+
+persist_enum!(AiDifficulty { Easy, Normal, Hard });
+
+// A computer player; Every so often it looks at the world and issues the same commands a human would
+// It only ever goes through World::apply_command, so it plays by the exact same rules
+pub struct AiPlayer {
+    pub owner: Owner,
+    pub difficulty: AiDifficulty,
+    rng: SimRng, // Own rng, seeded off the world's, so thinking never shifts the world's random rolls
+    next_think_tick: u64,
+    attack_group: Vec<EntID>, // Army units currently out on the attack
+    attack_group_max_hp: f32, // How much hp the attack group set out with
+}
+
+// What the AI sees when it looks at the world
+struct AiView<'a> {
+    miners: Vec<&'a Ent>,
+    collectors: Vec<&'a Ent>,
+    scouts: Vec<&'a Ent>,
+    mainframes: Vec<(&'a Ent, &'a Structure)>,
+    construction_sites: usize,
+    enemy_structures: Vec<&'a Ent>,
+    enemy_units: Vec<&'a Ent>,
+    ore_patches: Vec<&'a Ent>,
+    ores: Vec<&'a Ent>,
+}
+
+impl AiPlayer {
+    pub fn new(owner: Owner, difficulty: AiDifficulty, seed: u64) -> Self {
+        Self {
+            owner,
+            difficulty,
+            rng: SimRng::new(seed),
+            next_think_tick: 0,
+            attack_group: Vec::<EntID>::new(),
+            attack_group_max_hp: 0.0,
+        }
+    }
+
+    // Looks at the world (if it is time to) and returns whatever commands it wants applied
+    pub fn think(&mut self, world: &World, world_info: &WorldInfo) -> Vec<Command> {
+        let mut commands: Vec<Command> = Vec::<Command>::new();
+        if world.current_tick < self.next_think_tick {
+            return commands;
+        }
+        self.next_think_tick = world.current_tick + self.difficulty.think_interval_ticks();
+
+        let view = self.look(world, world_info);
+        // Whatever is left of the attack group once the dead are gone
+        self.attack_group
+            .retain(|ent_id| world_info.has_ent_by_id(*ent_id));
+        let Some(base) = view
+            .mainframes
+            .first()
+            .map(|(ent, _)| rect_center(ent.get_rect()))
+        else {
+            // No base left; Throw everything at the enemy
+            if self.attack_group.is_empty() && !self.blunders() {
+                self.attack(&view, &view.scouts, world_info, &mut commands);
+            }
+            return commands;
+        };
+
+        if !self.blunders() {
+            self.assign_miners(&view, &mut commands);
+        }
+        if !self.blunders() {
+            self.assign_collectors(&view, &mut commands);
+        }
+        if !self.blunders() {
+            self.produce(&view, world, &mut commands);
+        }
+        if !self.blunders() {
+            self.expand(&view, world, world_info, base, &mut commands);
+        }
+        if !self.blunders() {
+            self.retreat(world_info, base, &mut commands);
+        }
+        if !self.blunders() {
+            self.command_army(&view, world_info, base, &mut commands);
+        }
+        commands
+    }
+
+    fn blunders(&mut self) -> bool {
+        self.rng.gen::<f32>() < self.difficulty.blunder_chance()
+    }
+
+    fn look<'a>(&self, world: &'a World, world_info: &WorldInfo) -> AiView<'a> {
+        let mut view = AiView {
+            miners: Vec::<&Ent>::new(),
+            collectors: Vec::<&Ent>::new(),
+            scouts: Vec::<&Ent>::new(),
+            mainframes: Vec::<(&Ent, &Structure)>::new(),
+            construction_sites: 0,
+            enemy_structures: Vec::<&Ent>::new(),
+            enemy_units: Vec::<&Ent>::new(),
+            ore_patches: Vec::<&Ent>::new(),
+            ores: Vec::<&Ent>::new(),
+        };
+        for game_object in &world.game_objects {
+            match game_object {
+                GameObject::Unit(ent, _) | GameObject::Structure(ent, _)
+                    if !world_info.has_ent(ent) => {}
+                GameObject::Unit(ent, unit_type) if ent.owner == self.owner => match unit_type {
+                    UnitType::Scout(_) => view.scouts.push(ent),
+                    UnitType::Miner(_) => view.miners.push(ent),
+                    UnitType::Collector(_) => view.collectors.push(ent),
+                },
+                GameObject::Structure(ent, structure_type) if ent.owner == self.owner => {
+                    match structure_type {
+                        StructureType::Mainframe(structure) => {
+                            if structure.is_under_construction() {
+                                view.construction_sites += 1;
+                            } else {
+                                view.mainframes.push((ent, structure));
+                            }
+                        }
+                        StructureType::Spawner(_) => (),
+                    }
+                }
+                GameObject::Unit(ent, _) if ent.owner != Owner::Nature => {
+                    view.enemy_units.push(ent)
+                }
+                GameObject::Structure(ent, _) if ent.owner != Owner::Nature => {
+                    view.enemy_structures.push(ent)
+                }
+                GameObject::Unit(_, _) | GameObject::Structure(_, _) => (),
+                GameObject::OrePatch(ent, _) => {
+                    if world_info.has_ent(ent) {
+                        view.ore_patches.push(ent);
+                    }
+                }
+                GameObject::Ore(ent, _) => {
+                    if world_info.has_ent(ent) {
+                        view.ores.push(ent);
+                    }
+                }
+            }
+        }
+        view
+    }
+
+    // Idle miners go mine the closest ore patch
+    fn assign_miners(&self, view: &AiView, commands: &mut Vec<Command>) {
+        for miner in view.miners.iter().filter(|miner| miner.orders.is_empty()) {
+            if let Some(ore_patch) = closest_ent(rect_center(miner.get_rect()), &view.ore_patches) {
+                commands.push(self.targeted_order(OrderType::Mine, vec![miner.id], ore_patch));
+            }
+        }
+    }
+
+    // Idle collectors go pick up the closest ore lying around
+    fn assign_collectors(&self, view: &AiView, commands: &mut Vec<Command>) {
+        for collector in view
+            .collectors
+            .iter()
+            .filter(|collector| collector.orders.is_empty())
+        {
+            if let Some(ore) = closest_ent(rect_center(collector.get_rect()), &view.ores) {
+                commands.push(self.targeted_order(OrderType::Collect, vec![collector.id], ore));
+            }
+        }
+    }
+
+    // Keeps every idle mainframe busy, as long as there is ore and supply for it
+    // Economy comes first; Whatever is left goes into the army
+    fn produce(&self, view: &AiView, world: &World, commands: &mut Vec<Command>) {
+        let Some(player_state) = world.player_state(self.owner) else {
+            return;
+        };
+        // Queued up units take up supply once they pop out, so count them in right away
+        let mut resources_left = player_state.clone();
+        resources_left.supply_used += view
+            .mainframes
+            .iter()
+            .map(|(_, structure)| structure.production_queue().len() as u32 * UNIT_SUPPLY_COST)
+            .sum::<u32>();
+        let mut miner_count = view.miners.len();
+        let mut collector_count = view.collectors.len();
+        for (ent, _) in view
+            .mainframes
+            .iter()
+            .filter(|(_, structure)| structure.production_queue().is_empty())
+        {
+            let unit_type = if miner_count < view.mainframes.len() * AI_MINERS_PER_MAINFRAME {
+                UnitParentType::Miner
+            } else if (collector_count as f32) < miner_count as f32 * AI_COLLECTORS_PER_MINER {
+                UnitParentType::Collector
+            } else {
+                UnitParentType::Scout
+            };
+            if !resources_left.has_supply_for(UNIT_SUPPLY_COST)
+                || resources_left.spend(&unit_cost(unit_type)).is_err()
+            {
+                return;
+            }
+            match unit_type {
+                UnitParentType::Miner => miner_count += 1,
+                UnitParentType::Collector => collector_count += 1,
+                UnitParentType::Scout => (),
+            }
+            resources_left.supply_used += UNIT_SUPPLY_COST;
+            commands.push(Command::EnqueueProduction {
+                issuer: self.owner,
+                structure: ent.id,
+                unit_type,
+            });
+        }
+    }
+
+    // Puts down another mainframe once supply starts running out
+    fn expand(
+        &self,
+        view: &AiView,
+        world: &World,
+        world_info: &WorldInfo,
+        base: Vector2D<f32>,
+        commands: &mut Vec<Command>,
+    ) {
+        let Some(player_state) = world.player_state(self.owner) else {
+            return;
+        };
+        if view.construction_sites > 0
+            || player_state.has_supply_for(AI_SUPPLY_HEADROOM)
+            || !player_state.can_afford(&structure_cost(StructureParentType::Mainframe))
+        {
+            return;
+        }
+        let Some(builder) = closest_ent(base, &view.miners) else {
+            return;
+        };
+        if let Some(position) = find_expansion_position(base, world_info) {
+            commands.push(Command::PlaceStructure {
+                issuer: self.owner,
+                builder: builder.id,
+                structure_type: StructureParentType::Mainframe,
+                position,
+                queued: false,
+            });
+        }
+    }
+
+    // Pulls a beaten up attack group back home
+    fn retreat(
+        &mut self,
+        world_info: &WorldInfo,
+        base: Vector2D<f32>,
+        commands: &mut Vec<Command>,
+    ) {
+        let Some(retreat_hp_ratio) = self.difficulty.retreat_hp_ratio() else {
+            return;
+        };
+        if self.attack_group.is_empty() {
+            return;
+        }
+        let hp_left: f32 = self
+            .attack_group
+            .iter()
+            .filter_map(|ent_id| world_info.get_ent_hp_by_id(*ent_id))
+            .sum();
+        if hp_left >= self.attack_group_max_hp * retreat_hp_ratio {
+            return;
+        }
+        commands.push(Command::IssueOrder {
+            issuer: self.owner,
+            units: std::mem::take(&mut self.attack_group),
+            order: Order::new(OrderType::Move, base, empty_ent_target()),
+            queued: false,
+        });
+    }
+
+    // Gathers idle army units at home, then sends them out once there are enough of them
+    fn command_army(
+        &mut self,
+        view: &AiView,
+        world_info: &WorldInfo,
+        base: Vector2D<f32>,
+        commands: &mut Vec<Command>,
+    ) {
+        let home_army: Vec<&Ent> = view
+            .scouts
+            .iter()
+            .copied()
+            .filter(|scout| !self.attack_group.contains(&scout.id))
+            .collect();
+
+        // An attack group that is done with its target moves on to the next one
+        let attack_group_idle = view
+            .scouts
+            .iter()
+            .filter(|scout| self.attack_group.contains(&scout.id))
+            .all(|scout| scout.orders.is_empty());
+        if !self.attack_group.is_empty() && attack_group_idle {
+            let attack_group: Vec<&Ent> = view
+                .scouts
+                .iter()
+                .copied()
+                .filter(|scout| self.attack_group.contains(&scout.id))
+                .collect();
+            self.attack(view, &attack_group, world_info, commands);
+        }
+
+        if self.attack_group.is_empty() && home_army.len() >= self.difficulty.attack_army_size() {
+            self.attack(view, &home_army, world_info, commands);
+            return;
+        }
+
+        let stragglers: Vec<EntID> = home_army
+            .iter()
+            .filter(|scout| {
+                scout.orders.is_empty()
+                    && (rect_center(scout.get_rect()) - base).length() > AI_GATHER_RADIUS
+            })
+            .map(|scout| scout.id)
+            .collect();
+        if !stragglers.is_empty() {
+            commands.push(Command::IssueOrder {
+                issuer: self.owner,
+                units: stragglers,
+                order: Order::new(OrderType::ActionMove, base, empty_ent_target()),
+                queued: false,
+            });
+        }
+    }
+
+    // Action moves a group towards an enemy; Structures come first, then whatever units are left
+    // Easy AIs pick any enemy at random, everyone else goes for the closest one
+    fn attack(
+        &mut self,
+        view: &AiView,
+        group: &[&Ent],
+        world_info: &WorldInfo,
+        commands: &mut Vec<Command>,
+    ) {
+        let Some(group_center) = group_center(group) else {
+            return;
+        };
+        let targets = if view.enemy_structures.is_empty() {
+            &view.enemy_units
+        } else {
+            &view.enemy_structures
+        };
+        let target = match self.difficulty {
+            AiDifficulty::Easy if !targets.is_empty() => {
+                Some(targets[self.rng.gen_range(0..targets.len())])
+            }
+            _ => closest_ent(group_center, targets),
+        };
+        let Some(target) = target else {
+            return;
+        };
+        self.attack_group = group.iter().map(|ent| ent.id).collect();
+        self.attack_group_max_hp = self
+            .attack_group
+            .iter()
+            .filter_map(|ent_id| world_info.get_ent_hp_by_id(*ent_id))
+            .sum();
+        commands.push(Command::IssueOrder {
+            issuer: self.owner,
+            units: self.attack_group.clone(),
+            order: Order::new(
+                OrderType::ActionMove,
+                rect_center(target.get_rect()),
+                empty_ent_target(),
+            ),
+            queued: false,
+        });
+    }
+
+    fn targeted_order(&self, order_type: OrderType, units: Vec<EntID>, target: &Ent) -> Command {
+        Command::IssueOrder {
+            issuer: self.owner,
+            units,
+            order: Order::new(
+                order_type,
+                rect_center(target.get_rect()),
+                EntTarget {
+                    ent_id: Some(target.id),
+                    ent_rect: Some(target.get_rect()),
+                    ent_owner: Some(target.owner),
+                    ent_parent_type: Some(target.parent_type()),
+                },
+            ),
+            queued: false,
+        }
+    }
+
+    pub fn hash_state(&self, hasher: &mut StateHasher) {
+        self.owner.hash(hasher);
+        self.difficulty.hash(hasher);
+        hasher.write_u64(self.rng.state());
+        hasher.write_u64(self.next_think_tick);
+        self.attack_group.hash(hasher);
+        hasher.write_f32(self.attack_group_max_hp);
+    }
+}
+
+impl Persist for AiPlayer {
+    fn save(&self, writer: &mut SaveWriter) {
+        writer.write_label("ai_player");
+        self.owner.save(writer);
+        self.difficulty.save(writer);
+        self.rng.save(writer);
+        self.next_think_tick.save(writer);
+        self.attack_group.save(writer);
+        self.attack_group_max_hp.save(writer);
+    }
+
+    fn load(reader: &mut SaveReader) -> Result<Self, String> {
+        reader.expect_label("ai_player")?;
+        Ok(Self {
+            owner: Owner::load(reader)?,
+            difficulty: AiDifficulty::load(reader)?,
+            rng: SimRng::load(reader)?,
+            next_think_tick: u64::load(reader)?,
+            attack_group: Vec::<EntID>::load(reader)?,
+            attack_group_max_hp: f32::load(reader)?,
+        })
+    }
+}
+
+fn rect_center(rect: Rect) -> Vector2D<f32> {
+    Vector2D::<f32>::new(rect.center().x as f32, rect.center().y as f32)
+}
+
+fn group_center(group: &[&Ent]) -> Option<Vector2D<f32>> {
+    if group.is_empty() {
+        return None;
+    }
+    let sum = group
+        .iter()
+        .fold(Vector2D::<f32>::new(0.0, 0.0), |sum, ent| {
+            sum + rect_center(ent.get_rect())
+        });
+    Some(sum / group.len() as f32)
+}
+
+// Ties go to the oldest ent (lowest EntID), since ents are listed in spawn order
+fn closest_ent<'a>(position: Vector2D<f32>, ents: &[&'a Ent]) -> Option<&'a Ent> {
+    let mut closest: Option<(&Ent, f32)> = None;
+    for ent in ents {
+        let distance = (rect_center(ent.get_rect()) - position).length();
+        if closest.is_none_or(|(_, closest_distance)| distance < closest_distance) {
+            closest = Some((ent, distance));
+        }
+    }
+    closest.map(|(ent, _)| ent)
+}
+
+// Looks for room for a new mainframe around the base, circling outwards
+fn find_expansion_position(base: Vector2D<f32>, world_info: &WorldInfo) -> Option<Vector2D<f32>> {
+    let size = structure_rect_size(StructureParentType::Mainframe);
+    let step = size + AI_EXPANSION_GAP;
+    for ring in 1..=AI_EXPANSION_SEARCH_RINGS {
+        for (dx, dy) in [
+            (0, 1),
+            (1, 0),
+            (-1, 0),
+            (0, -1),
+            (1, 1),
+            (-1, 1),
+            (1, -1),
+            (-1, -1),
+        ] {
+            let x = base.x as i32 - size / 2 + dx * ring * step;
+            let y = base.y as i32 - size / 2 + dy * ring * step;
+            if world_info.can_place(Rect::new(x, y, size as u32, size as u32)) {
+                return Some(Vector2D::<f32>::new(x as f32, y as f32));
+            }
+        }
+    }
+    None
+}
@@ -5,10 +5,12 @@ use sdl2::{
 use vector2d::Vector2D;
 
 use crate::consts::values::{
-    CAMERA_ZOOM_INCREMENT, MAP_HEIGHT, MAP_WIDTH, MAX_ZOOM_SCALE, MIN_ZOOM_SCALE, SCREEN_HEIGHT,
-    SCREEN_WIDTH,
+    CAMERA_ZOOM_INCREMENT, MAP_HEIGHT, MAP_WIDTH, MAX_ZOOM_SCALE, MIN_ZOOM_SCALE, MOUSE_RECT_SIZE,
+    SCREEN_HEIGHT, SCREEN_WIDTH,
 };
 
+use super::save_file::{Persist, SaveReader, SaveWriter};
+
 pub struct Camera {
     pub position: Vector2D<i32>,
     pub scale: Vector2D<f32>,
@@ -17,12 +19,18 @@ pub struct Camera {
     anchor_position: Option<Vector2D<i32>>,
 }
 
+impl Default for Camera {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Camera {
     pub fn new() -> Self {
         Self {
             position: Vector2D::<i32>::new(MAP_WIDTH as i32 / -2, MAP_HEIGHT as i32 / -2),
             scale: Vector2D::<f32>::new(1.0, 1.0),
-            mouse_rect: Rect::new(-1, -1, 2, 2),
+            mouse_rect: Rect::new(-1, -1, MOUSE_RECT_SIZE, MOUSE_RECT_SIZE),
             is_anchored: false,
             anchor_position: None,
         }
@@ -54,6 +62,23 @@ impl Camera {
         self.is_anchored = false;
     }
 
+    // Moves the camera so that a spot on the map ends up in the middle of the screen
+    pub fn center_on(&mut self, position: Vector2D<f32>) {
+        self.position.x = -(position.x - SCREEN_WIDTH as f32 / self.scale.x / 2.0) as i32;
+        self.position.y = -(position.y - SCREEN_HEIGHT as f32 / self.scale.y / 2.0) as i32;
+        self.clamp_camera_to_map_bounds();
+    }
+
+    // The part of the map that is currently on screen
+    pub fn get_view_area(&self) -> Rect {
+        Rect::new(
+            -self.position.x,
+            -self.position.y,
+            (SCREEN_WIDTH as f32 / self.scale.x) as u32,
+            (SCREEN_HEIGHT as f32 / self.scale.y) as u32,
+        )
+    }
+
     pub fn get_scaled_screen_area(&self) -> Rect {
         Rect::new(
             0,
@@ -193,3 +218,19 @@ impl Camera {
         self.clamp_camera_to_map_bounds();
     }
 }
+
+// Only the view gets persisted; Mouse and drag state start fresh
+impl Persist for Camera {
+    fn save(&self, writer: &mut SaveWriter) {
+        self.position.save(writer);
+        self.scale.save(writer);
+    }
+
+    fn load(reader: &mut SaveReader) -> Result<Self, String> {
+        let mut camera = Camera::new();
+        camera.position = Vector2D::<i32>::load(reader)?;
+        camera.scale = Vector2D::<f32>::load(reader)?;
+        camera.clamp_camera_to_map_bounds();
+        Ok(camera)
+    }
+}
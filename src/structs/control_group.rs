@@ -0,0 +1,94 @@
+use std::collections::BTreeSet;
+
+use vector2d::Vector2D;
+
+use crate::consts::values::{CONTROL_GROUP_COUNT, CONTROL_GROUP_DOUBLE_TAP_MS};
+
+use super::{ent::EntID, world_info::WorldInfo};
+
+// Numbered groups of ents the player can store away and recall with a single key
+// These only live on the player's side, so they never get hashed, saved or recorded, and the simulation never touches them;
+// Recalling a group goes through a regular Command::Select
+pub struct ControlGroups {
+    groups: [BTreeSet<EntID>; CONTROL_GROUP_COUNT],
+    last_recall: Option<(usize, u32)>, // Which group got recalled last, and when (SDL timestamp, in milliseconds)
+}
+
+impl Default for ControlGroups {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ControlGroups {
+    pub fn new() -> Self {
+        Self {
+            groups: std::array::from_fn(|_| BTreeSet::<EntID>::new()),
+            last_recall: None,
+        }
+    }
+
+    // Replaces whatever was in the group
+    pub fn assign(&mut self, group: usize, ents: &[EntID]) {
+        if let Some(control_group) = self.groups.get_mut(group) {
+            *control_group = ents.iter().copied().collect();
+        }
+    }
+
+    pub fn add(&mut self, group: usize, ents: &[EntID]) {
+        if let Some(control_group) = self.groups.get_mut(group) {
+            control_group.extend(ents.iter().copied());
+        }
+    }
+
+    pub fn get(&self, group: usize) -> Vec<EntID> {
+        self.groups
+            .get(group)
+            .map_or_else(Vec::<EntID>::new, |control_group| {
+                control_group.iter().copied().collect()
+            })
+    }
+
+    // Keeps track of recalls; Returns whether this one followed right after a recall of the same group
+    pub fn recall(&mut self, group: usize, timestamp: u32) -> bool {
+        let double_tap = self
+            .last_recall
+            .is_some_and(|(last_group, last_timestamp)| {
+                last_group == group
+                    && timestamp.wrapping_sub(last_timestamp) <= CONTROL_GROUP_DOUBLE_TAP_MS
+            });
+        // A third tap starts over, rather than counting as another double tap
+        self.last_recall = if double_tap {
+            None
+        } else {
+            Some((group, timestamp))
+        };
+        double_tap
+    }
+
+    // Average position of everything in the group, for the camera to jump to
+    pub fn center(&self, group: usize, world_info: &WorldInfo) -> Option<Vector2D<f32>> {
+        let positions: Vec<Vector2D<f32>> = self
+            .groups
+            .get(group)?
+            .iter()
+            .filter_map(|ent_id| world_info.get_ent_rect_center_poisition_by_id(*ent_id))
+            .collect();
+        if positions.is_empty() {
+            return None;
+        }
+        let sum = positions
+            .iter()
+            .fold(Vector2D::<f32>::new(0.0, 0.0), |sum, position| {
+                sum + *position
+            });
+        Some(sum / positions.len() as f32)
+    }
+
+    // Drops ents that are gone from the world
+    pub fn prune(&mut self, world_info: &WorldInfo) {
+        for control_group in &mut self.groups {
+            control_group.retain(|ent_id| world_info.has_ent_by_id(*ent_id));
+        }
+    }
+}
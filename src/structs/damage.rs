@@ -0,0 +1,140 @@
+use std::hash::{Hash, Hasher};
+
+use crate::consts::values::{SHIELD_REGEN_DELAY_TICKS, SHIELD_REGEN_RATE};
+
+use super::{
+    ent::EntID,
+    save_file::{persist_enum, Persist, SaveReader, SaveWriter},
+    state_hash::StateHasher,
+};
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash)]
+pub enum DamageType {
+    Beam,    // Instant hits
+    Kinetic, // Projectiles
+    Mining,  // Breaking down ore patches and collecting ore; Never hurts anything else
+}
+
+// How much of each damage type gets shrugged off, from 0.0 (none of it) to 1.0 (all of it)
+#[derive(Copy, Clone)]
+pub struct Resistances {
+    pub beam: f32,
+    pub kinetic: f32,
+    pub mining: f32,
+}
+
+impl Resistances {
+    pub const fn against(&self, damage_type: DamageType) -> f32 {
+        match damage_type {
+            DamageType::Beam => self.beam,
+            DamageType::Kinetic => self.kinetic,
+            DamageType::Mining => self.mining,
+        }
+    }
+}
+
+// Everything that stands between an ent and losing hp
+// Damage gets resisted first, then soaked up by the shield, then whatever is left gets reduced by armor
+#[derive(Copy, Clone)]
+pub struct Defense {
+    pub armor: f32, // Flat damage reduction per hit, for damage that made it past the shield
+    pub resistances: Resistances,
+    pub max_shield: f32,
+    pub shield_regen: f32,             // How much shield comes back per second
+    pub shield_regen_delay_ticks: u32, // How long (in ticks) the shield waits after taking damage before coming back
+}
+
+impl Defense {
+    // For units and structures; They can be fought, but not mined
+    pub const fn combat(armor: f32, max_shield: f32) -> Self {
+        Self {
+            armor,
+            resistances: Resistances {
+                beam: 0.0,
+                kinetic: 0.0,
+                mining: 1.0,
+            },
+            max_shield,
+            shield_regen: SHIELD_REGEN_RATE,
+            shield_regen_delay_ticks: SHIELD_REGEN_DELAY_TICKS,
+        }
+    }
+
+    // For ore patches and ore; They can be mined, but not fought
+    pub const fn ore() -> Self {
+        Self {
+            armor: 0.0,
+            resistances: Resistances {
+                beam: 1.0,
+                kinetic: 1.0,
+                mining: 0.0,
+            },
+            max_shield: 0.0,
+            shield_regen: 0.0,
+            shield_regen_delay_ticks: 0,
+        }
+    }
+
+    pub fn hash_state(&self, hasher: &mut StateHasher) {
+        hasher.write_f32(self.armor);
+        hasher.write_f32(self.resistances.beam);
+        hasher.write_f32(self.resistances.kinetic);
+        hasher.write_f32(self.resistances.mining);
+        hasher.write_f32(self.max_shield);
+        hasher.write_f32(self.shield_regen);
+        hasher.write_u32(self.shield_regen_delay_ticks);
+    }
+}
+
+// A record of a single hit landing, for anything that wants to know who hurt whom
+// (UI, audio, kill credit, statistics, ...)
+#[derive(Copy, Clone)]
+pub struct DamageEvent {
+    pub source: EntID,
+    pub target: EntID,
+    pub amount: f32, // Damage actually dealt, shield and hp together, after resistances and armor
+    pub damage_type: DamageType,
+    pub killed: bool, // Whether this hit finished the target off
+}
+
+persist_enum!(DamageType {
+    Beam,
+    Kinetic,
+    Mining
+});
+
+impl Persist for Resistances {
+    fn save(&self, writer: &mut SaveWriter) {
+        self.beam.save(writer);
+        self.kinetic.save(writer);
+        self.mining.save(writer);
+    }
+
+    fn load(reader: &mut SaveReader) -> Result<Self, String> {
+        Ok(Self {
+            beam: f32::load(reader)?,
+            kinetic: f32::load(reader)?,
+            mining: f32::load(reader)?,
+        })
+    }
+}
+
+impl Persist for Defense {
+    fn save(&self, writer: &mut SaveWriter) {
+        self.armor.save(writer);
+        self.resistances.save(writer);
+        self.max_shield.save(writer);
+        self.shield_regen.save(writer);
+        self.shield_regen_delay_ticks.save(writer);
+    }
+
+    fn load(reader: &mut SaveReader) -> Result<Self, String> {
+        Ok(Self {
+            armor: f32::load(reader)?,
+            resistances: Resistances::load(reader)?,
+            max_shield: f32::load(reader)?,
+            shield_regen: f32::load(reader)?,
+            shield_regen_delay_ticks: u32::load(reader)?,
+        })
+    }
+}
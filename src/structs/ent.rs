@@ -1,12 +1,17 @@
+use std::hash::{Hash, Hasher};
+
 use sdl2::{
     pixels::Color,
     rect::{Point, Rect},
 };
 use vector2d::Vector2D;
 
-use crate::consts::helper::CURRENT_ENT_ID;
-
-use super::order::Order;
+use super::{
+    damage::Defense,
+    order::Order,
+    save_file::{persist_enum, Persist, SaveReader, SaveWriter},
+    state_hash::StateHasher,
+};
 
 #[derive(Copy, Clone, PartialEq, Eq, Hash)]
 pub enum EntParentType {
@@ -16,7 +21,14 @@ pub enum EntParentType {
     Structure,
 }
 
-#[derive(Copy, Clone, PartialEq, Eq, Hash)]
+impl EntParentType {
+    // Static obstacles never move, so units path around them instead of bumping into them
+    pub const fn is_static_obstacle(&self) -> bool {
+        matches!(self, EntParentType::OrePatch | EntParentType::Structure)
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum Owner {
     Nature,
     Player,
@@ -32,15 +44,45 @@ pub enum State {
           // -> TODO: maybe make it switch targets to closes target? will have to see
 }
 
-#[derive(Copy, Clone, PartialEq, Eq, Hash)]
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct EntID(pub u64);
 
+// Hands out unique EntIDs; Owned by the World so that IDs are reproducible between runs
+#[derive(Copy, Clone, PartialEq, Eq, Hash)]
+pub struct EntIDAllocator {
+    last_id: EntID,
+}
+
+impl Default for EntIDAllocator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EntIDAllocator {
+    pub const fn new() -> Self {
+        Self { last_id: EntID(0) }
+    }
+
+    pub fn next_id(&mut self) -> EntID {
+        self.last_id.0 += 1;
+        self.last_id
+    }
+
+    pub const fn last_id(&self) -> EntID {
+        self.last_id
+    }
+}
+
 pub struct Ent {
     pub id: EntID,
     pub position: Vector2D<f32>,
+    pub previous_position: Vector2D<f32>, // Position at the start of the last tick; Used for render interpolation
     pub rect_size: Point,
     pub max_hp: u32,
     pub hp: f32,
+    pub defense: Defense,
+    pub shield: f32, // Mirrors the shield kept in world_info, same as hp
     pub color: Color,
     pub owner: Owner,
     pub state: State,
@@ -51,6 +93,7 @@ pub struct Ent {
 
 impl Ent {
     pub fn new(
+        id: EntID,
         parent_type: EntParentType,
         owner: Owner,
         max_hp: u32,
@@ -58,15 +101,18 @@ impl Ent {
         rect_size: Point,
         color: Color,
     ) -> Self {
-        unsafe {
-            CURRENT_ENT_ID.0 += 1;
-        }
         Self {
-            id: unsafe { CURRENT_ENT_ID },
+            id,
             position,
+            previous_position: position,
             rect_size,
             max_hp,
             hp: max_hp as f32,
+            defense: match parent_type {
+                EntParentType::Unit | EntParentType::Structure => Defense::combat(0.0, 0.0),
+                EntParentType::OrePatch | EntParentType::Ore => Defense::ore(),
+            },
+            shield: 0.0,
             color,
             owner,
             state: State::Alert,
@@ -76,6 +122,12 @@ impl Ent {
         }
     }
 
+    // Swaps in a new defense, starting out with a full shield
+    pub fn set_defense(&mut self, defense: Defense) {
+        self.defense = defense;
+        self.shield = defense.max_shield;
+    }
+
     pub fn get_rect(&self) -> Rect {
         Rect::new(
             self.position.x as i32,
@@ -85,6 +137,19 @@ impl Ent {
         )
     }
 
+    // Returns the rect this ent should be rendered at, interpolating between
+    // its previous and current position by alpha (0.0 to 1.0)
+    pub fn get_render_rect(&self, alpha: f32) -> Rect {
+        let render_position =
+            self.previous_position + (self.position - self.previous_position) * alpha;
+        Rect::new(
+            render_position.x as i32,
+            render_position.y as i32,
+            self.rect_size.x as u32,
+            self.rect_size.y as u32,
+        )
+    }
+
     pub fn parent_type(&self) -> EntParentType {
         self.parent_type
     }
@@ -117,4 +182,107 @@ impl Ent {
         new_orders.append(&mut self.orders);
         self.orders = new_orders;
     }
+
+    // Feeds all simulation relevant data of this ent into a state hasher
+    // Note: selection is player input, not simulation state, so it is left out
+    pub fn hash_state(&self, hasher: &mut StateHasher) {
+        self.id.hash(hasher);
+        self.parent_type.hash(hasher);
+        self.owner.hash(hasher);
+        self.state.hash(hasher);
+        hasher.write_vector(self.position);
+        hasher.write_point(self.rect_size);
+        hasher.write_u32(self.max_hp);
+        hasher.write_f32(self.hp);
+        self.defense.hash_state(hasher);
+        hasher.write_f32(self.shield);
+        hasher.write_usize(self.orders.len());
+        for order in &self.orders {
+            order.hash_state(hasher);
+        }
+    }
+}
+
+persist_enum!(EntParentType {
+    Unit,
+    OrePatch,
+    Ore,
+    Structure
+});
+persist_enum!(Owner {
+    Nature,
+    Player,
+    Cpu
+});
+persist_enum!(State {
+    Alert,
+    Busy,
+    Stop,
+    Hold
+});
+
+impl Persist for EntID {
+    fn save(&self, writer: &mut SaveWriter) {
+        self.0.save(writer);
+    }
+
+    fn load(reader: &mut SaveReader) -> Result<Self, String> {
+        Ok(EntID(u64::load(reader)?))
+    }
+}
+
+impl Persist for EntIDAllocator {
+    fn save(&self, writer: &mut SaveWriter) {
+        self.last_id.save(writer);
+    }
+
+    fn load(reader: &mut SaveReader) -> Result<Self, String> {
+        Ok(Self {
+            last_id: EntID::load(reader)?,
+        })
+    }
+}
+
+impl Persist for Ent {
+    fn save(&self, writer: &mut SaveWriter) {
+        writer.write_label("ent");
+        self.id.save(writer);
+        self.parent_type.save(writer);
+        self.owner.save(writer);
+        self.state.save(writer);
+        self.position.save(writer);
+        self.rect_size.save(writer);
+        self.max_hp.save(writer);
+        self.hp.save(writer);
+        self.defense.save(writer);
+        self.shield.save(writer);
+        self.color.save(writer);
+        self.selected.save(writer);
+        self.orders.save(writer);
+    }
+
+    fn load(reader: &mut SaveReader) -> Result<Self, String> {
+        reader.expect_label("ent")?;
+        let id = EntID::load(reader)?;
+        let parent_type = EntParentType::load(reader)?;
+        let owner = Owner::load(reader)?;
+        let state = State::load(reader)?;
+        let position = Vector2D::<f32>::load(reader)?;
+        Ok(Self {
+            id,
+            position,
+            previous_position: position,
+            rect_size: Point::load(reader)?,
+            max_hp: u32::load(reader)?,
+            hp: f32::load(reader)?,
+            defense: Defense::load(reader)?,
+            shield: f32::load(reader)?,
+            color: Color::load(reader)?,
+            owner,
+            state,
+            selected: bool::load(reader)?,
+            orders: Vec::<Order>::load(reader)?,
+            parent_type,
+        })
+    }
 }
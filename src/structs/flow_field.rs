@@ -0,0 +1,145 @@
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BTreeSet, BinaryHeap};
+
+use vector2d::Vector2D;
+
+use super::nav_grid::NavGrid;
+
+// Every free cell's cheapest way towards a single goal cell
+// Computed once per destination, then shared by every unit headed there
+pub struct FlowField {
+    integration: Vec<u32>, // Cost to reach the goal from each cell; u32::MAX if unreachable
+    directions: Vec<Option<(i32, i32)>>, // Which neighbouring cell to step into from each cell
+}
+
+impl FlowField {
+    // Dijkstra flood from the goal (integration field), then every cell points at its cheapest neighbour (direction field)
+    pub fn new(nav_grid: &NavGrid, goal: (i32, i32)) -> Self {
+        let cell_count = nav_grid.cell_count();
+        let mut integration: Vec<u32> = vec![u32::MAX; cell_count];
+        // Ties get broken by cell index, so the same flood always builds the same field
+        let mut open: BinaryHeap<Reverse<(u32, usize)>> = BinaryHeap::new();
+
+        let goal_index = nav_grid.index(goal);
+        integration[goal_index] = 0;
+        open.push(Reverse((0, goal_index)));
+
+        while let Some(Reverse((cost, current_index))) = open.pop() {
+            if cost > integration[current_index] {
+                continue;
+            }
+            for (neighbour, step_cost) in nav_grid.neighbours(nav_grid.cell_at(current_index)) {
+                let neighbour_index = nav_grid.index(neighbour);
+                let new_cost = cost + step_cost;
+                if new_cost < integration[neighbour_index] {
+                    integration[neighbour_index] = new_cost;
+                    open.push(Reverse((new_cost, neighbour_index)));
+                }
+            }
+        }
+
+        let mut directions: Vec<Option<(i32, i32)>> = vec![None; cell_count];
+        for (index, direction) in directions.iter_mut().enumerate() {
+            if index == goal_index || integration[index] == u32::MAX {
+                continue;
+            }
+            let cell = nav_grid.cell_at(index);
+            let mut best_cost = integration[index];
+            for (neighbour, _) in nav_grid.neighbours(cell) {
+                let neighbour_cost = integration[nav_grid.index(neighbour)];
+                if neighbour_cost < best_cost {
+                    best_cost = neighbour_cost;
+                    *direction = Some((neighbour.0 - cell.0, neighbour.1 - cell.1));
+                }
+            }
+        }
+
+        Self {
+            integration,
+            directions,
+        }
+    }
+
+    pub fn direction_at(&self, nav_grid: &NavGrid, cell: (i32, i32)) -> Option<(i32, i32)> {
+        self.directions[nav_grid.index(cell)]
+    }
+
+    pub fn can_reach(&self, nav_grid: &NavGrid, cell: (i32, i32)) -> bool {
+        self.integration[nav_grid.index(cell)] != u32::MAX
+    }
+}
+
+// Flow fields currently in use, by goal cell
+// Fields only depend on the nav grid and their goal, so they are not part of the simulation state
+pub struct FlowFieldCache {
+    fields: BTreeMap<(i32, i32), FlowField>,
+}
+
+impl Default for FlowFieldCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FlowFieldCache {
+    pub fn new() -> Self {
+        Self {
+            fields: BTreeMap::new(),
+        }
+    }
+
+    // Must be called whenever the nav grid changes; Every field is out of date at that point
+    pub fn clear(&mut self) {
+        self.fields.clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.fields.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty()
+    }
+
+    // The cell a flow field towards some destination leads to (the closest free one, if the destination is blocked)
+    pub fn goal_cell(nav_grid: &NavGrid, destination: Vector2D<f32>) -> Option<(i32, i32)> {
+        nav_grid.nearest_free_cell(nav_grid.cell_of(destination))
+    }
+
+    // Point to steer towards from some position to reach a destination
+    // Straight to it when nothing is in the way, else into the next cell of the destination's flow field
+    pub fn steering_target(
+        &mut self,
+        nav_grid: &NavGrid,
+        position: Vector2D<f32>,
+        destination: Vector2D<f32>,
+    ) -> Vector2D<f32> {
+        let Some(goal) = Self::goal_cell(nav_grid, destination) else {
+            return destination;
+        };
+        if nav_grid.has_line_of_sight(position, nav_grid.cell_center(goal)) {
+            return destination;
+        }
+        let flow_field = self
+            .fields
+            .entry(goal)
+            .or_insert_with(|| FlowField::new(nav_grid, goal));
+
+        // A unit pushed into a blocked cell follows the field from the closest free cell
+        let Some(cell) = nav_grid.nearest_free_cell(nav_grid.cell_of(position)) else {
+            return destination;
+        };
+        if !flow_field.can_reach(nav_grid, cell) {
+            return destination;
+        }
+        match flow_field.direction_at(nav_grid, cell) {
+            Some((dx, dy)) => nav_grid.cell_center((cell.0 + dx, cell.1 + dy)),
+            None => destination,
+        }
+    }
+
+    // Drops every field no order heads for anymore
+    pub fn retain_goals(&mut self, goals: &BTreeSet<(i32, i32)>) {
+        self.fields.retain(|goal, _| goals.contains(goal));
+    }
+}
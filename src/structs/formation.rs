@@ -0,0 +1,96 @@
+use std::{cmp::Ordering, collections::BTreeMap};
+
+use vector2d::Vector2D;
+
+use crate::consts::values::{
+    FORMATION_MAX_SPREAD, FORMATION_ROW_LENGTH, FORMATION_SPACING, MAP_HEIGHT, MAP_WIDTH,
+};
+
+use super::ent::EntID;
+
+// Hands every member of a group (along with where it sets off from) its own spot around a destination
+// Without a facing, a group that sticks together keeps its shape; A spread out one lines up facing the way it is headed
+// With a facing, the group lines up in rows across it, front row on the destination
+pub fn formation_spots(
+    members: &[(EntID, Vector2D<f32>)],
+    destination: Vector2D<f32>,
+    facing: Option<Vector2D<f32>>,
+) -> BTreeMap<EntID, Vector2D<f32>> {
+    let mut spots = BTreeMap::<EntID, Vector2D<f32>>::new();
+    if members.is_empty() {
+        return spots;
+    }
+    let group_center = members
+        .iter()
+        .fold(Vector2D::<f32>::new(0.0, 0.0), |sum, (_, position)| {
+            sum + *position
+        })
+        / members.len() as f32;
+
+    let facing = match facing {
+        Some(facing) => facing,
+        None => {
+            let spread = members
+                .iter()
+                .map(|(_, position)| (*position - group_center).length())
+                .fold(0.0, f32::max);
+            if spread <= FORMATION_MAX_SPREAD {
+                for (ent_id, position) in members {
+                    spots.insert(
+                        *ent_id,
+                        clamp_to_map(destination + (*position - group_center)),
+                    );
+                }
+                return spots;
+            }
+            destination - group_center
+        }
+    };
+    // Face up when there is no telling which way to face
+    let forward = if facing.length() > 0.0 {
+        facing.normalise()
+    } else {
+        Vector2D::<f32>::new(0.0, -1.0)
+    };
+    let across = Vector2D::<f32>::new(-forward.y, forward.x);
+
+    // Whoever is furthest ahead takes the front row, and everyone keeps their side within their row,
+    // so nobody has to cross paths; Ties go to the oldest unit
+    let mut members = members.to_vec();
+    members.sort_by(|(a_id, a_position), (b_id, b_position)| {
+        let a_depth = Vector2D::<f32>::dot(*a_position - group_center, forward);
+        let b_depth = Vector2D::<f32>::dot(*b_position - group_center, forward);
+        b_depth
+            .partial_cmp(&a_depth)
+            .unwrap_or(Ordering::Equal)
+            .then(a_id.cmp(b_id))
+    });
+    for (row_index, row) in members.chunks_mut(FORMATION_ROW_LENGTH).enumerate() {
+        row.sort_by(|(a_id, a_position), (b_id, b_position)| {
+            let a_side = Vector2D::<f32>::dot(*a_position - group_center, across);
+            let b_side = Vector2D::<f32>::dot(*b_position - group_center, across);
+            a_side
+                .partial_cmp(&b_side)
+                .unwrap_or(Ordering::Equal)
+                .then(a_id.cmp(b_id))
+        });
+        // Rows get centered on the line through the destination, even a shorter last one
+        let row_width = (row.len() - 1) as f32 * FORMATION_SPACING;
+        for (column_index, (ent_id, _)) in row.iter().enumerate() {
+            let side_offset = column_index as f32 * FORMATION_SPACING - row_width / 2.0;
+            let depth_offset = row_index as f32 * FORMATION_SPACING;
+            spots.insert(
+                *ent_id,
+                clamp_to_map(destination + across * side_offset - forward * depth_offset),
+            );
+        }
+    }
+    spots
+}
+
+fn clamp_to_map(spot: Vector2D<f32>) -> Vector2D<f32> {
+    Vector2D::<f32>::new(
+        spot.x.clamp(0.0, MAP_WIDTH as f32),
+        spot.y.clamp(0.0, MAP_HEIGHT as f32),
+    )
+}
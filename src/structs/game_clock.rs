@@ -0,0 +1,60 @@
+use std::time::Instant;
+
+use crate::consts::values::{MAX_FRAME_TIME, MAX_TICKS_PER_FRAME, TIME_STEP};
+
+// Accumulates real elapsed time and converts it into a whole number of fixed TIME_STEP ticks
+// This keeps the simulation speed independent of the frame rate (vsync, load, etc)
+pub struct GameClock {
+    last_frame: Instant,
+    accumulator: f32,
+}
+
+impl Default for GameClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GameClock {
+    pub fn new() -> Self {
+        Self {
+            last_frame: Instant::now(),
+            accumulator: 0.0,
+        }
+    }
+
+    // Should be called once per frame
+    // Returns how many fixed ticks the world needs to run to catch up with real time
+    pub fn advance(&mut self) -> u32 {
+        let now = Instant::now();
+        // Clamp the frame time, so a long hitch (window drag, breakpoint) does not get simulated all at once
+        let frame_time = now
+            .duration_since(self.last_frame)
+            .as_secs_f32()
+            .min(MAX_FRAME_TIME);
+        self.last_frame = now;
+        self.accumulate(frame_time)
+    }
+
+    // Adds some elapsed time and consumes as many fixed ticks as possible out of it
+    pub fn accumulate(&mut self, elapsed: f32) -> u32 {
+        self.accumulator += elapsed;
+        let mut ticks = 0;
+        while self.accumulator >= TIME_STEP {
+            if ticks == MAX_TICKS_PER_FRAME {
+                // Too far behind; Drop the backlog instead of spiraling into ever longer frames
+                self.accumulator %= TIME_STEP;
+                break;
+            }
+            self.accumulator -= TIME_STEP;
+            ticks += 1;
+        }
+        ticks
+    }
+
+    // How far (0.0 to 1.0) we are between the last tick and the next one
+    // Used to interpolate ent positions while rendering
+    pub fn alpha(&self) -> f32 {
+        self.accumulator / TIME_STEP
+    }
+}
@@ -1,20 +1,31 @@
-use sdl2::{event::Event, keyboard::Keycode, mouse::MouseButton, rect::Point, EventPump};
+use sdl2::{
+    event::Event,
+    keyboard::{Keycode, Mod},
+    mouse::MouseButton,
+    rect::{Point, Rect},
+    EventPump,
+};
 use vector2d::Vector2D;
 
 use crate::{
     consts::{
-        debug_flags::DEBUG_CAN_CONTROL_CPU,
         helper::{empty_ent_target, select_all_army},
+        values::{MOUSE_RECT_SIZE, QUICKSAVE_PATH, REPLAY_SEEK_TICKS},
     },
-    enums::{game_object::GameObject, unit_type::UnitType},
+    enums::{command::Command, game_object::GameObject, structure_type::StructureType},
 };
 
 use super::{
     camera::Camera,
-    ent::{EntParentType, Owner},
+    ent::{EntID, EntParentType, Owner},
     order::{EntTarget, Order, OrderType},
-    selection::MouseCommand,
+    replay::{Replay, ReplayPlayer},
+    save_file::{load_game, save_game},
+    selection::{MouseCommand, Selection},
+    structure::StructureParentType,
+    unit::UnitParentType,
     world::World,
+    world_info::WorldInfo,
 };
 
 // TODO: CLEANUP THIS FILE
@@ -22,426 +33,798 @@ use super::{
 pub struct Input;
 
 impl Input {
+    // Processes all pending SDL events
+    // Camera movement and the selection box get handled right away, anything that affects the simulation
+    // gets turned into a Command, recorded into the replay (if any), and then applied
+    // Returns whether to keep running, or an error if quicksaving or quickloading failed
     pub fn process_input(
         event_queue: &mut EventPump,
         camera: &mut Camera,
         world: &mut World,
-    ) -> bool {
+        world_info: &mut WorldInfo,
+        mut recording: Option<&mut Replay>,
+    ) -> Result<bool, String> {
         for event in event_queue.poll_iter() {
-            match event {
+            let command = match event {
                 Event::Quit { .. } => {
-                    return false;
+                    return Ok(false);
                 }
 
                 Event::MouseWheel { direction, y, .. } => {
                     camera.zoom(direction, y);
+                    None
                 }
 
                 Event::MouseMotion { x, y, .. } => {
-                    Self::process_mouse_motion(x, y, camera, world);
+                    let scaled_mouse_pos = Self::process_camera_motion(x, y, camera);
+                    world.selection.tick(scaled_mouse_pos);
+                    None
                 }
 
                 Event::MouseButtonDown {
                     mouse_btn, x, y, ..
                 } => {
-                    Self::process_mouse_button_down(mouse_btn, x, y, camera, world);
+                    camera.update_mouse_rect(Point::new(x, y));
+                    let scaled_mouse_pos = camera.get_scaled_mouse_pos();
+                    match mouse_btn {
+                        MouseButton::Left => match world.selection.left_click_command {
+                            // No command engaged, just open selection
+                            MouseCommand::Select => {
+                                world.selection.open(scaled_mouse_pos);
+                                None
+                            }
+                            MouseCommand::Action => Some(Self::left_click_action_command(
+                                scaled_mouse_pos,
+                                world,
+                                world_info,
+                            )),
+                            MouseCommand::Patrol => {
+                                Some(Self::left_click_patrol_command(scaled_mouse_pos, world))
+                            }
+                            MouseCommand::Place(_) => Self::place_structure_command(world),
+                        },
+                        MouseButton::Right => {
+                            // Release left click command (if any)
+                            world.selection.release_command();
+                            // Selected structures get their rally point moved, selected units get an order
+                            if let Some(rally_command) =
+                                Self::rally_point_command(scaled_mouse_pos, world, world_info)
+                            {
+                                Self::dispatch_command(
+                                    &rally_command,
+                                    world,
+                                    world_info,
+                                    recording.as_deref_mut(),
+                                );
+                            }
+                            let command =
+                                Self::right_click_command(scaled_mouse_pos, world, world_info);
+                            if command.is_none() {
+                                world.selection.start_formation(scaled_mouse_pos);
+                            }
+                            command
+                        }
+                        MouseButton::Middle => {
+                            camera.grab(&scaled_mouse_pos);
+                            None
+                        }
+                        MouseButton::X1 | MouseButton::X2 | MouseButton::Unknown => None,
+                    }
                 }
                 Event::MouseButtonUp {
-                    mouse_btn, x, y, ..
+                    mouse_btn,
+                    clicks,
+                    x,
+                    y,
+                    ..
                 } => {
-                    Self::process_mouse_button_up(mouse_btn, x, y, camera, world);
+                    camera.update_mouse_rect(Point::new(x, y));
+                    let scaled_mouse_pos = camera.get_scaled_mouse_pos();
+                    match mouse_btn {
+                        MouseButton::Left => match world.selection.left_click_command {
+                            MouseCommand::Select => {
+                                Self::select_command(scaled_mouse_pos, clicks, camera, world)
+                            }
+                            MouseCommand::Action
+                            | MouseCommand::Patrol
+                            | MouseCommand::Place(_) => {
+                                world.selection.release_command();
+                                None
+                            }
+                        },
+                        MouseButton::Middle => {
+                            camera.release();
+                            None
+                        }
+                        MouseButton::Right => Self::formation_move_command(scaled_mouse_pos, world),
+                        MouseButton::X1 | MouseButton::X2 | MouseButton::Unknown => None,
+                    }
                 }
 
                 Event::KeyDown {
                     keycode: Some(Keycode::LShift),
                     ..
-                } => world.selection.shift_press(),
+                } => {
+                    world.selection.shift_press();
+                    None
+                }
 
                 Event::KeyDown {
                     keycode: Some(Keycode::Escape),
                     ..
-                } => world.selection.clear(&mut world.game_objects),
+                } => {
+                    world.selection.clear();
+                    Some(Command::ClearSelection {
+                        issuer: Owner::Player,
+                    })
+                }
 
                 Event::KeyDown {
                     keycode: Some(Keycode::A),
                     ..
-                } => world.selection.engange_command(MouseCommand::Action),
+                } => {
+                    world.selection.engange_command(MouseCommand::Action);
+                    None
+                }
+
+                Event::KeyDown {
+                    keycode: Some(Keycode::R),
+                    ..
+                } => {
+                    world.selection.engange_command(MouseCommand::Patrol);
+                    None
+                }
+
+                Event::KeyDown {
+                    keycode: Some(Keycode::LCtrl | Keycode::RCtrl),
+                    ..
+                } => {
+                    world.selection.ctrl_press();
+                    None
+                }
+
+                Event::KeyUp {
+                    keycode: Some(Keycode::LCtrl | Keycode::RCtrl),
+                    ..
+                } => {
+                    world.selection.ctrl_release();
+                    None
+                }
 
                 Event::KeyUp {
                     keycode: Some(Keycode::LShift),
                     ..
-                } => world.selection.shift_release(),
+                } => {
+                    world.selection.shift_release();
+                    None
+                }
 
                 Event::KeyDown {
                     keycode: Some(Keycode::S),
                     ..
-                } => {
-                    for game_object in &mut world.game_objects {
-                        match game_object {
-                            GameObject::Unit(ent, unit) => {
-                                if ent.selected()
-                                    && (ent.owner == Owner::Player || DEBUG_CAN_CONTROL_CPU)
-                                {
-                                    // Issue stop order to owned selected units
-                                    match unit {
-                                        UnitType::Scout(unit)
-                                        | UnitType::Miner(unit)
-                                        | UnitType::Collector(unit) => unit.stop(ent),
-                                    }
-                                }
-                            }
-                            _ => (),
-                        }
-                    }
-                }
+                } => Some(Command::Stop {
+                    issuer: Owner::Player,
+                    units: Self::selected_units(world),
+                }),
 
                 Event::KeyDown {
                     keycode: Some(Keycode::H),
                     ..
+                } => Some(Command::HoldPosition {
+                    issuer: Owner::Player,
+                    units: Self::selected_units(world),
+                    queued: world.selection.queueing,
+                }),
+
+                Event::KeyDown {
+                    keycode: Some(Keycode::B),
+                    ..
                 } => {
-                    for game_object in &mut world.game_objects {
-                        match game_object {
-                            GameObject::Unit(ent, _) => {
-                                if ent.selected()
-                                    && (ent.owner == Owner::Player || DEBUG_CAN_CONTROL_CPU)
-                                {
-                                    // If queueing, need to figure out if this is the first order of the chain or not
-                                    // To know what to render
-                                    let mut hold_position_spot: Option<Vector2D<f32>> = None;
-                                    if world.selection.queueing {
-                                        if let Some(order) = ent.orders.last() {
-                                            hold_position_spot = Some(order.current_move_target);
-                                        } else {
-                                            hold_position_spot = Some(ent.position);
-                                        }
-                                    }
-                                    // Issue hold position order to owned selected units
-                                    let hold_position_order = Order::new(
-                                        OrderType::HoldPosition,
-                                        hold_position_spot.unwrap_or(ent.position),
-                                        empty_ent_target(),
-                                    );
-                                    ent.add_order(hold_position_order, !world.selection.queueing);
-                                }
-                            }
-                            _ => {}
-                        }
+                    // Only builders can place structures
+                    if !Self::selected_builders(world).is_empty() {
+                        world
+                            .selection
+                            .engange_command(MouseCommand::Place(StructureParentType::Mainframe));
                     }
+                    None
+                }
+
+                Event::KeyDown {
+                    keycode: Some(Keycode::Z),
+                    ..
+                } => Self::enqueue_production_command(world, UnitParentType::Scout),
+
+                Event::KeyDown {
+                    keycode: Some(Keycode::X),
+                    ..
+                } => Self::enqueue_production_command(world, UnitParentType::Miner),
+
+                Event::KeyDown {
+                    keycode: Some(Keycode::C),
+                    ..
+                } => Self::enqueue_production_command(world, UnitParentType::Collector),
+
+                Event::KeyDown {
+                    keycode: Some(Keycode::Backspace),
+                    ..
+                } => Self::cancel_construction_command(world)
+                    .or_else(|| Self::cancel_production_command(world)),
+
+                Event::KeyDown {
+                    keycode: Some(keycode),
+                    keymod,
+                    timestamp,
+                    repeat: false,
+                    ..
+                } if Self::control_group_index(keycode).is_some() => {
+                    Self::control_group_index(keycode).and_then(|group| {
+                        Self::control_group_command(
+                            group, keymod, timestamp, camera, world, world_info,
+                        )
+                    })
                 }
 
                 Event::KeyDown {
                     keycode: Some(Keycode::F2),
                     ..
+                } => Some(select_all_army(world)),
+
+                Event::KeyDown {
+                    keycode: Some(Keycode::F5),
+                    ..
                 } => {
-                    select_all_army(world);
+                    // Quicksave
+                    save_game(QUICKSAVE_PATH, world, world_info, camera)
+                        .map_err(|e| format!(">> Could not save game: {e}"))?;
+                    None
                 }
 
-                _ => {}
+                Event::KeyDown {
+                    keycode: Some(Keycode::F9),
+                    ..
+                } => {
+                    // Quickload; Replaces the whole game state
+                    // A replay can only reproduce a match that started from its scenario, so no loading while recording
+                    if recording.is_some() {
+                        return Err(">> Cannot load a game while recording a replay".to_owned());
+                    }
+                    let (loaded_world, loaded_world_info, loaded_camera) =
+                        load_game(QUICKSAVE_PATH)
+                            .map_err(|e| format!(">> Could not load game: {e}"))?;
+                    *world = loaded_world;
+                    *world_info = loaded_world_info;
+                    *camera = loaded_camera;
+                    None
+                }
+
+                _ => None,
+            };
+
+            if let Some(command) = command {
+                Self::dispatch_command(&command, world, world_info, recording.as_deref_mut());
             }
         }
-        true
+        Ok(true)
     }
 
-    fn process_mouse_button_up(
-        mouse_btn: MouseButton,
-        x: i32,
-        y: i32,
-        camera: &mut Camera,
+    // Records a command into the replay (if any), and then applies it
+    fn dispatch_command(
+        command: &Command,
         world: &mut World,
+        world_info: &mut WorldInfo,
+        recording: Option<&mut Replay>,
     ) {
-        camera.update_mouse_rect(Point::new(x, y));
-        let scaled_mouse_pos = camera.get_scaled_mouse_pos();
-        match mouse_btn {
-            MouseButton::Left => match world.selection.left_click_command {
-                MouseCommand::Select => world
-                    .selection
-                    .close(scaled_mouse_pos, &mut world.game_objects),
-                MouseCommand::Action => world.selection.release_command(),
-            },
-            MouseButton::Middle => camera.release(),
-            MouseButton::Right | MouseButton::X1 | MouseButton::X2 | MouseButton::Unknown => (),
+        if let Some(replay) = recording {
+            replay.record_command(world.current_tick, command);
         }
+        world.apply_command(command, world_info);
     }
 
-    fn process_mouse_button_down(
-        mouse_btn: MouseButton,
-        x: i32,
-        y: i32,
+    // Processes SDL events while watching a replay
+    // Only the camera and the playback controls respond; The world is driven by the replay
+    pub fn process_replay_input(
+        event_queue: &mut EventPump,
         camera: &mut Camera,
+        replay_player: &mut ReplayPlayer,
         world: &mut World,
-    ) {
-        // First, update mouse position
-        camera.update_mouse_rect(Point::new(x, y));
-        // Then, get scaled mouse position
-        let scaled_mouse_pos = camera.get_scaled_mouse_pos();
-        // This right click might issue an attack, mine or follow order, we will need to store its possible target
-        let mut click_target = empty_ent_target();
-        // Flag to know if we found at least one target
-        let mut found_target = false;
+        world_info: &mut WorldInfo,
+    ) -> bool {
+        for event in event_queue.poll_iter() {
+            match event {
+                Event::Quit { .. } => {
+                    return false;
+                }
 
-        // Check wether we clicked on something attackable
+                Event::MouseWheel { direction, y, .. } => {
+                    camera.zoom(direction, y);
+                }
+
+                Event::MouseMotion { x, y, .. } => {
+                    Self::process_camera_motion(x, y, camera);
+                }
+
+                Event::MouseButtonDown {
+                    mouse_btn: MouseButton::Middle,
+                    x,
+                    y,
+                    ..
+                } => {
+                    camera.update_mouse_rect(Point::new(x, y));
+                    camera.grab(&camera.get_scaled_mouse_pos());
+                }
+
+                Event::MouseButtonUp {
+                    mouse_btn: MouseButton::Middle,
+                    ..
+                } => camera.release(),
+
+                Event::KeyDown {
+                    keycode: Some(Keycode::Space),
+                    ..
+                } => replay_player.toggle_pause(),
+
+                Event::KeyDown {
+                    keycode: Some(Keycode::Up),
+                    ..
+                } => replay_player.speed_up(),
+
+                Event::KeyDown {
+                    keycode: Some(Keycode::Down),
+                    ..
+                } => replay_player.slow_down(),
+
+                Event::KeyDown {
+                    keycode: Some(Keycode::Left),
+                    ..
+                } => replay_player.seek(
+                    world,
+                    world_info,
+                    world.current_tick.saturating_sub(REPLAY_SEEK_TICKS),
+                ),
+
+                Event::KeyDown {
+                    keycode: Some(Keycode::Right),
+                    ..
+                } => replay_player.seek(world, world_info, world.current_tick + REPLAY_SEEK_TICKS),
+
+                _ => {}
+            }
+        }
+        true
+    }
+
+    // Owned or not, every currently selected unit; The command itself checks what can be controlled
+    fn selected_units(world: &World) -> Vec<EntID> {
+        let mut units: Vec<EntID> = Vec::<EntID>::new();
+        for game_object in &world.game_objects {
+            if let GameObject::Unit(ent, _) = game_object {
+                if ent.selected() {
+                    units.push(ent.id);
+                }
+            }
+        }
+        units
+    }
+
+    // Finishes a selection box or click
+    // Dragging grabs everything in the box (adding to the selection with shift),
+    // clicking grabs the ent under the mouse (toggling it in or out of the selection with shift),
+    // and double clicking (or ctrl clicking) grabs every unit of that type on screen
+    fn select_command(
+        scaled_mouse_pos: Point,
+        clicks: u8,
+        camera: &Camera,
+        world: &mut World,
+    ) -> Option<Command> {
+        let is_click = world.selection.is_click();
+        let picked = world.selection.close(scaled_mouse_pos, &world.game_objects);
+        if !is_click {
+            return Some(Command::Select {
+                issuer: Owner::Player,
+                ents: picked,
+                add: world.selection.queueing,
+            });
+        }
+        let clicked = *picked.first()?;
+
+        if clicks >= 2 || world.selection.same_type {
+            return Some(Command::Select {
+                issuer: Owner::Player,
+                ents: Selection::same_type_in_area(
+                    clicked,
+                    camera.get_view_area(),
+                    &world.game_objects,
+                ),
+                add: world.selection.queueing,
+            });
+        }
+
+        if !world.selection.queueing {
+            return Some(Command::Select {
+                issuer: Owner::Player,
+                ents: vec![clicked],
+                add: false,
+            });
+        }
+
+        // Toggle; The whole resulting selection gets sent over, since a Select can only ever add
+        let mut ents = Self::selected_ents(world);
+        if ents.contains(&clicked) {
+            ents.retain(|ent_id| *ent_id != clicked);
+        } else {
+            ents.push(clicked);
+        }
+        if ents.is_empty() {
+            return Some(Command::ClearSelection {
+                issuer: Owner::Player,
+            });
+        }
+        Some(Command::Select {
+            issuer: Owner::Player,
+            ents,
+            add: false,
+        })
+    }
+
+    // Every currently selected ent, owned or not
+    fn selected_ents(world: &World) -> Vec<EntID> {
+        let mut ents: Vec<EntID> = Vec::<EntID>::new();
         for game_object in &world.game_objects {
             match game_object {
                 GameObject::Unit(ent, _)
                 | GameObject::Structure(ent, _)
                 | GameObject::OrePatch(ent, _)
                 | GameObject::Ore(ent, _) => {
-                    if ent
-                        .get_rect()
-                        .has_intersection(camera.get_scaled_mouse_rect())
-                    {
-                        click_target = EntTarget {
-                            ent_id: Some(ent.id),
-                            ent_rect: Some(ent.get_rect()),
-                            ent_owner: Some(ent.owner),
-                            ent_parent_type: Some(ent.parent_type()),
-                        };
-                        found_target = true;
-                        break;
+                    if ent.selected() {
+                        ents.push(ent.id);
                     }
                 }
             }
         }
+        ents
+    }
 
-        match mouse_btn {
-            MouseButton::Left => {
-                // Left click
-                match world.selection.left_click_command {
-                    MouseCommand::Select => {
-                        // No command engaged, just open selection
-                        world
-                            .selection
-                            .open(scaled_mouse_pos, &mut world.game_objects);
-                    }
-                    MouseCommand::Action => {
-                        // Attack command engage
-                        // This could either trigger a direct action or an action move
-                        let target_is_ore_patch =
-                            click_target.ent_parent_type == Some(EntParentType::OrePatch);
-                        let target_is_ore =
-                            click_target.ent_parent_type == Some(EntParentType::Ore);
-                        for game_object in &mut world.game_objects {
-                            match game_object {
-                                GameObject::Unit(ent, unit_type) => {
-                                    if ent.selected()
-                                        && (ent.owner == Owner::Player || DEBUG_CAN_CONTROL_CPU)
-                                    {
-                                        if !found_target {
-                                            // No action target found; Issue attack move order
-                                            let action_move_order = Order::new(
-                                                OrderType::ActionMove,
-                                                Vector2D::<f32>::new(
-                                                    scaled_mouse_pos.x as f32,
-                                                    scaled_mouse_pos.y as f32,
-                                                ),
-                                                empty_ent_target(),
-                                            );
-                                            ent.add_order(
-                                                action_move_order,
-                                                !world.selection.queueing,
-                                            );
-                                        } else {
-                                            // Action target found; check if it is a valid one
-                                            // (defaults to self in case it's not there, canceling the action (it should be there tho))
-                                            let action_target_id =
-                                                click_target.ent_id.unwrap_or(ent.id);
-                                            if action_target_id == ent.id {
-                                                // Cannot action yourself.. for now!
-                                                continue;
-                                            }
-
-                                            if let Some(ent_owner) = click_target.ent_owner {
-                                                if ent_owner == ent.owner {
-                                                    // Cannot attack an ent on the same team!
-                                                    continue;
-                                                }
-                                            }
-
-                                            // Now we know we will either attack, mine or collect from this target!
-                                            // Check ent parent type to know what to do
-                                            let new_order_type = if target_is_ore_patch {
-                                                OrderType::Mine
-                                            } else if target_is_ore {
-                                                OrderType::Collect
-                                            } else {
-                                                OrderType::Attack
-                                            };
-
-                                            // Check if the unit can actually perform this action
-                                            match unit_type {
-                                                UnitType::Scout(_) => {
-                                                    if new_order_type != OrderType::Attack {
-                                                        continue;
-                                                    }
-                                                }
-                                                UnitType::Miner(_) => {
-                                                    if new_order_type != OrderType::Mine {
-                                                        continue;
-                                                    }
-                                                }
-                                                UnitType::Collector(_) => {
-                                                    if new_order_type != OrderType::Collect {
-                                                        continue;
-                                                    }
-                                                }
-                                            }
-
-                                            let new_order = Order::new(
-                                                new_order_type,
-                                                Vector2D::<f32>::new(
-                                                    scaled_mouse_pos.x as f32,
-                                                    scaled_mouse_pos.y as f32,
-                                                ),
-                                                EntTarget {
-                                                    ent_id: Some(action_target_id),
-                                                    ent_rect: click_target.ent_rect,
-                                                    ent_owner: click_target.ent_owner,
-                                                    ent_parent_type: click_target.ent_parent_type,
-                                                },
-                                            );
-                                            ent.add_order(new_order, !world.selection.queueing);
-                                        }
-                                    }
-                                }
-                                _ => {}
-                            }
-                        }
+    // Every currently selected ent owned by the player; Only those can go into a control group
+    fn selected_player_ents(world: &World) -> Vec<EntID> {
+        let mut ents: Vec<EntID> = Vec::<EntID>::new();
+        for game_object in &world.game_objects {
+            match game_object {
+                GameObject::Unit(ent, _)
+                | GameObject::Structure(ent, _)
+                | GameObject::OrePatch(ent, _)
+                | GameObject::Ore(ent, _) => {
+                    if ent.selected() && ent.owner == Owner::Player {
+                        ents.push(ent.id);
                     }
                 }
             }
-            MouseButton::Middle => {
-                camera.grab(&scaled_mouse_pos);
+        }
+        ents
+    }
+
+    // Digit keys map onto control groups, 0 through 9
+    const fn control_group_index(keycode: Keycode) -> Option<usize> {
+        match keycode {
+            Keycode::Num0 => Some(0),
+            Keycode::Num1 => Some(1),
+            Keycode::Num2 => Some(2),
+            Keycode::Num3 => Some(3),
+            Keycode::Num4 => Some(4),
+            Keycode::Num5 => Some(5),
+            Keycode::Num6 => Some(6),
+            Keycode::Num7 => Some(7),
+            Keycode::Num8 => Some(8),
+            Keycode::Num9 => Some(9),
+            _ => None,
+        }
+    }
+
+    // Ctrl stores the current selection into the group, shift adds it to the group,
+    // and a plain press recalls the group (jumping the camera over to it on a double tap)
+    fn control_group_command(
+        group: usize,
+        keymod: Mod,
+        timestamp: u32,
+        camera: &mut Camera,
+        world: &mut World,
+        world_info: &WorldInfo,
+    ) -> Option<Command> {
+        // Forget about grouped ents that died since the last time groups got touched
+        world.selection.control_groups.prune(world_info);
+        if keymod.intersects(Mod::LCTRLMOD | Mod::RCTRLMOD) {
+            let selected = Self::selected_player_ents(world);
+            world.selection.control_groups.assign(group, &selected);
+            return None;
+        }
+        if keymod.intersects(Mod::LSHIFTMOD | Mod::RSHIFTMOD) {
+            let selected = Self::selected_player_ents(world);
+            world.selection.control_groups.add(group, &selected);
+            return None;
+        }
+        let ents = world.selection.control_groups.get(group);
+        if ents.is_empty() {
+            return None;
+        }
+        if world.selection.control_groups.recall(group, timestamp) {
+            if let Some(center) = world.selection.control_groups.center(group, world_info) {
+                camera.center_on(center);
             }
-            MouseButton::Right => {
-                // Right click
-                // Issue either a move or attack command
-
-                // Release left click command (if any)
-                world.selection.release_command();
-                // Check wether we clicked on something attackable, or minable
-                for game_object in &world.game_objects {
-                    match game_object {
-                        GameObject::Unit(ent, _)
-                        | GameObject::Structure(ent, _)
-                        | GameObject::OrePatch(ent, _)
-                        | GameObject::Ore(ent, _) => {
-                            if ent
-                                .get_rect()
-                                .has_intersection(camera.get_scaled_mouse_rect())
-                            {
-                                click_target = EntTarget {
-                                    ent_id: Some(ent.id),
-                                    ent_rect: Some(ent.get_rect()),
-                                    ent_owner: Some(ent.owner),
-                                    ent_parent_type: Some(ent.parent_type()),
-                                };
-                                break;
+        }
+        Some(Command::Select {
+            issuer: Owner::Player,
+            ents,
+            add: false,
+        })
+    }
+
+    // Every currently selected (and built) structure owned by the player, along with how many units it has queued up
+    fn selected_structures(world: &World) -> Vec<(EntID, usize)> {
+        let mut structures: Vec<(EntID, usize)> = Vec::<(EntID, usize)>::new();
+        for game_object in &world.game_objects {
+            if let GameObject::Structure(ent, structure_type) = game_object {
+                if ent.selected() && ent.owner == Owner::Player {
+                    match structure_type {
+                        StructureType::Mainframe(structure) | StructureType::Spawner(structure) => {
+                            if !structure.is_under_construction() {
+                                structures.push((ent.id, structure.production_queue().len()));
                             }
                         }
                     }
                 }
+            }
+        }
+        structures
+    }
 
-                let target_is_ore_patch =
-                    click_target.ent_parent_type == Some(EntParentType::OrePatch);
-                let target_is_ore = click_target.ent_parent_type == Some(EntParentType::Ore);
+    // Every currently selected player unit able to build, along with where it is
+    fn selected_builders(world: &World) -> Vec<(EntID, Point)> {
+        let mut builders: Vec<(EntID, Point)> = Vec::<(EntID, Point)>::new();
+        for game_object in &world.game_objects {
+            if let GameObject::Unit(ent, unit_type) = game_object {
+                if ent.selected()
+                    && ent.owner == Owner::Player
+                    && unit_type.can_perform(OrderType::Build)
+                {
+                    builders.push((ent.id, ent.get_rect().center()));
+                }
+            }
+        }
+        builders
+    }
 
-                for game_object in &mut world.game_objects {
-                    match game_object {
-                        GameObject::Unit(ent, unit_type) => {
-                            if ent.selected()
-                                && (ent.owner == Owner::Player || DEBUG_CAN_CONTROL_CPU)
-                            {
-                                if !found_target {
-                                    // No right click target found; Issue move order
-                                    let move_order = Order::new(
-                                        OrderType::Move,
-                                        Vector2D::<f32>::new(
-                                            scaled_mouse_pos.x as f32,
-                                            scaled_mouse_pos.y as f32,
-                                        ),
-                                        empty_ent_target(),
-                                    );
-                                    ent.add_order(move_order, !world.selection.queueing);
-                                } else {
-                                    // Right click arget found; check if it is a valid one
-                                    // (defaults to self in case it's not there, canceling the attack (it should be there tho))
-                                    let attack_target_id = click_target.ent_id.unwrap_or(ent.id);
-                                    if attack_target_id == ent.id {
-                                        // Cannot attack yourself!
-                                        continue;
-                                    }
-                                    // At this point, we know we will either attack, follow, collect or mine this target, depending on it's type and team
-                                    // Now we know we will either attack, mine or collect from this target!
-                                    // Check ent parent type to know what to do
-                                    let new_order_type = if target_is_ore_patch {
-                                        OrderType::Mine
-                                    } else if target_is_ore {
-                                        OrderType::Collect
-                                    } else if click_target.ent_owner == Some(ent.owner) {
-                                        OrderType::Follow
-                                    } else {
-                                        OrderType::Attack
-                                    };
-
-                                    // Check if the unit can actually perform this action
-                                    match unit_type {
-                                        UnitType::Scout(_) => {
-                                            if !(vec![OrderType::Attack, OrderType::Follow]
-                                                .contains(&new_order_type))
-                                            {
-                                                continue;
-                                            }
-                                        }
-                                        UnitType::Miner(_) => {
-                                            if !(vec![OrderType::Mine, OrderType::Follow]
-                                                .contains(&new_order_type))
-                                            {
-                                                continue;
-                                            }
-                                        }
-                                        UnitType::Collector(_) => {
-                                            if !(vec![OrderType::Collect, OrderType::Follow]
-                                                .contains(&new_order_type))
-                                            {
-                                                continue;
-                                            }
-                                        }
-                                    }
-
-                                    let new_order = Order::new(
-                                        new_order_type,
-                                        Vector2D::<f32>::new(
-                                            scaled_mouse_pos.x as f32,
-                                            scaled_mouse_pos.y as f32,
-                                        ),
-                                        EntTarget {
-                                            ent_id: Option::Some(attack_target_id),
-                                            ent_rect: click_target.ent_rect,
-                                            ent_owner: click_target.ent_owner,
-                                            ent_parent_type: click_target.ent_parent_type,
-                                        },
-                                    );
-                                    ent.add_order(new_order, !world.selection.queueing);
-                                }
-                            }
-                        }
-                        _ => (),
-                    }
+    // Selected builder closest to a spot; Ties go to the oldest unit
+    fn closest_selected_builder(world: &World, spot: Point) -> Option<EntID> {
+        Self::selected_builders(world)
+            .into_iter()
+            .min_by_key(|(ent_id, position)| {
+                let offset = *position - spot;
+                (
+                    offset.x as i64 * offset.x as i64 + offset.y as i64 * offset.y as i64,
+                    *ent_id,
+                )
+            })
+            .map(|(ent_id, _)| ent_id)
+    }
+
+    // Left click in placement mode; Lays down the structure under the mouse, and sends the closest builder over
+    fn place_structure_command(world: &World) -> Option<Command> {
+        let (structure_type, placement_rect) = world.selection.placement()?;
+        let builder = Self::closest_selected_builder(world, placement_rect.center())?;
+        Some(Command::PlaceStructure {
+            issuer: Owner::Player,
+            builder,
+            structure_type,
+            position: Vector2D::<f32>::new(placement_rect.x() as f32, placement_rect.y() as f32),
+            queued: world.selection.queueing,
+        })
+    }
+
+    // Tears down the first selected construction site (if any)
+    fn cancel_construction_command(world: &World) -> Option<Command> {
+        world
+            .game_objects
+            .iter()
+            .find_map(|game_object| match game_object {
+                GameObject::Structure(
+                    ent,
+                    StructureType::Mainframe(structure) | StructureType::Spawner(structure),
+                ) if ent.selected()
+                    && ent.owner == Owner::Player
+                    && structure.is_under_construction() =>
+                {
+                    Some(Command::CancelConstruction {
+                        issuer: Owner::Player,
+                        structure: ent.id,
+                    })
                 }
+                _ => None,
+            })
+    }
+
+    // Queues a unit up in whichever selected structure is the least busy
+    fn enqueue_production_command(world: &World, unit_type: UnitParentType) -> Option<Command> {
+        let (structure, _) = Self::selected_structures(world)
+            .into_iter()
+            .min_by_key(|(_, queue_length)| *queue_length)?;
+        Some(Command::EnqueueProduction {
+            issuer: Owner::Player,
+            structure,
+            unit_type,
+        })
+    }
+
+    // Cancels the last unit queued up in the first selected structure that has anything queued
+    fn cancel_production_command(world: &World) -> Option<Command> {
+        let (structure, queue_length) = Self::selected_structures(world)
+            .into_iter()
+            .find(|(_, queue_length)| *queue_length > 0)?;
+        Some(Command::CancelProduction {
+            issuer: Owner::Player,
+            structure,
+            index: queue_length - 1,
+        })
+    }
+
+    // Right click with structures selected
+    // Rallies onto a clicked friendly ent, or onto the clicked spot
+    fn rally_point_command(
+        scaled_mouse_pos: Point,
+        world: &World,
+        world_info: &WorldInfo,
+    ) -> Option<Command> {
+        let structures: Vec<EntID> = Self::selected_structures(world)
+            .into_iter()
+            .map(|(structure, _)| structure)
+            .collect();
+        if structures.is_empty() {
+            return None;
+        }
+        let click_target = Self::find_click_target(scaled_mouse_pos, world_info);
+        let rally_point = match click_target.ent_parent_type {
+            Some(EntParentType::Unit | EntParentType::Structure)
+                if click_target.ent_owner == Some(Owner::Player) =>
+            {
+                Order::new(
+                    OrderType::Follow,
+                    Vector2D::<f32>::new(scaled_mouse_pos.x as f32, scaled_mouse_pos.y as f32),
+                    click_target,
+                )
             }
-            MouseButton::Unknown | MouseButton::X1 | MouseButton::X2 => (),
+            _ => Order::new(
+                OrderType::Move,
+                Vector2D::<f32>::new(scaled_mouse_pos.x as f32, scaled_mouse_pos.y as f32),
+                empty_ent_target(),
+            ),
+        };
+        Some(Command::SetRallyPoint {
+            issuer: Owner::Player,
+            structures,
+            rally_point: Some(rally_point),
+        })
+    }
+
+    // Check wether we clicked on something attackable, minable, collectable or followable
+    // Picks the ent under the mouse; Ties go to the oldest ent (lowest EntID)
+    fn find_click_target(scaled_mouse_pos: Point, world_info: &WorldInfo) -> EntTarget {
+        match world_info
+            .get_ents_in_rect(Self::get_click_rect(scaled_mouse_pos))
+            .first()
+        {
+            Some((ent_id, ent_rect)) => EntTarget {
+                ent_id: Some(*ent_id),
+                ent_rect: Some(*ent_rect),
+                ent_owner: world_info.get_ent_owner_by_id(*ent_id),
+                ent_parent_type: world_info.get_ent_parent_type_by_id(*ent_id),
+            },
+            None => empty_ent_target(),
         }
     }
 
-    fn process_mouse_motion(x: i32, y: i32, camera: &mut Camera, world: &mut World) {
+    // Left click with the action command engaged
+    // This could either trigger a direct action (attack, mine or collect) or an action move
+    fn left_click_action_command(
+        scaled_mouse_pos: Point,
+        world: &World,
+        world_info: &WorldInfo,
+    ) -> Command {
+        let click_target = Self::find_click_target(scaled_mouse_pos, world_info);
+        let order_type = match click_target.ent_parent_type {
+            None => OrderType::ActionMove,
+            Some(EntParentType::OrePatch) => OrderType::Mine,
+            Some(EntParentType::Ore) => OrderType::Collect,
+            Some(_) => OrderType::Attack,
+        };
+        Command::IssueOrder {
+            issuer: Owner::Player,
+            units: Self::selected_units(world),
+            order: Order::new(
+                order_type,
+                Vector2D::<f32>::new(scaled_mouse_pos.x as f32, scaled_mouse_pos.y as f32),
+                click_target,
+            ),
+            queued: world.selection.queueing,
+        }
+    }
+
+    // Left click with the patrol command engaged
+    // Patrols never target anything, they just walk a route and fight whatever they come across
+    fn left_click_patrol_command(scaled_mouse_pos: Point, world: &World) -> Command {
+        Command::IssueOrder {
+            issuer: Owner::Player,
+            units: Self::selected_units(world),
+            order: Order::new(
+                OrderType::Patrol,
+                Vector2D::<f32>::new(scaled_mouse_pos.x as f32, scaled_mouse_pos.y as f32),
+                empty_ent_target(),
+            ),
+            queued: world.selection.queueing,
+        }
+    }
+
+    // Right click
+    // Issue either a move, or an attack, mine, collect or follow order depending on what got clicked
+    fn right_click_command(
+        scaled_mouse_pos: Point,
+        world: &World,
+        world_info: &WorldInfo,
+    ) -> Option<Command> {
+        let click_target = Self::find_click_target(scaled_mouse_pos, world_info);
+        let order_type = match click_target.ent_parent_type {
+            // Moves wait for the button to come back up, since dragging picks which way the formation faces
+            None => return None,
+            Some(EntParentType::OrePatch) => OrderType::Mine,
+            Some(EntParentType::Ore) => OrderType::Collect,
+            Some(_) if click_target.ent_owner == Some(Owner::Player) => OrderType::Follow,
+            Some(_) => OrderType::Attack,
+        };
+        Some(Command::IssueOrder {
+            issuer: Owner::Player,
+            units: Self::selected_units(world),
+            order: Order::new(
+                order_type,
+                Vector2D::<f32>::new(scaled_mouse_pos.x as f32, scaled_mouse_pos.y as f32),
+                click_target,
+            ),
+            queued: world.selection.queueing,
+        })
+    }
+
+    // Finishes a right click (or drag) on open ground, moving the selected units there in formation
+    // Dragging picks which way the formation faces
+    fn formation_move_command(scaled_mouse_pos: Point, world: &mut World) -> Option<Command> {
+        let (origin, facing) = world.selection.finish_formation(scaled_mouse_pos)?;
+        // Nothing to move (e.g. only structures are selected)
+        let units = Self::selected_units(world);
+        if units.is_empty() {
+            return None;
+        }
+        Some(Command::FormationMove {
+            issuer: Owner::Player,
+            units,
+            destination: Vector2D::<f32>::new(origin.x as f32, origin.y as f32),
+            facing: facing.map(|facing| Vector2D::<f32>::new(facing.x as f32, facing.y as f32)),
+            queued: world.selection.queueing,
+        })
+    }
+
+    // Drags the camera if anchored, returns the scaled mouse position from before the drag
+    fn process_camera_motion(x: i32, y: i32, camera: &mut Camera) -> Point {
         camera.update_mouse_rect(Point::new(x, y));
         let scaled_mouse_pos = camera.get_scaled_mouse_pos();
-        world
-            .selection
-            .tick(scaled_mouse_pos, &mut world.game_objects);
         if camera.is_anchored() {
             let anchored_mouse_pos = camera.get_anchored_mouse_pos();
             camera.drag_to(anchored_mouse_pos);
         }
+        scaled_mouse_pos
+    }
+
+    // Rect used to check what a click at some world position landed on
+    fn get_click_rect(scaled_mouse_pos: Point) -> Rect {
+        Rect::new(
+            scaled_mouse_pos.x,
+            scaled_mouse_pos.y,
+            MOUSE_RECT_SIZE,
+            MOUSE_RECT_SIZE,
+        )
     }
 }
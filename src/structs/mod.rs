@@ -1,14 +1,30 @@
+pub mod ai_player;
 pub mod camera;
+pub mod control_group;
+pub mod damage;
 pub mod ent;
+pub mod flow_field;
+pub mod formation;
+pub mod game_clock;
 pub mod input;
+pub mod nav_grid;
 pub mod order;
 pub mod ore;
 pub mod ore_patch;
+pub mod player_state;
+pub mod projectile;
+pub mod replay;
+pub mod save_file;
 pub mod selection;
+pub mod sim_rng;
+pub mod spatial_hash;
+pub mod spawner;
+pub mod state_hash;
 pub mod structure;
 pub mod text_label;
 pub mod ui;
 pub mod ui_element;
 pub mod unit;
+pub mod weapon;
 pub mod world;
 pub mod world_info;
@@ -0,0 +1,318 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+use sdl2::rect::Rect;
+use vector2d::Vector2D;
+
+use crate::consts::values::{
+    MAP_HEIGHT, MAP_WIDTH, NAV_CELL_SIZE, NAV_FREE_CELL_SEARCH_RADIUS, NAV_OBSTACLE_CLEARANCE,
+};
+
+// Movement costs between neighbouring cells (straight, diagonal), scaled to stay integers
+const STRAIGHT_COST: u32 = 10;
+const DIAGONAL_COST: u32 = 14;
+
+// Coarse grid over the whole map, marking which cells are blocked by static obstacles (ore patches, structures)
+// Units don't block cells; They just collide with each other while following their paths
+pub struct NavGrid {
+    pub columns: i32,
+    pub rows: i32,
+    blocked: Vec<bool>,
+}
+
+impl Default for NavGrid {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl NavGrid {
+    pub fn new() -> Self {
+        let columns = (MAP_WIDTH as i32 + NAV_CELL_SIZE - 1) / NAV_CELL_SIZE;
+        let rows = (MAP_HEIGHT as i32 + NAV_CELL_SIZE - 1) / NAV_CELL_SIZE;
+        Self {
+            columns,
+            rows,
+            blocked: vec![false; (columns * rows) as usize],
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.blocked.fill(false);
+    }
+
+    // Blocks every cell touched by an obstacle rect
+    // Obstacles get inflated a bit, so that a unit's center following a path keeps its whole body clear of them
+    pub fn block_rect(&mut self, rect: Rect) {
+        let (min_column, min_row) = self.cell_of(Vector2D::<f32>::new(
+            (rect.left() - NAV_OBSTACLE_CLEARANCE) as f32,
+            (rect.top() - NAV_OBSTACLE_CLEARANCE) as f32,
+        ));
+        let (max_column, max_row) = self.cell_of(Vector2D::<f32>::new(
+            (rect.right() + NAV_OBSTACLE_CLEARANCE) as f32,
+            (rect.bottom() + NAV_OBSTACLE_CLEARANCE) as f32,
+        ));
+        for row in min_row..=max_row {
+            for column in min_column..=max_column {
+                let index = self.index((column, row));
+                self.blocked[index] = true;
+            }
+        }
+    }
+
+    // Positions outside the map get clamped to its border cells
+    pub fn cell_of(&self, position: Vector2D<f32>) -> (i32, i32) {
+        (
+            ((position.x / NAV_CELL_SIZE as f32).floor() as i32).clamp(0, self.columns - 1),
+            ((position.y / NAV_CELL_SIZE as f32).floor() as i32).clamp(0, self.rows - 1),
+        )
+    }
+
+    pub fn cell_center(&self, cell: (i32, i32)) -> Vector2D<f32> {
+        Vector2D::<f32>::new(
+            (cell.0 * NAV_CELL_SIZE + NAV_CELL_SIZE / 2) as f32,
+            (cell.1 * NAV_CELL_SIZE + NAV_CELL_SIZE / 2) as f32,
+        )
+    }
+
+    pub fn is_blocked(&self, cell: (i32, i32)) -> bool {
+        if cell.0 < 0 || cell.1 < 0 || cell.0 >= self.columns || cell.1 >= self.rows {
+            return true;
+        }
+        self.blocked[self.index(cell)]
+    }
+
+    pub fn index(&self, cell: (i32, i32)) -> usize {
+        (cell.1 * self.columns + cell.0) as usize
+    }
+
+    pub fn cell_at(&self, index: usize) -> (i32, i32) {
+        (index as i32 % self.columns, index as i32 / self.columns)
+    }
+
+    pub fn cell_count(&self) -> usize {
+        self.blocked.len()
+    }
+
+    // Free cells that can be walked to from a given cell, along with the cost of the step
+    pub fn neighbours(&self, cell: (i32, i32)) -> impl Iterator<Item = ((i32, i32), u32)> + '_ {
+        [
+            (1, 0),
+            (-1, 0),
+            (0, 1),
+            (0, -1),
+            (1, 1),
+            (1, -1),
+            (-1, 1),
+            (-1, -1),
+        ]
+        .into_iter()
+        .filter_map(move |(dx, dy)| {
+            let neighbour = (cell.0 + dx, cell.1 + dy);
+            if self.is_blocked(neighbour) {
+                return None;
+            }
+            let is_diagonal = dx != 0 && dy != 0;
+            if !is_diagonal {
+                return Some((neighbour, STRAIGHT_COST));
+            }
+            // No cutting corners around obstacles
+            if self.is_blocked((cell.0 + dx, cell.1)) || self.is_blocked((cell.0, cell.1 + dy)) {
+                return None;
+            }
+            Some((neighbour, DIAGONAL_COST))
+        })
+    }
+
+    // Walks the cells crossed by the segment one by one (grid traversal), checking that none of them are blocked
+    // Blocked cells right at the start don't count; A unit that got pushed into one must still be able to walk out
+    pub fn has_line_of_sight(&self, from: Vector2D<f32>, to: Vector2D<f32>) -> bool {
+        let (mut column, mut row) = self.cell_of(from);
+        let (end_column, end_row) = self.cell_of(to);
+        let direction = to - from;
+        let cell_size = NAV_CELL_SIZE as f32;
+
+        // How far along the segment (0.0 to 1.0) the next column / row border gets crossed,
+        // and how far apart those crossings are
+        let (step_x, mut next_x, delta_x) = if direction.x > 0.0 {
+            (
+                1,
+                ((column + 1) as f32 * cell_size - from.x) / direction.x,
+                cell_size / direction.x,
+            )
+        } else if direction.x < 0.0 {
+            (
+                -1,
+                (column as f32 * cell_size - from.x) / direction.x,
+                -cell_size / direction.x,
+            )
+        } else {
+            (0, f32::INFINITY, f32::INFINITY)
+        };
+        let (step_y, mut next_y, delta_y) = if direction.y > 0.0 {
+            (
+                1,
+                ((row + 1) as f32 * cell_size - from.y) / direction.y,
+                cell_size / direction.y,
+            )
+        } else if direction.y < 0.0 {
+            (
+                -1,
+                (row as f32 * cell_size - from.y) / direction.y,
+                -cell_size / direction.y,
+            )
+        } else {
+            (0, f32::INFINITY, f32::INFINITY)
+        };
+
+        // Every step moves one cell closer to the end cell, so this bounds the walk
+        let steps = (end_column - column).abs() + (end_row - row).abs();
+        let mut leaving_start = true;
+        for _ in 0..=steps {
+            if self.is_blocked((column, row)) {
+                if !leaving_start {
+                    return false;
+                }
+            } else {
+                leaving_start = false;
+            }
+            if column == end_column && row == end_row {
+                return true;
+            }
+            if (next_x < next_y && column != end_column) || row == end_row {
+                column += step_x;
+                next_x += delta_x;
+            } else {
+                row += step_y;
+                next_y += delta_y;
+            }
+        }
+        true
+    }
+
+    // Closest unblocked cell to a given cell (itself if free), searching in growing rings
+    pub fn nearest_free_cell(&self, cell: (i32, i32)) -> Option<(i32, i32)> {
+        if !self.is_blocked(cell) {
+            return Some(cell);
+        }
+        for radius in 1..=NAV_FREE_CELL_SEARCH_RADIUS {
+            let mut best: Option<((i32, i32), i32)> = None;
+            for row in cell.1 - radius..=cell.1 + radius {
+                for column in cell.0 - radius..=cell.0 + radius {
+                    // Only look at the ring itself; Inner cells were checked already
+                    if (row - cell.1).abs() != radius && (column - cell.0).abs() != radius {
+                        continue;
+                    }
+                    if self.is_blocked((column, row)) {
+                        continue;
+                    }
+                    let distance = (column - cell.0).pow(2) + (row - cell.1).pow(2);
+                    if best.is_none_or(|(_, best_distance)| distance < best_distance) {
+                        best = Some(((column, row), distance));
+                    }
+                }
+            }
+            if let Some((free_cell, _)) = best {
+                return Some(free_cell);
+            }
+        }
+        None
+    }
+
+    // Point a unit can actually stand on, closest to the given position
+    pub fn closest_free_point(&self, position: Vector2D<f32>) -> Vector2D<f32> {
+        let cell = self.cell_of(position);
+        match self.nearest_free_cell(cell) {
+            Some(free_cell) if free_cell != cell => self.cell_center(free_cell),
+            _ => position,
+        }
+    }
+
+    // A* search from one position to another, over 8-connected cells
+    // Returns a smoothed list of waypoints (ending at the reachable point closest to the destination)
+    pub fn find_path(&self, from: Vector2D<f32>, to: Vector2D<f32>) -> Option<Vec<Vector2D<f32>>> {
+        let start = self.nearest_free_cell(self.cell_of(from))?;
+        let goal = self.nearest_free_cell(self.cell_of(to))?;
+
+        let cell_count = self.cell_count();
+        let mut cost_so_far: Vec<u32> = vec![u32::MAX; cell_count];
+        let mut came_from: Vec<usize> = vec![usize::MAX; cell_count];
+        // Ties get broken by cell index, so the same search always finds the same path
+        let mut open: BinaryHeap<Reverse<(u32, usize)>> = BinaryHeap::new();
+
+        let start_index = self.index(start);
+        let goal_index = self.index(goal);
+        cost_so_far[start_index] = 0;
+        open.push(Reverse((self.heuristic(start, goal), start_index)));
+
+        while let Some(Reverse((_, current_index))) = open.pop() {
+            if current_index == goal_index {
+                break;
+            }
+            for (neighbour, step_cost) in self.neighbours(self.cell_at(current_index)) {
+                let neighbour_index = self.index(neighbour);
+                let new_cost = cost_so_far[current_index] + step_cost;
+                if new_cost < cost_so_far[neighbour_index] {
+                    cost_so_far[neighbour_index] = new_cost;
+                    came_from[neighbour_index] = current_index;
+                    open.push(Reverse((
+                        new_cost + self.heuristic(neighbour, goal),
+                        neighbour_index,
+                    )));
+                }
+            }
+        }
+
+        if cost_so_far[goal_index] == u32::MAX {
+            return None;
+        }
+
+        // Walk back from the goal to build the list of cell centers
+        let mut points: Vec<Vector2D<f32>> = Vec::<Vector2D<f32>>::new();
+        let mut current_index = goal_index;
+        while current_index != start_index {
+            points.push(self.cell_center(self.cell_at(current_index)));
+            current_index = came_from[current_index];
+        }
+        points.reverse();
+        // End at the exact destination when it is reachable
+        if goal == self.cell_of(to) {
+            if let Some(last) = points.last_mut() {
+                *last = to;
+            } else {
+                points.push(to);
+            }
+        }
+        if points.is_empty() {
+            points.push(self.cell_center(goal));
+        }
+
+        Some(self.smooth_path(from, points))
+    }
+
+    // Octile distance; Never overestimates the cost with diagonal moves allowed
+    fn heuristic(&self, from: (i32, i32), to: (i32, i32)) -> u32 {
+        let dx = (from.0 - to.0).unsigned_abs();
+        let dy = (from.1 - to.1).unsigned_abs();
+        STRAIGHT_COST * dx.max(dy) + (DIAGONAL_COST - STRAIGHT_COST) * dx.min(dy)
+    }
+
+    // Drops every waypoint that can be skipped by going in a straight line
+    fn smooth_path(&self, from: Vector2D<f32>, points: Vec<Vector2D<f32>>) -> Vec<Vector2D<f32>> {
+        let mut smoothed: Vec<Vector2D<f32>> = Vec::<Vector2D<f32>>::new();
+        let mut anchor = from;
+        let mut i = 0;
+        while i < points.len() {
+            let mut furthest = i;
+            while furthest + 1 < points.len()
+                && self.has_line_of_sight(anchor, points[furthest + 1])
+            {
+                furthest += 1;
+            }
+            smoothed.push(points[furthest]);
+            anchor = points[furthest];
+            i = furthest + 1;
+        }
+        smoothed
+    }
+}
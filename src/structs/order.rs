@@ -1,9 +1,15 @@
+use std::hash::Hash;
+
 use sdl2::rect::Rect;
 use vector2d::Vector2D;
 
-use super::ent::{EntID, EntParentType, Owner};
+use super::{
+    ent::{EntID, EntParentType, Owner},
+    save_file::{persist_enum, Persist, SaveReader, SaveWriter},
+    state_hash::StateHasher,
+};
 
-#[derive(Copy, Clone, PartialEq, Eq)]
+#[derive(Copy, Clone, PartialEq, Eq, Hash)]
 pub enum OrderType {
     Move,
     Mine,
@@ -13,6 +19,31 @@ pub enum OrderType {
     LazyAttack,
     ActionMove,
     HoldPosition,
+    Deposit,
+    Build,
+    Patrol,
+}
+
+impl OrderType {
+    // Orders headed for a fixed point share a flow field with every other unit headed there
+    pub const fn uses_flow_field(&self) -> bool {
+        matches!(
+            self,
+            OrderType::Move | OrderType::ActionMove | OrderType::Patrol
+        )
+    }
+
+    // Orders headed for another ent (which might move) get their own A* path around obstacles
+    pub const fn uses_pathfinding(&self) -> bool {
+        matches!(
+            self,
+            OrderType::Follow
+                | OrderType::Mine
+                | OrderType::Collect
+                | OrderType::Deposit
+                | OrderType::Build
+        )
+    }
 }
 
 #[derive(Copy, Clone, PartialEq)]
@@ -22,6 +53,8 @@ pub struct Order {
     pub completed: bool,
     pub current_move_target: Vector2D<f32>,
     pub ent_target: EntTarget,
+    pub speed_limit: Option<f32>, // Caps how fast the unit goes while carrying this order out; Keeps formations together
+    pub formation_anchor: Option<Vector2D<f32>>, // Where the unit's formation is headed; Members share its flow field until they can see their own spot
 }
 
 #[derive(Copy, Clone, PartialEq, Eq)]
@@ -44,6 +77,8 @@ impl Order {
             completed: false,
             current_move_target,
             ent_target,
+            speed_limit: None,
+            formation_anchor: None,
         }
     }
 
@@ -54,4 +89,82 @@ impl Order {
     pub fn complete(&mut self) {
         self.completed = true;
     }
+
+    pub fn hash_state(&self, hasher: &mut StateHasher) {
+        self.order_type.hash(hasher);
+        self.executed.hash(hasher);
+        self.completed.hash(hasher);
+        hasher.write_vector(self.current_move_target);
+        self.ent_target.ent_id.hash(hasher);
+        self.ent_target.ent_rect.is_some().hash(hasher);
+        if let Some(ent_rect) = self.ent_target.ent_rect {
+            hasher.write_rect(ent_rect);
+        }
+        self.ent_target.ent_owner.hash(hasher);
+        self.ent_target.ent_parent_type.hash(hasher);
+        self.speed_limit.is_some().hash(hasher);
+        if let Some(speed_limit) = self.speed_limit {
+            hasher.write_f32(speed_limit);
+        }
+        self.formation_anchor.is_some().hash(hasher);
+        if let Some(formation_anchor) = self.formation_anchor {
+            hasher.write_vector(formation_anchor);
+        }
+    }
+}
+
+persist_enum!(OrderType {
+    Move,
+    Mine,
+    Collect,
+    Follow,
+    Attack,
+    LazyAttack,
+    ActionMove,
+    HoldPosition,
+    Deposit,
+    Build,
+    Patrol,
+});
+
+impl Persist for EntTarget {
+    fn save(&self, writer: &mut SaveWriter) {
+        self.ent_id.save(writer);
+        self.ent_rect.save(writer);
+        self.ent_owner.save(writer);
+        self.ent_parent_type.save(writer);
+    }
+
+    fn load(reader: &mut SaveReader) -> Result<Self, String> {
+        Ok(Self {
+            ent_id: Option::<EntID>::load(reader)?,
+            ent_rect: Option::<Rect>::load(reader)?,
+            ent_owner: Option::<Owner>::load(reader)?,
+            ent_parent_type: Option::<EntParentType>::load(reader)?,
+        })
+    }
+}
+
+impl Persist for Order {
+    fn save(&self, writer: &mut SaveWriter) {
+        self.order_type.save(writer);
+        self.executed.save(writer);
+        self.completed.save(writer);
+        self.current_move_target.save(writer);
+        self.ent_target.save(writer);
+        self.speed_limit.save(writer);
+        self.formation_anchor.save(writer);
+    }
+
+    fn load(reader: &mut SaveReader) -> Result<Self, String> {
+        Ok(Self {
+            order_type: OrderType::load(reader)?,
+            executed: bool::load(reader)?,
+            completed: bool::load(reader)?,
+            current_move_target: Vector2D::<f32>::load(reader)?,
+            ent_target: EntTarget::load(reader)?,
+            speed_limit: Option::<f32>::load(reader)?,
+            formation_anchor: Option::<Vector2D<f32>>::load(reader)?,
+        })
+    }
 }
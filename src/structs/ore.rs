@@ -1,12 +1,14 @@
-use sdl2::{gfx::primitives::DrawRenderer, rect::Point, render::Canvas, video::Window};
-use vector2d::Vector2D;
+use std::hash::Hash;
 
-use crate::consts::{
-    helper::draw_circle_selection_border,
-    values::{BLACK_RGB, WHITE_RGB},
-};
+use sdl2::rect::Point;
 
-use super::{ent::Ent, ore_patch::OreType, world_info::WorldInfo};
+use super::{
+    ent::Ent,
+    ore_patch::OreType,
+    save_file::{Persist, SaveReader, SaveWriter},
+    state_hash::StateHasher,
+    world_info::WorldInfo,
+};
 
 pub struct Ore {
     ore_type: OreType,
@@ -33,46 +35,32 @@ impl Ore {
         ent.rect_size = Point::new(current_radius as i32, current_radius as i32);
     }
 
-    pub fn draw(&self, ent: &mut Ent, canvas: &mut Canvas<Window>) {
-        // If dead, return early
-        if ent.hp <= 0.0 {
-            return {};
-        }
-        // If selected, draw selection border
-        if ent.selected() {
-            let ent_rect_center = ent.get_rect().center();
-            draw_circle_selection_border(
-                canvas,
-                Vector2D::<f32>::new(ent_rect_center.x as f32, ent_rect_center.y as f32),
-                self.get_radius(ent),
-                WHITE_RGB,
-            );
-        }
-
-        let ent_rect_center = ent.get_rect().center();
-
-        // Draw self (if alive)
-        canvas.set_draw_color(ent.color);
-        canvas
-            .filled_circle(
-                ent_rect_center.x as i16,
-                ent_rect_center.y as i16,
-                self.get_radius(ent),
-                ent.color,
-            )
-            .ok();
-        canvas.set_draw_color(BLACK_RGB);
-        canvas
-            .circle(
-                ent_rect_center.x as i16,
-                ent_rect_center.y as i16,
-                self.get_radius(ent),
-                ent.color,
-            )
-            .ok();
+    pub fn ore_type(&self) -> OreType {
+        self.ore_type
     }
 
     pub fn get_radius(&self, ent: &Ent) -> i16 {
         ((self.value * 100.0) * (ent.hp / ent.max_hp as f32)) as i16
     }
+
+    pub fn hash_state(&self, hasher: &mut StateHasher) {
+        self.ore_type.hash(hasher);
+        hasher.write_f32(self.value);
+    }
+}
+
+impl Persist for Ore {
+    fn save(&self, writer: &mut SaveWriter) {
+        writer.write_label("ore");
+        self.ore_type.save(writer);
+        self.value.save(writer);
+    }
+
+    fn load(reader: &mut SaveReader) -> Result<Self, String> {
+        reader.expect_label("ore")?;
+        Ok(Self {
+            ore_type: OreType::load(reader)?,
+            value: f32::load(reader)?,
+        })
+    }
 }
@@ -1,22 +1,21 @@
+use std::hash::{Hash, Hasher};
+
 use rand::Rng;
-use sdl2::{rect::Point, render::Canvas, video::Window};
+use sdl2::rect::Point;
 use vector2d::Vector2D;
 
-use crate::{
-    consts::{
-        helper::draw_rect_selection_border,
-        values::{BLACK_RGB, BLUE_RGB, YELLOW_RGBA_WEAK},
-    },
-    enums::game_object::GameObject,
-};
+use crate::{consts::values::BLUE_RGB, enums::game_object::GameObject};
 
 use super::{
-    ent::{Ent, EntParentType},
+    ent::{Ent, EntIDAllocator, EntParentType},
     ore::Ore,
+    save_file::{persist_enum, Persist, SaveReader, SaveWriter},
+    sim_rng::SimRng,
+    state_hash::StateHasher,
     world_info::WorldInfo,
 };
 
-#[derive(Copy, Clone, PartialEq, Eq, Hash)]
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum OreType {
     Blue,
 }
@@ -35,7 +34,13 @@ impl OrePatch {
         }
     }
 
-    pub fn tick(&mut self, ent: &mut Ent, world_info: &mut WorldInfo) -> Option<GameObject> {
+    pub fn tick(
+        &mut self,
+        ent: &mut Ent,
+        world_info: &mut WorldInfo,
+        rng: &mut SimRng,
+        ent_ids: &mut EntIDAllocator,
+    ) -> Option<GameObject> {
         // Update local HP based on world_info data
         // If not found there, then unit is dead
         ent.hp = world_info.get_ent_hp(ent).unwrap_or(0.0);
@@ -50,15 +55,21 @@ impl OrePatch {
         if health_left != 1.0 && (health_left * 100.0) as u32 % self.density == 0 {
             // TODO: Limit how many times this triggers per chunk  (chunks are correct)
             dbg! {(health_left, (health_left * 100.0) as u32 % self.density)};
-            return Some(self.drop_new_ore(ent, world_info));
+            return Some(self.drop_new_ore(ent, world_info, rng, ent_ids));
         }
 
         None
     }
 
-    fn drop_new_ore(&self, ent: &Ent, world_info: &mut WorldInfo) -> GameObject {
-        let mut rng = rand::thread_rng();
+    fn drop_new_ore(
+        &self,
+        ent: &Ent,
+        world_info: &mut WorldInfo,
+        rng: &mut SimRng,
+        ent_ids: &mut EntIDAllocator,
+    ) -> GameObject {
         let new_ent = Ent::new(
+            ent_ids.next_id(),
             EntParentType::Ore,
             ent.owner,
             (self.richness * 100.0) as u32,
@@ -77,25 +88,33 @@ impl OrePatch {
             ),
             BLUE_RGB,
         );
-        world_info.add_ent(&new_ent);
+        world_info.add_ore(&new_ent, self.ore_type);
         GameObject::Ore(new_ent, Ore::new(self.ore_type, self.richness))
     }
-    pub fn draw(&self, ent: &mut Ent, canvas: &mut Canvas<Window>) {
-        // If dead, return early
-        if ent.hp <= 0.0 {
-            return {};
-        }
-        // If selected, draw selection border
-        if ent.selected() {
-            let border_color = YELLOW_RGBA_WEAK;
-            draw_rect_selection_border(canvas, &ent.get_rect(), border_color);
-        }
 
-        // Draw self (if alive)
-        canvas.set_draw_color(ent.color);
-        let rect = ent.get_rect();
-        canvas.fill_rect(rect).ok();
-        canvas.set_draw_color(BLACK_RGB);
-        canvas.draw_rect(rect).ok();
+    pub fn hash_state(&self, hasher: &mut StateHasher) {
+        self.ore_type.hash(hasher);
+        hasher.write_u32(self.density);
+        hasher.write_f32(self.richness);
+    }
+}
+
+persist_enum!(OreType { Blue });
+
+impl Persist for OrePatch {
+    fn save(&self, writer: &mut SaveWriter) {
+        writer.write_label("ore_patch");
+        self.ore_type.save(writer);
+        self.density.save(writer);
+        self.richness.save(writer);
+    }
+
+    fn load(reader: &mut SaveReader) -> Result<Self, String> {
+        reader.expect_label("ore_patch")?;
+        Ok(Self {
+            ore_type: OreType::load(reader)?,
+            density: u32::load(reader)?,
+            richness: f32::load(reader)?,
+        })
     }
 }
@@ -0,0 +1,145 @@
+use std::collections::BTreeMap;
+use std::hash::{Hash, Hasher};
+
+use crate::consts::values::PLAYER_INCOME_WINDOW_TICKS;
+
+use super::{
+    ore_patch::OreType,
+    save_file::{Persist, SaveReader, SaveWriter},
+    state_hash::StateHasher,
+};
+
+// Everything an owner has going on besides its ents: resources, income and supply
+// Every change to the stockpile goes through deposit / spend / refund, so that
+// resources always equal gathered + refunded - spent (per ore type)
+#[derive(Clone)]
+pub struct PlayerState {
+    resources: BTreeMap<OreType, f32>, // Current stockpile
+    gathered: BTreeMap<OreType, f32>,  // Everything ever deposited
+    spent: BTreeMap<OreType, f32>,     // Everything ever spent
+    refunded: BTreeMap<OreType, f32>,  // Everything ever given back (i.e. cancelled production)
+    income_this_window: f32,           // Ore deposited since the current income window started
+    pub income_per_minute: f32, // Ore deposited during the last full income window, scaled to a minute
+    pub supply_used: u32,
+    pub supply_cap: u32,
+}
+
+impl Default for PlayerState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PlayerState {
+    pub fn new() -> Self {
+        Self {
+            resources: BTreeMap::new(),
+            gathered: BTreeMap::new(),
+            spent: BTreeMap::new(),
+            refunded: BTreeMap::new(),
+            income_this_window: 0.0,
+            income_per_minute: 0.0,
+            supply_used: 0,
+            supply_cap: 0,
+        }
+    }
+
+    pub fn resources(&self, ore_type: OreType) -> f32 {
+        self.resources.get(&ore_type).copied().unwrap_or(0.0)
+    }
+
+    pub fn gathered(&self, ore_type: OreType) -> f32 {
+        self.gathered.get(&ore_type).copied().unwrap_or(0.0)
+    }
+
+    pub fn spent(&self, ore_type: OreType) -> f32 {
+        self.spent.get(&ore_type).copied().unwrap_or(0.0)
+    }
+
+    pub fn refunded(&self, ore_type: OreType) -> f32 {
+        self.refunded.get(&ore_type).copied().unwrap_or(0.0)
+    }
+
+    pub fn deposit(&mut self, ore_type: OreType, amount: f32) {
+        *self.resources.entry(ore_type).or_insert(0.0) += amount;
+        *self.gathered.entry(ore_type).or_insert(0.0) += amount;
+        self.income_this_window += amount;
+    }
+
+    pub fn can_afford(&self, cost: &[(OreType, f32)]) -> bool {
+        cost.iter()
+            .all(|(ore_type, amount)| self.resources(*ore_type) >= *amount)
+    }
+
+    // Takes a cost out of the stockpile; Either all of it gets paid, or none of it
+    pub fn spend(&mut self, cost: &[(OreType, f32)]) -> Result<(), String> {
+        if !self.can_afford(cost) {
+            return Err("not enough resources".to_owned());
+        }
+        for (ore_type, amount) in cost {
+            *self.resources.entry(*ore_type).or_insert(0.0) -= amount;
+            *self.spent.entry(*ore_type).or_insert(0.0) += amount;
+        }
+        Ok(())
+    }
+
+    pub fn refund(&mut self, cost: &[(OreType, f32)]) {
+        for (ore_type, amount) in cost {
+            *self.resources.entry(*ore_type).or_insert(0.0) += amount;
+            *self.refunded.entry(*ore_type).or_insert(0.0) += amount;
+        }
+    }
+
+    pub fn has_supply_for(&self, supply: u32) -> bool {
+        self.supply_used + supply <= self.supply_cap
+    }
+
+    // Should be called once every income window; Rolls the window over
+    pub fn update_income_rate(&mut self) {
+        self.income_per_minute =
+            self.income_this_window * (60.0 * 60.0) / PLAYER_INCOME_WINDOW_TICKS as f32;
+        self.income_this_window = 0.0;
+    }
+
+    pub fn hash_state(&self, hasher: &mut StateHasher) {
+        for ledger in [&self.resources, &self.gathered, &self.spent, &self.refunded] {
+            hasher.write_usize(ledger.len());
+            for (ore_type, amount) in ledger {
+                ore_type.hash(hasher);
+                hasher.write_f32(*amount);
+            }
+        }
+        hasher.write_f32(self.income_this_window);
+        hasher.write_f32(self.income_per_minute);
+        hasher.write_u32(self.supply_used);
+        hasher.write_u32(self.supply_cap);
+    }
+}
+
+impl Persist for PlayerState {
+    fn save(&self, writer: &mut SaveWriter) {
+        writer.write_label("player_state");
+        self.resources.save(writer);
+        self.gathered.save(writer);
+        self.spent.save(writer);
+        self.refunded.save(writer);
+        self.income_this_window.save(writer);
+        self.income_per_minute.save(writer);
+        self.supply_used.save(writer);
+        self.supply_cap.save(writer);
+    }
+
+    fn load(reader: &mut SaveReader) -> Result<Self, String> {
+        reader.expect_label("player_state")?;
+        Ok(Self {
+            resources: BTreeMap::<OreType, f32>::load(reader)?,
+            gathered: BTreeMap::<OreType, f32>::load(reader)?,
+            spent: BTreeMap::<OreType, f32>::load(reader)?,
+            refunded: BTreeMap::<OreType, f32>::load(reader)?,
+            income_this_window: f32::load(reader)?,
+            income_per_minute: f32::load(reader)?,
+            supply_used: u32::load(reader)?,
+            supply_cap: u32::load(reader)?,
+        })
+    }
+}
@@ -0,0 +1,154 @@
+use std::hash::Hash;
+
+use sdl2::rect::Point;
+use vector2d::Vector2D;
+
+use crate::consts::values::TIME_STEP;
+
+use super::{
+    ent::EntID,
+    save_file::{persist_enum, Persist, SaveReader, SaveWriter},
+    state_hash::StateHasher,
+    weapon::Shot,
+    world_info::WorldInfo,
+};
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash)]
+pub enum ProjectileMotion {
+    Homing,    // Follows its target around; Fizzles out if the target dies before it lands
+    Ballistic, // Flies to where the target was when it got fired; Misses if the target is not there anymore
+}
+
+// How the shots of a weapon travel, for weapons that do not hit instantly
+#[derive(Copy, Clone)]
+pub struct ProjectileKind {
+    pub speed: f32, // How fast it flies
+    pub motion: ProjectileMotion,
+}
+
+// A shot on its way from attacker to target; Deals its damage on impact
+pub struct Projectile {
+    pub shot: Shot,
+    pub kind: ProjectileKind,
+    pub target_id: EntID,
+    pub origin: Vector2D<f32>,            // Where it got fired from
+    pub position: Vector2D<f32>,          // Where it is right now
+    pub previous_position: Vector2D<f32>, // Where it was before this tick, so rendering can interpolate
+    pub destination: Vector2D<f32>,       // Where it is going to land
+}
+
+impl Projectile {
+    pub fn new(
+        shot: Shot,
+        kind: ProjectileKind,
+        target_id: EntID,
+        origin: Vector2D<f32>,
+        destination: Vector2D<f32>,
+    ) -> Self {
+        Self {
+            shot,
+            kind,
+            target_id,
+            origin,
+            position: origin,
+            previous_position: origin,
+            destination,
+        }
+    }
+
+    // Flies one tick further; Returns whether it is still in the air afterwards
+    pub fn tick(&mut self, world_info: &mut WorldInfo) -> bool {
+        self.previous_position = self.position;
+
+        if self.kind.motion == ProjectileMotion::Homing {
+            match world_info.get_ent_rect_center_poisition_by_id(self.target_id) {
+                Some(target_position) => self.destination = target_position,
+                // Target died first, fizzle out
+                None => return false,
+            }
+        }
+
+        let to_destination = self.destination - self.position;
+        let distance = to_destination.length();
+        let step = self.kind.speed * TIME_STEP;
+        if distance > step {
+            self.position += to_destination / distance * step;
+            return true;
+        }
+
+        // Impact
+        self.position = self.destination;
+        let hit_target = match self.kind.motion {
+            ProjectileMotion::Homing => true,
+            ProjectileMotion::Ballistic => world_info
+                .get_ent_rect_by_id(self.target_id)
+                .is_some_and(|target_rect| {
+                    target_rect
+                        .contains_point(Point::new(self.position.x as i32, self.position.y as i32))
+                }),
+        };
+        self.shot.land(
+            hit_target.then_some(self.target_id),
+            Some(self.position),
+            world_info,
+        );
+        false
+    }
+
+    pub fn get_render_position(&self, alpha: f32) -> Vector2D<f32> {
+        Vector2D::<f32>::lerp(self.previous_position, self.position, alpha)
+    }
+
+    pub fn hash_state(&self, hasher: &mut StateHasher) {
+        self.shot.hash_state(hasher);
+        hasher.write_f32(self.kind.speed);
+        self.kind.motion.hash(hasher);
+        self.target_id.hash(hasher);
+        hasher.write_vector(self.origin);
+        hasher.write_vector(self.position);
+        hasher.write_vector(self.previous_position);
+        hasher.write_vector(self.destination);
+    }
+}
+
+persist_enum!(ProjectileMotion { Homing, Ballistic });
+
+impl Persist for ProjectileKind {
+    fn save(&self, writer: &mut SaveWriter) {
+        self.speed.save(writer);
+        self.motion.save(writer);
+    }
+
+    fn load(reader: &mut SaveReader) -> Result<Self, String> {
+        Ok(Self {
+            speed: f32::load(reader)?,
+            motion: ProjectileMotion::load(reader)?,
+        })
+    }
+}
+
+impl Persist for Projectile {
+    fn save(&self, writer: &mut SaveWriter) {
+        writer.write_label("projectile");
+        self.shot.save(writer);
+        self.kind.save(writer);
+        self.target_id.save(writer);
+        self.origin.save(writer);
+        self.position.save(writer);
+        self.previous_position.save(writer);
+        self.destination.save(writer);
+    }
+
+    fn load(reader: &mut SaveReader) -> Result<Self, String> {
+        reader.expect_label("projectile")?;
+        Ok(Self {
+            shot: Shot::load(reader)?,
+            kind: ProjectileKind::load(reader)?,
+            target_id: EntID::load(reader)?,
+            origin: Vector2D::<f32>::load(reader)?,
+            position: Vector2D::<f32>::load(reader)?,
+            previous_position: Vector2D::<f32>::load(reader)?,
+            destination: Vector2D::<f32>::load(reader)?,
+        })
+    }
+}
@@ -0,0 +1,254 @@
+use std::fs;
+
+use crate::consts::{
+    setup::Scenario,
+    values::{
+        REPLAY_FILE_HEADER, REPLAY_FILE_VERSION, REPLAY_MAX_SPEED, REPLAY_STATE_HASH_INTERVAL,
+    },
+};
+
+use crate::enums::command::Command;
+
+use super::{
+    ai_player::AiDifficulty,
+    save_file::{Persist, SaveReader, SaveWriter},
+    world::World,
+    world_info::WorldInfo,
+};
+
+// A command, along with the tick it got applied on (before that tick ran)
+pub struct RecordedCommand {
+    pub tick: u64,
+    pub command: Command,
+}
+
+// World state hash taken right after a given tick ran
+// Used to detect when a replay stops matching its recording
+pub struct StateHashCheckpoint {
+    pub tick: u64,
+    pub hash: u64,
+}
+
+// Everything needed to re-simulate a match: how it started, and every command that went into it
+// The simulation is deterministic, so this is enough to get the exact same match back
+pub struct Replay {
+    pub seed: u64,
+    pub debug_ent_count: i32,
+    pub ai_difficulty: Option<AiDifficulty>,
+    pub commands: Vec<RecordedCommand>,
+    pub state_hashes: Vec<StateHashCheckpoint>,
+    pub end_tick: u64,
+}
+
+impl Replay {
+    pub fn new(scenario: &Scenario) -> Self {
+        Self {
+            seed: scenario.seed,
+            debug_ent_count: scenario.debug_ent_count,
+            ai_difficulty: scenario.ai_difficulty,
+            commands: Vec::new(),
+            state_hashes: Vec::new(),
+            end_tick: 0,
+        }
+    }
+
+    pub fn scenario(&self) -> Scenario {
+        Scenario::new(self.seed, self.debug_ent_count, self.ai_difficulty)
+    }
+
+    pub fn record_command(&mut self, tick: u64, command: &Command) {
+        self.commands.push(RecordedCommand {
+            tick,
+            command: command.clone(),
+        });
+    }
+
+    // Should be called right after every world tick while recording
+    pub fn record_tick(&mut self, world: &World, world_info: &WorldInfo) {
+        self.end_tick = world.current_tick;
+        if world
+            .current_tick
+            .is_multiple_of(REPLAY_STATE_HASH_INTERVAL)
+        {
+            self.state_hashes.push(StateHashCheckpoint {
+                tick: world.current_tick,
+                hash: world.state_hash(world_info),
+            });
+        }
+    }
+
+    pub fn save(&self, path: &str) -> Result<(), String> {
+        let mut writer = SaveWriter::new();
+        writer.write_token(REPLAY_FILE_HEADER);
+        REPLAY_FILE_VERSION.save(&mut writer);
+        self.seed.save(&mut writer);
+        self.debug_ent_count.save(&mut writer);
+        self.ai_difficulty.save(&mut writer);
+        self.end_tick.save(&mut writer);
+        writer.write_label("commands");
+        self.commands.save(&mut writer);
+        writer.write_label("state_hashes");
+        self.state_hashes.save(&mut writer);
+        fs::write(path, writer.finish()).map_err(|e| e.to_string())
+    }
+
+    pub fn load(path: &str) -> Result<Self, String> {
+        let input = fs::read_to_string(path).map_err(|e| e.to_string())?;
+        let mut reader = SaveReader::new(&input);
+        reader.expect_label(REPLAY_FILE_HEADER)?;
+        let version: u32 = reader.parse_token()?;
+        if version != REPLAY_FILE_VERSION {
+            return Err(format!(
+                "unsupported replay file version {version} (expected {REPLAY_FILE_VERSION})"
+            ));
+        }
+        let seed = u64::load(&mut reader)?;
+        let debug_ent_count = i32::load(&mut reader)?;
+        let ai_difficulty = Option::<AiDifficulty>::load(&mut reader)?;
+        let end_tick = u64::load(&mut reader)?;
+        reader.expect_label("commands")?;
+        let commands = Vec::<RecordedCommand>::load(&mut reader)?;
+        reader.expect_label("state_hashes")?;
+        let state_hashes = Vec::<StateHashCheckpoint>::load(&mut reader)?;
+        Ok(Self {
+            seed,
+            debug_ent_count,
+            ai_difficulty,
+            commands,
+            state_hashes,
+            end_tick,
+        })
+    }
+}
+
+impl Persist for RecordedCommand {
+    fn save(&self, writer: &mut SaveWriter) {
+        self.tick.save(writer);
+        self.command.save(writer);
+    }
+
+    fn load(reader: &mut SaveReader) -> Result<Self, String> {
+        Ok(Self {
+            tick: u64::load(reader)?,
+            command: Command::load(reader)?,
+        })
+    }
+}
+
+impl Persist for StateHashCheckpoint {
+    fn save(&self, writer: &mut SaveWriter) {
+        self.tick.save(writer);
+        self.hash.save(writer);
+    }
+
+    fn load(reader: &mut SaveReader) -> Result<Self, String> {
+        Ok(Self {
+            tick: u64::load(reader)?,
+            hash: u64::load(reader)?,
+        })
+    }
+}
+
+// Plays a Replay back by feeding its commands to a fresh world at the recorded ticks
+pub struct ReplayPlayer {
+    pub replay: Replay,
+    next_command: usize,
+    next_checkpoint: usize,
+    pub paused: bool,
+    pub speed: u32,
+    pub divergence: Option<u64>, // First tick whose state hash did not match the recording
+}
+
+impl ReplayPlayer {
+    pub fn new(replay: Replay) -> Self {
+        Self {
+            replay,
+            next_command: 0,
+            next_checkpoint: 0,
+            paused: false,
+            speed: 1,
+            divergence: None,
+        }
+    }
+
+    // Rewinds playback and builds the world the replay starts from
+    pub fn start(&mut self) -> (World, WorldInfo) {
+        self.next_command = 0;
+        self.next_checkpoint = 0;
+        self.divergence = None;
+        self.replay.scenario().setup()
+    }
+
+    pub fn is_finished(&self, world: &World) -> bool {
+        world.current_tick >= self.replay.end_tick
+    }
+
+    // Applies the commands recorded for the current tick, runs it, then checks it against the recording
+    pub fn step(&mut self, world: &mut World, world_info: &mut WorldInfo) {
+        while let Some(recorded) = self.replay.commands.get(self.next_command) {
+            if recorded.tick > world.current_tick {
+                break;
+            }
+            world.apply_command(&recorded.command, world_info);
+            self.next_command += 1;
+        }
+
+        world.tick(world_info);
+
+        while let Some(checkpoint) = self.replay.state_hashes.get(self.next_checkpoint) {
+            if checkpoint.tick > world.current_tick {
+                break;
+            }
+            if self.divergence.is_none() && checkpoint.hash != world.state_hash(world_info) {
+                self.divergence = Some(checkpoint.tick);
+            }
+            self.next_checkpoint += 1;
+        }
+    }
+
+    // Runs the ticks a frame asked for, scaled by playback speed
+    pub fn advance(&mut self, world: &mut World, world_info: &mut WorldInfo, ticks: u32) {
+        if self.paused {
+            return;
+        }
+        for _ in 0..ticks * self.speed {
+            if self.is_finished(world) {
+                break;
+            }
+            self.step(world, world_info);
+        }
+    }
+
+    // Jumps to a given tick
+    // Ticks can only run forward, so seeking backwards re-simulates from the start
+    pub fn seek(&mut self, world: &mut World, world_info: &mut WorldInfo, target_tick: u64) {
+        let target_tick = target_tick.min(self.replay.end_tick);
+        if target_tick < world.current_tick {
+            (*world, *world_info) = self.start();
+        }
+        while world.current_tick < target_tick {
+            self.step(world, world_info);
+        }
+    }
+
+    pub fn toggle_pause(&mut self) {
+        self.paused = !self.paused;
+    }
+
+    pub fn speed_up(&mut self) {
+        self.speed = (self.speed * 2).min(REPLAY_MAX_SPEED);
+    }
+
+    pub fn slow_down(&mut self) {
+        self.speed = (self.speed / 2).max(1);
+    }
+
+    // Paused playback should not interpolate towards a tick that is not coming
+    pub fn render_alpha(&self, alpha: f32) -> f32 {
+        if self.paused {
+            1.0
+        } else {
+            alpha
+        }
+    }
+}
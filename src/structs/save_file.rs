@@ -0,0 +1,299 @@
+use std::collections::BTreeMap;
+use std::fs;
+
+use sdl2::{
+    pixels::Color,
+    rect::{Point, Rect},
+};
+use vector2d::Vector2D;
+
+use crate::consts::values::{SAVE_FILE_HEADER, SAVE_FILE_VERSION};
+
+use super::{camera::Camera, world::World, world_info::WorldInfo};
+
+// Anything that can be written to and read back from a save file
+pub trait Persist: Sized {
+    fn save(&self, writer: &mut SaveWriter);
+    fn load(reader: &mut SaveReader) -> Result<Self, String>;
+}
+
+// Builds a save file as a stream of whitespace separated tokens
+// Floats are stored by their bit pattern, so loading gives back the exact same state
+pub struct SaveWriter {
+    output: String,
+}
+
+impl Default for SaveWriter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SaveWriter {
+    pub fn new() -> Self {
+        Self {
+            output: String::new(),
+        }
+    }
+
+    pub fn write_token(&mut self, token: &str) {
+        if !self.output.is_empty() && !self.output.ends_with('\n') {
+            self.output.push(' ');
+        }
+        self.output.push_str(token);
+    }
+
+    // Labels are only there to catch malformed files (and to make them readable)
+    pub fn write_label(&mut self, label: &str) {
+        if !self.output.is_empty() {
+            self.output.push('\n');
+        }
+        self.write_token(label);
+    }
+
+    pub fn finish(self) -> String {
+        self.output + "\n"
+    }
+}
+
+// Reads back a token stream produced by a SaveWriter
+pub struct SaveReader<'a> {
+    tokens: std::str::SplitWhitespace<'a>,
+    pub version: u32,
+}
+
+impl<'a> SaveReader<'a> {
+    pub fn new(input: &'a str) -> Self {
+        Self {
+            tokens: input.split_whitespace(),
+            version: SAVE_FILE_VERSION,
+        }
+    }
+
+    pub fn next_token(&mut self) -> Result<&'a str, String> {
+        self.tokens
+            .next()
+            .ok_or_else(|| "unexpected end of save file".to_owned())
+    }
+
+    pub fn parse_token<T: std::str::FromStr>(&mut self) -> Result<T, String> {
+        let token = self.next_token()?;
+        token
+            .parse::<T>()
+            .map_err(|_| format!("invalid token in save file: {token}"))
+    }
+
+    pub fn expect_label(&mut self, label: &str) -> Result<(), String> {
+        let token = self.next_token()?;
+        if token != label {
+            return Err(format!("expected {label} in save file, found {token}"));
+        }
+        Ok(())
+    }
+
+    // Reads an enum variant index, checking it against the amount of variants
+    pub fn read_variant(&mut self, variant_count: u8) -> Result<u8, String> {
+        let variant: u8 = self.parse_token()?;
+        if variant >= variant_count {
+            return Err(format!("invalid enum variant in save file: {variant}"));
+        }
+        Ok(variant)
+    }
+}
+
+// Implements Persist for a fieldless enum, storing the variant index
+macro_rules! persist_enum {
+    ($t:ident { $($variant:ident),* $(,)? }) => {
+        impl $crate::structs::save_file::Persist for $t {
+            fn save(&self, writer: &mut $crate::structs::save_file::SaveWriter) {
+                (*self as u8).save(writer);
+            }
+
+            fn load(reader: &mut $crate::structs::save_file::SaveReader) -> Result<Self, String> {
+                const VARIANTS: &[$t] = &[$($t::$variant),*];
+                let variant = reader.read_variant(VARIANTS.len() as u8)?;
+                Ok(VARIANTS[variant as usize])
+            }
+        }
+    };
+}
+pub(crate) use persist_enum;
+
+macro_rules! persist_by_display {
+    ($($t:ty),*) => {
+        $(
+            impl Persist for $t {
+                fn save(&self, writer: &mut SaveWriter) {
+                    writer.write_token(&self.to_string());
+                }
+
+                fn load(reader: &mut SaveReader) -> Result<Self, String> {
+                    reader.parse_token()
+                }
+            }
+        )*
+    };
+}
+
+persist_by_display!(bool, u8, u32, u64, i32, usize);
+
+impl Persist for f32 {
+    fn save(&self, writer: &mut SaveWriter) {
+        writer.write_token(&self.to_bits().to_string());
+    }
+
+    fn load(reader: &mut SaveReader) -> Result<Self, String> {
+        Ok(f32::from_bits(reader.parse_token()?))
+    }
+}
+
+impl<T: Persist> Persist for Option<T> {
+    fn save(&self, writer: &mut SaveWriter) {
+        match self {
+            Some(value) => {
+                true.save(writer);
+                value.save(writer);
+            }
+            None => false.save(writer),
+        }
+    }
+
+    fn load(reader: &mut SaveReader) -> Result<Self, String> {
+        if bool::load(reader)? {
+            Ok(Some(T::load(reader)?))
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+impl<T: Persist> Persist for Vec<T> {
+    fn save(&self, writer: &mut SaveWriter) {
+        self.len().save(writer);
+        for value in self {
+            value.save(writer);
+        }
+    }
+
+    fn load(reader: &mut SaveReader) -> Result<Self, String> {
+        let len = usize::load(reader)?;
+        let mut values = Vec::<T>::with_capacity(len);
+        for _ in 0..len {
+            values.push(T::load(reader)?);
+        }
+        Ok(values)
+    }
+}
+
+impl<K: Persist + Ord, V: Persist> Persist for BTreeMap<K, V> {
+    fn save(&self, writer: &mut SaveWriter) {
+        self.len().save(writer);
+        for (key, value) in self {
+            key.save(writer);
+            value.save(writer);
+        }
+    }
+
+    fn load(reader: &mut SaveReader) -> Result<Self, String> {
+        let len = usize::load(reader)?;
+        let mut map = BTreeMap::<K, V>::new();
+        for _ in 0..len {
+            let key = K::load(reader)?;
+            map.insert(key, V::load(reader)?);
+        }
+        Ok(map)
+    }
+}
+
+impl<T: Persist + Copy> Persist for Vector2D<T> {
+    fn save(&self, writer: &mut SaveWriter) {
+        self.x.save(writer);
+        self.y.save(writer);
+    }
+
+    fn load(reader: &mut SaveReader) -> Result<Self, String> {
+        Ok(Vector2D::<T>::new(T::load(reader)?, T::load(reader)?))
+    }
+}
+
+impl Persist for Point {
+    fn save(&self, writer: &mut SaveWriter) {
+        self.x.save(writer);
+        self.y.save(writer);
+    }
+
+    fn load(reader: &mut SaveReader) -> Result<Self, String> {
+        Ok(Point::new(i32::load(reader)?, i32::load(reader)?))
+    }
+}
+
+impl Persist for Rect {
+    fn save(&self, writer: &mut SaveWriter) {
+        self.x.save(writer);
+        self.y.save(writer);
+        self.width().save(writer);
+        self.height().save(writer);
+    }
+
+    fn load(reader: &mut SaveReader) -> Result<Self, String> {
+        Ok(Rect::new(
+            i32::load(reader)?,
+            i32::load(reader)?,
+            u32::load(reader)?,
+            u32::load(reader)?,
+        ))
+    }
+}
+
+impl Persist for Color {
+    fn save(&self, writer: &mut SaveWriter) {
+        self.r.save(writer);
+        self.g.save(writer);
+        self.b.save(writer);
+        self.a.save(writer);
+    }
+
+    fn load(reader: &mut SaveReader) -> Result<Self, String> {
+        Ok(Color::RGBA(
+            u8::load(reader)?,
+            u8::load(reader)?,
+            u8::load(reader)?,
+            u8::load(reader)?,
+        ))
+    }
+}
+
+// Writes the whole game state (World + WorldInfo + Camera) to disk
+pub fn save_game(
+    path: &str,
+    world: &World,
+    world_info: &WorldInfo,
+    camera: &Camera,
+) -> Result<(), String> {
+    let mut writer = SaveWriter::new();
+    writer.write_token(SAVE_FILE_HEADER);
+    SAVE_FILE_VERSION.save(&mut writer);
+    world.save_with_info(&mut writer, world_info);
+    writer.write_label("camera");
+    camera.save(&mut writer);
+    fs::write(path, writer.finish()).map_err(|e| e.to_string())
+}
+
+// Reads the whole game state back from disk
+// WorldInfo gets rebuilt from the loaded game objects
+pub fn load_game(path: &str) -> Result<(World, WorldInfo, Camera), String> {
+    let input = fs::read_to_string(path).map_err(|e| e.to_string())?;
+    let mut reader = SaveReader::new(&input);
+    reader.expect_label(SAVE_FILE_HEADER)?;
+    let version: u32 = reader.parse_token()?;
+    if version != SAVE_FILE_VERSION {
+        return Err(format!(
+            "unsupported save file version {version} (expected {SAVE_FILE_VERSION})"
+        ));
+    }
+    reader.version = version;
+    let (world, world_info) = World::load_with_info(&mut reader)?;
+    reader.expect_label("camera")?;
+    let camera = Camera::load(&mut reader)?;
+    Ok((world, world_info, camera))
+}
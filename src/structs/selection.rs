@@ -1,59 +1,65 @@
 use sdl2::rect::Point;
 use sdl2::rect::Rect;
-use sdl2::render::BlendMode;
-use sdl2::render::Canvas;
-use sdl2::video::Window;
 
-use crate::consts::helper::find_selection_box_translation;
-use crate::consts::values::SELECTION_BOX_COLOR;
+use crate::consts::helper::{find_selection_box_translation, structure_rect_size};
+use crate::consts::values::CLICK_SELECT_MAX_DRAG;
 use crate::enums::game_object::GameObject;
+use crate::enums::unit_type::UnitType;
 
+use super::control_group::ControlGroups;
 use super::ent::Ent;
+use super::ent::EntID;
+use super::ent::EntParentType;
 use super::ent::Owner;
+use super::structure::StructureParentType;
+use super::unit::UnitParentType;
 
+#[derive(Copy, Clone, PartialEq)]
 pub enum MouseCommand {
     Select,
     Action,
+    Patrol,
+    Place(StructureParentType), // Placing down a structure; Shows its footprint under the mouse
 }
 
 // This resource tracks the current selection of units and structures
 pub struct Selection {
     pub open: bool,
-    pub just_closed: bool,
     pub queueing: bool,
-    pub clearing: bool,
+    pub same_type: bool, // Ctrl is held; Clicking a unit grabs every unit of its type on screen
     pub origin: Point,
     pub center: Point,
     pub selection_box: Rect,
     pub left_click_command: MouseCommand,
+    pub mouse_position: Point, // Last known (scaled) mouse position
+    pub control_groups: ControlGroups,
+    pub formation_origin: Option<Point>, // Where the right drag picking a formation's facing started, if one is going on
+}
+
+impl Default for Selection {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Selection {
     pub fn new() -> Self {
         Self {
             open: false,
-            just_closed: false,
-            clearing: false,
             origin: Point::new(-1, -1),
             center: Point::new(-1, -1),
             selection_box: Rect::new(-1, -1, 0, 0),
             queueing: false,
+            same_type: false,
             left_click_command: MouseCommand::Select,
+            mouse_position: Point::new(-1, -1),
+            control_groups: ControlGroups::new(),
+            formation_origin: None,
         }
     }
-    pub fn tick(&mut self, mouse_position: Point, game_objects: &mut Vec<GameObject>) {
-        if self.clearing {
-            for game_object in game_objects {
-                match game_object {
-                    GameObject::Unit(ent, _)
-                    | GameObject::Structure(ent, _)
-                    | GameObject::OrePatch(ent, _)
-                    | GameObject::Ore(ent, _) => ent.deselect(),
-                }
-            }
-            self.clearing = false;
-            self.origin = mouse_position;
-        } else if self.open {
+    pub fn tick(&mut self, mouse_position: Point) {
+        self.mouse_position = mouse_position;
+        if self.open {
             let new_pos = find_selection_box_translation(mouse_position, self.origin);
             self.selection_box.set_x(new_pos.x);
             self.selection_box.set_y(new_pos.y);
@@ -62,91 +68,153 @@ impl Selection {
             self.selection_box
                 .set_height((mouse_position.y - self.origin.y).unsigned_abs());
             self.center = self.selection_box.center();
-        } else if self.just_closed {
-            // TODO: Differentiate bewteen at least one unit selected and at least one structure selected
-            //        => prioritize selecting only units, like in SCII
-            let mut at_least_one_selected = false;
-            let mut at_least_one_from_player_selected = false;
-            let mut ents_to_select: Vec<&mut Ent> = Vec::<&mut Ent>::new();
-            let mut ents_to_deselect: Vec<&mut Ent> = Vec::<&mut Ent>::new();
-            for game_object in game_objects {
-                match game_object {
-                    GameObject::Unit(ent, _)
-                    | GameObject::Structure(ent, _)
-                    | GameObject::OrePatch(ent, _)
-                    | GameObject::Ore(ent, _) => {
-                        let possible_intersection = ent.get_rect().intersection(self.selection_box);
-                        if possible_intersection.is_some() {
-                            // Flag that this selection grabbed at least one ent
-                            at_least_one_selected = true;
-                            // Check if this ent is player-controlled
-                            if ent.owner == Owner::Player {
-                                at_least_one_from_player_selected = true;
-                            }
-                            // Mark this unit as selectable
-                            ents_to_select.push(ent);
-                        } else {
-                            ents_to_deselect.push(ent);
-                        }
-                    }
-                }
-            }
-            // If at least one ent was grabbed by selection, and not queueing, deselect current selection (if any)
-            if at_least_one_selected {
-                for ent in ents_to_deselect {
-                    if !self.queueing {
-                        ent.deselect();
-                    }
-                }
-            }
-            for ent in ents_to_select {
-                if ent.owner == Owner::Player || !at_least_one_from_player_selected {
-                    ent.select();
-                } else {
-                    ent.deselect();
-                }
-            }
-            self.just_closed = false;
-            self.origin = mouse_position;
         } else {
             self.origin = mouse_position;
         }
     }
 
-    pub fn draw(&self, canvas: &mut Canvas<Window>) {
+    pub fn open(&mut self, mouse_position: Point) {
+        self.open = true;
+        self.tick(mouse_position);
+    }
+
+    // Whether the selection box is small enough to count as a single click rather than a drag
+    pub fn is_click(&self) -> bool {
+        self.selection_box.width() <= CLICK_SELECT_MAX_DRAG
+            && self.selection_box.height() <= CLICK_SELECT_MAX_DRAG
+    }
+
+    // Closes the selection box, returning the ents it grabbed
+    // A click only ever grabs the single ent under the mouse
+    // The selection itself gets applied through a Command::Select
+    pub fn close(&mut self, mouse_position: Point, game_objects: &[GameObject]) -> Vec<EntID> {
         if !self.open {
-            return {};
+            self.tick(mouse_position);
+            return Vec::<EntID>::new();
+        }
+        let is_click = self.is_click();
+        self.open = false;
+        self.origin = mouse_position;
+
+        let mut ents_to_select: Vec<&Ent> = Vec::<&Ent>::new();
+        for game_object in game_objects {
+            match game_object {
+                GameObject::Unit(ent, _)
+                | GameObject::Structure(ent, _)
+                | GameObject::OrePatch(ent, _)
+                | GameObject::Ore(ent, _) => {
+                    let grabbed = if is_click {
+                        ent.get_rect().contains_point(mouse_position)
+                    } else {
+                        ent.get_rect().has_intersection(self.selection_box)
+                    };
+                    if grabbed {
+                        ents_to_select.push(ent);
+                    }
+                }
+            }
+        }
+        // Player units take priority, then player structures; Only grab other ents if there are none
+        let Some(best_priority) = ents_to_select
+            .iter()
+            .map(|ent| Self::selection_priority(ent))
+            .min()
+        else {
+            return Vec::<EntID>::new();
         };
-        canvas.set_blend_mode(BlendMode::Blend);
-        canvas.set_draw_color(SELECTION_BOX_COLOR);
-        canvas.fill_rect(self.selection_box).ok();
-        canvas.set_blend_mode(BlendMode::None);
+        let mut picked = ents_to_select
+            .iter()
+            .filter(|ent| Self::selection_priority(ent) == best_priority)
+            .map(|ent| ent.id);
+        if is_click {
+            // Whatever got drawn last sits on top
+            return picked.next_back().into_iter().collect();
+        }
+        picked.collect()
     }
 
-    pub fn open(&mut self, mouse_position: Point, game_objects: &mut Vec<GameObject>) {
-        self.open = true;
-        self.tick(mouse_position, game_objects);
+    fn selection_priority(ent: &Ent) -> u8 {
+        match (ent.owner, ent.parent_type()) {
+            (Owner::Player, EntParentType::Unit) => 0,
+            (Owner::Player, EntParentType::Structure) => 1,
+            _ => 2,
+        }
     }
 
-    pub fn close(&mut self, mouse_position: Point, game_objects: &mut Vec<GameObject>) {
-        if self.open {
-            self.open = false;
-            self.just_closed = true;
+    // Every unit of the same type and owner as the given one that is within the given area (usually what is on screen)
+    pub fn same_type_in_area(ent_id: EntID, area: Rect, game_objects: &[GameObject]) -> Vec<EntID> {
+        let Some((owner, parent_type)) =
+            game_objects
+                .iter()
+                .find_map(|game_object| match game_object {
+                    GameObject::Unit(ent, unit_type) if ent.id == ent_id => {
+                        Some((ent.owner, Self::unit_parent_type(unit_type)))
+                    }
+                    _ => None,
+                })
+        else {
+            return vec![ent_id];
+        };
+        game_objects
+            .iter()
+            .filter_map(|game_object| match game_object {
+                GameObject::Unit(ent, unit_type)
+                    if ent.owner == owner
+                        && Self::unit_parent_type(unit_type) == parent_type
+                        && ent.get_rect().has_intersection(area) =>
+                {
+                    Some(ent.id)
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn unit_parent_type(unit_type: &UnitType) -> UnitParentType {
+        match unit_type {
+            UnitType::Scout(unit) | UnitType::Miner(unit) | UnitType::Collector(unit) => {
+                unit.parent_type()
+            }
         }
-        self.tick(mouse_position, game_objects);
     }
 
-    pub fn clear(&mut self, game_objects: &mut Vec<GameObject>) {
+    pub fn clear(&mut self) {
         self.open = false;
-        self.just_closed = false;
-        self.clearing = true;
-        self.tick(Point::new(-1, -1), game_objects);
+        self.formation_origin = None;
+    }
+
+    pub fn start_formation(&mut self, mouse_position: Point) {
+        self.formation_origin = Some(mouse_position);
+    }
+
+    // Facing of the formation being dragged out (if it got dragged far enough to count)
+    pub fn formation_facing(&self, mouse_position: Point) -> Option<Point> {
+        let facing = mouse_position - self.formation_origin?;
+        (facing.x.unsigned_abs() > CLICK_SELECT_MAX_DRAG
+            || facing.y.unsigned_abs() > CLICK_SELECT_MAX_DRAG)
+            .then_some(facing)
+    }
+
+    // Lets go of the formation being dragged out, returning where it goes and which way it faces
+    pub fn finish_formation(&mut self, mouse_position: Point) -> Option<(Point, Option<Point>)> {
+        let facing = self.formation_facing(mouse_position);
+        self.formation_origin
+            .take()
+            .map(|formation_origin| (formation_origin, facing))
     }
 
     pub fn shift_press(&mut self) {
         self.queueing = true;
     }
 
+    pub fn ctrl_press(&mut self) {
+        self.same_type = true;
+    }
+
+    pub fn ctrl_release(&mut self) {
+        self.same_type = false;
+    }
+
     pub fn shift_release(&mut self) {
         self.queueing = false;
         self.release_command();
@@ -158,6 +226,18 @@ impl Selection {
         }
     }
 
+    // Footprint of the structure being placed (if any), centered on the mouse
+    pub fn placement(&self) -> Option<(StructureParentType, Rect)> {
+        let MouseCommand::Place(structure_type) = self.left_click_command else {
+            return None;
+        };
+        let rect_size = structure_rect_size(structure_type) as u32;
+        Some((
+            structure_type,
+            Rect::from_center(self.mouse_position, rect_size, rect_size),
+        ))
+    }
+
     pub fn release_command(&mut self) {
         if !self.queueing {
             self.left_click_command = MouseCommand::Select;
@@ -0,0 +1,56 @@
+use rand::{Error, RngCore};
+
+use super::save_file::{Persist, SaveReader, SaveWriter};
+
+// Small seeded pseudo random number generator (SplitMix64) owned by the World
+// Its whole state is a single u64, so it can be hashed and persisted to get bit-identical runs
+#[derive(Copy, Clone, PartialEq, Eq, Hash)]
+pub struct SimRng {
+    state: u64,
+}
+
+impl SimRng {
+    pub const fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    pub const fn state(&self) -> u64 {
+        self.state
+    }
+}
+
+impl RngCore for SimRng {
+    fn next_u32(&mut self) -> u32 {
+        (self.next_u64() >> 32) as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        for chunk in dest.chunks_mut(8) {
+            let bytes = self.next_u64().to_le_bytes();
+            chunk.copy_from_slice(&bytes[..chunk.len()]);
+        }
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+impl Persist for SimRng {
+    fn save(&self, writer: &mut SaveWriter) {
+        self.state.save(writer);
+    }
+
+    fn load(reader: &mut SaveReader) -> Result<Self, String> {
+        Ok(Self::new(u64::load(reader)?))
+    }
+}
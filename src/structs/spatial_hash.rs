@@ -0,0 +1,93 @@
+use std::collections::BTreeMap;
+
+use sdl2::rect::Rect;
+
+use crate::consts::values::{MAP_HEIGHT, MAP_WIDTH, SPATIAL_HASH_CELL_SIZE};
+
+use super::ent::EntID;
+
+// Uniform grid over the map, bucketing ents by the cells their rect touches
+// Only a broadphase: queries return candidates, the caller checks the actual rects / distances
+// Ents outside of the map get bucketed into the border cells
+pub struct SpatialHash {
+    columns: i32,
+    rows: i32,
+    cells: Vec<Vec<EntID>>,
+    ent_cells: BTreeMap<EntID, (i32, i32, i32, i32)>, // Cell range (min column, min row, max column, max row) each ent is in
+}
+
+impl Default for SpatialHash {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SpatialHash {
+    pub fn new() -> Self {
+        let columns = (MAP_WIDTH as i32 + SPATIAL_HASH_CELL_SIZE - 1) / SPATIAL_HASH_CELL_SIZE;
+        let rows = (MAP_HEIGHT as i32 + SPATIAL_HASH_CELL_SIZE - 1) / SPATIAL_HASH_CELL_SIZE;
+        Self {
+            columns,
+            rows,
+            cells: vec![Vec::<EntID>::new(); (columns * rows) as usize],
+            ent_cells: BTreeMap::new(),
+        }
+    }
+
+    pub fn insert(&mut self, ent_id: EntID, rect: Rect) {
+        let cell_range = self.cell_range(rect);
+        for index in self.indices(cell_range) {
+            self.cells[index].push(ent_id);
+        }
+        self.ent_cells.insert(ent_id, cell_range);
+    }
+
+    // Only touches the buckets when the ent actually moved into other cells
+    pub fn update(&mut self, ent_id: EntID, rect: Rect) {
+        if self.ent_cells.get(&ent_id) == Some(&self.cell_range(rect)) {
+            return;
+        }
+        self.remove(ent_id);
+        self.insert(ent_id, rect);
+    }
+
+    pub fn remove(&mut self, ent_id: EntID) {
+        if let Some(cell_range) = self.ent_cells.remove(&ent_id) {
+            for index in self.indices(cell_range) {
+                let cell = &mut self.cells[index];
+                if let Some(position) = cell.iter().position(|id| *id == ent_id) {
+                    cell.swap_remove(position);
+                }
+            }
+        }
+    }
+
+    // Every ent in the cells touched by a rect, sorted by EntID
+    // Sorting keeps query results (and whatever gets done with them) deterministic
+    pub fn query(&self, rect: Rect) -> Vec<EntID> {
+        let mut ent_ids: Vec<EntID> = Vec::<EntID>::new();
+        for index in self.indices(self.cell_range(rect)) {
+            ent_ids.extend_from_slice(&self.cells[index]);
+        }
+        ent_ids.sort_unstable();
+        ent_ids.dedup();
+        ent_ids
+    }
+
+    fn cell_range(&self, rect: Rect) -> (i32, i32, i32, i32) {
+        (
+            (rect.left().div_euclid(SPATIAL_HASH_CELL_SIZE)).clamp(0, self.columns - 1),
+            (rect.top().div_euclid(SPATIAL_HASH_CELL_SIZE)).clamp(0, self.rows - 1),
+            (rect.right().div_euclid(SPATIAL_HASH_CELL_SIZE)).clamp(0, self.columns - 1),
+            (rect.bottom().div_euclid(SPATIAL_HASH_CELL_SIZE)).clamp(0, self.rows - 1),
+        )
+    }
+
+    fn indices(&self, cell_range: (i32, i32, i32, i32)) -> impl Iterator<Item = usize> {
+        let (min_column, min_row, max_column, max_row) = cell_range;
+        let columns = self.columns;
+        (min_row..=max_row).flat_map(move |row| {
+            (min_column..=max_column).map(move |column| (row * columns + column) as usize)
+        })
+    }
+}
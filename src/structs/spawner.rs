@@ -0,0 +1,134 @@
+use std::hash::{Hash, Hasher};
+
+use vector2d::Vector2D;
+
+use crate::consts::values::{
+    SPAWNER_AGGRESSION_DECAY, SPAWNER_AGGRESSION_THRESHOLD, SPAWNER_AGGRO_RADIUS,
+    SPAWNER_COOLDOWN_TICKS, SPAWNER_WAVE_SIZE,
+};
+
+use super::{
+    ent::EntID,
+    save_file::{Persist, SaveReader, SaveWriter},
+    state_hash::StateHasher,
+    unit::UnitParentType,
+};
+
+// Keeps an eye on the ore patches around a spawner structure
+// Damage dealt to them builds up aggression; Once there is enough of it, a wave of units gets sent after whoever did it
+pub struct Spawner {
+    pub aggression_threshold: f32, // How much aggression it takes to send out a wave
+    pub aggression_decay: f32,     // How much aggression wears off every tick
+    pub cooldown_ticks: u32,       // How long to wait between waves
+    pub wave_size: u32,            // How many units a wave is made of
+    pub wave_unit_type: UnitParentType,
+    pub aggro_radius: f32, // How far away (from the spawner's center) ore patches get watched over
+    aggression: f32,
+    cooldown_left: u32,
+    aggressor: Option<EntID>, // Whoever did the latest damage
+    aggressor_position: Option<Vector2D<f32>>, // Where the latest damage came from; Waves head here if the aggressor is gone
+}
+
+impl Default for Spawner {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Spawner {
+    pub fn new() -> Self {
+        Self {
+            aggression_threshold: SPAWNER_AGGRESSION_THRESHOLD,
+            aggression_decay: SPAWNER_AGGRESSION_DECAY,
+            cooldown_ticks: SPAWNER_COOLDOWN_TICKS,
+            wave_size: SPAWNER_WAVE_SIZE,
+            wave_unit_type: UnitParentType::Scout,
+            aggro_radius: SPAWNER_AGGRO_RADIUS,
+            aggression: 0.0,
+            cooldown_left: 0,
+            aggressor: None,
+            aggressor_position: None,
+        }
+    }
+
+    pub fn provoke(&mut self, damage: f32, aggressor: EntID, aggressor_position: Vector2D<f32>) {
+        self.aggression += damage;
+        self.aggressor = Some(aggressor);
+        self.aggressor_position = Some(aggressor_position);
+    }
+
+    // Cools down and calms down a bit; Returns whether a wave should be sent out this tick
+    pub fn tick(&mut self) -> bool {
+        self.aggression = (self.aggression - self.aggression_decay).max(0.0);
+        if self.cooldown_left > 0 {
+            self.cooldown_left -= 1;
+            return false;
+        }
+        if self.aggression < self.aggression_threshold || self.aggressor_position.is_none() {
+            return false;
+        }
+        self.aggression -= self.aggression_threshold;
+        self.cooldown_left = self.cooldown_ticks;
+        true
+    }
+
+    pub fn aggression(&self) -> f32 {
+        self.aggression
+    }
+
+    pub fn aggressor(&self) -> Option<EntID> {
+        self.aggressor
+    }
+
+    pub fn aggressor_position(&self) -> Option<Vector2D<f32>> {
+        self.aggressor_position
+    }
+
+    pub fn hash_state(&self, hasher: &mut StateHasher) {
+        hasher.write_f32(self.aggression_threshold);
+        hasher.write_f32(self.aggression_decay);
+        hasher.write_u32(self.cooldown_ticks);
+        hasher.write_u32(self.wave_size);
+        self.wave_unit_type.hash(hasher);
+        hasher.write_f32(self.aggro_radius);
+        hasher.write_f32(self.aggression);
+        hasher.write_u32(self.cooldown_left);
+        self.aggressor.hash(hasher);
+        self.aggressor_position.is_some().hash(hasher);
+        if let Some(aggressor_position) = self.aggressor_position {
+            hasher.write_vector(aggressor_position);
+        }
+    }
+}
+
+impl Persist for Spawner {
+    fn save(&self, writer: &mut SaveWriter) {
+        writer.write_label("spawner");
+        self.aggression_threshold.save(writer);
+        self.aggression_decay.save(writer);
+        self.cooldown_ticks.save(writer);
+        self.wave_size.save(writer);
+        self.wave_unit_type.save(writer);
+        self.aggro_radius.save(writer);
+        self.aggression.save(writer);
+        self.cooldown_left.save(writer);
+        self.aggressor.save(writer);
+        self.aggressor_position.save(writer);
+    }
+
+    fn load(reader: &mut SaveReader) -> Result<Self, String> {
+        reader.expect_label("spawner")?;
+        Ok(Self {
+            aggression_threshold: f32::load(reader)?,
+            aggression_decay: f32::load(reader)?,
+            cooldown_ticks: u32::load(reader)?,
+            wave_size: u32::load(reader)?,
+            wave_unit_type: UnitParentType::load(reader)?,
+            aggro_radius: f32::load(reader)?,
+            aggression: f32::load(reader)?,
+            cooldown_left: u32::load(reader)?,
+            aggressor: Option::<EntID>::load(reader)?,
+            aggressor_position: Option::<Vector2D<f32>>::load(reader)?,
+        })
+    }
+}
@@ -0,0 +1,62 @@
+use std::hash::Hasher;
+
+use sdl2::rect::{Point, Rect};
+use vector2d::Vector2D;
+
+// FNV-1a hasher used to fingerprint the simulation state
+// Unlike std's DefaultHasher, its output is stable across runs, platforms and compiler versions
+pub struct StateHasher {
+    hash: u64,
+}
+
+const FNV_OFFSET_BASIS: u64 = 0xCBF2_9CE4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01B3;
+
+impl Default for StateHasher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StateHasher {
+    pub const fn new() -> Self {
+        Self {
+            hash: FNV_OFFSET_BASIS,
+        }
+    }
+
+    // Floats get hashed by their bit pattern, so any divergence at all gets caught
+    pub fn write_f32(&mut self, value: f32) {
+        self.write_u32(value.to_bits());
+    }
+
+    pub fn write_vector(&mut self, vector: Vector2D<f32>) {
+        self.write_f32(vector.x);
+        self.write_f32(vector.y);
+    }
+
+    pub fn write_point(&mut self, point: Point) {
+        self.write_i32(point.x);
+        self.write_i32(point.y);
+    }
+
+    pub fn write_rect(&mut self, rect: Rect) {
+        self.write_i32(rect.x);
+        self.write_i32(rect.y);
+        self.write_u32(rect.width());
+        self.write_u32(rect.height());
+    }
+}
+
+impl Hasher for StateHasher {
+    fn finish(&self) -> u64 {
+        self.hash
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.hash ^= *byte as u64;
+            self.hash = self.hash.wrapping_mul(FNV_PRIME);
+        }
+    }
+}
@@ -1,5 +1,400 @@
-use super::ent::Ent;
+use std::hash::{Hash, Hasher};
+
+use sdl2::rect::Rect;
+use vector2d::Vector2D;
+
+use crate::{
+    consts::{
+        helper::{
+            empty_ent_target, new_unit, structure_build_ticks, unit_build_ticks, unit_rect_size,
+        },
+        values::{
+            CONSTRUCTION_START_HP_RATIO, MAP_HEIGHT, MAP_WIDTH, MAX_PRODUCTION_QUEUE_LENGTH,
+            PRODUCTION_SPAWN_GAP, PRODUCTION_SPAWN_SEARCH_RINGS, UNIT_SUPPLY_COST,
+        },
+    },
+    enums::game_object::GameObject,
+};
+
+use super::{
+    ent::{Ent, EntIDAllocator},
+    order::{Order, OrderType},
+    player_state::PlayerState,
+    save_file::{persist_enum, Persist, SaveReader, SaveWriter},
+    spawner::Spawner,
+    state_hash::StateHasher,
+    unit::UnitParentType,
+    world_info::WorldInfo,
+};
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash)]
+pub enum StructureParentType {
+    Mainframe, // Main base; Collectors beam their ore in here
+    Spawner,   // Cpu nest; Sends out waves after whoever damages the ore patches around it
+}
+
+impl StructureParentType {
+    // Whether this kind of structure can be placed down by a builder
+    pub const fn is_buildable(&self) -> bool {
+        matches!(self, StructureParentType::Mainframe)
+    }
+}
 
 pub struct Structure {
-    pub ent: Ent,
+    parent_type: StructureParentType,
+    production_queue: Vec<UnitParentType>, // Units paid for and waiting to be produced; The first one is in production
+    production_progress: u32,              // Ticks spent producing the first unit in the queue
+    rally_point: Option<Order>, // Order handed to every freshly produced unit (a Move or a Follow)
+    construction_progress: Option<u32>, // Ticks of work put into this structure so far; None once it is built
+    spawner: Option<Spawner>,           // Only for spawner structures
+}
+
+impl Structure {
+    pub fn new(parent_type: StructureParentType) -> Self {
+        Self {
+            parent_type,
+            production_queue: Vec::<UnitParentType>::new(),
+            production_progress: 0,
+            rally_point: None,
+            construction_progress: None,
+            spawner: match parent_type {
+                StructureParentType::Spawner => Some(Spawner::new()),
+                StructureParentType::Mainframe => None,
+            },
+        }
+    }
+
+    // A freshly placed structure, that builders still have to work on
+    pub fn new_under_construction(parent_type: StructureParentType) -> Self {
+        Self {
+            construction_progress: Some(0),
+            ..Self::new(parent_type)
+        }
+    }
+
+    // Ticks production, and spawner waves; Returns any freshly spawned units
+    pub fn tick(
+        &mut self,
+        ent: &mut Ent,
+        world_info: &mut WorldInfo,
+        ent_ids: &mut EntIDAllocator,
+        player_state: Option<&mut PlayerState>,
+    ) -> Vec<GameObject> {
+        // Update local HP based on world_info data
+        // If not found there, then structure is dead
+        ent.hp = world_info.get_ent_hp(ent).unwrap_or(0.0);
+        ent.shield = world_info.get_ent_shield(ent).unwrap_or(0.0);
+
+        // If dead, or still being built, return early
+        if ent.hp <= 0.0 || self.is_under_construction() {
+            return Vec::<GameObject>::new();
+        }
+
+        let mut new_units: Vec<GameObject> = self.tick_spawner(ent, world_info, ent_ids);
+        if let Some(new_unit) = self.tick_production(ent, world_info, ent_ids, player_state) {
+            new_units.push(new_unit);
+        }
+        new_units
+    }
+
+    // Returns the freshly produced unit (if any)
+    fn tick_production(
+        &mut self,
+        ent: &Ent,
+        world_info: &mut WorldInfo,
+        ent_ids: &mut EntIDAllocator,
+        player_state: Option<&mut PlayerState>,
+    ) -> Option<GameObject> {
+        // If nothing to produce, return early
+        let unit_type = *self.production_queue.first()?;
+
+        if self.production_progress < unit_build_ticks(unit_type) {
+            self.production_progress += 1;
+            return None;
+        }
+
+        // Done; Wait around until the owner has room for one more unit
+        let player_state = player_state?;
+        if !player_state.has_supply_for(UNIT_SUPPLY_COST) {
+            return None;
+        }
+        player_state.supply_used += UNIT_SUPPLY_COST;
+
+        self.production_queue.remove(0);
+        self.production_progress = 0;
+        let mut new_unit = self.spawn_unit(ent, unit_type, world_info, ent_ids);
+
+        // Send it off to the rally point (if any)
+        if let Some(mut rally_order) = self.rally_point {
+            if let Some(ent_target_id) = rally_order.ent_target.ent_id {
+                // Rallying onto a dead ent is the same as no rally at all
+                match world_info.get_ent_rect_center_poisition_by_id(ent_target_id) {
+                    Some(target_position) => rally_order.current_move_target = target_position,
+                    None => return Some(new_unit),
+                }
+            }
+            if let GameObject::Unit(new_ent, unit_type) = &mut new_unit {
+                if unit_type.can_perform(rally_order.order_type) {
+                    new_ent.add_order(rally_order, true);
+                }
+            }
+        }
+        Some(new_unit)
+    }
+
+    // Returns the wave sent out this tick (if any)
+    fn tick_spawner(
+        &mut self,
+        ent: &Ent,
+        world_info: &mut WorldInfo,
+        ent_ids: &mut EntIDAllocator,
+    ) -> Vec<GameObject> {
+        let Some(spawner) = &mut self.spawner else {
+            return Vec::<GameObject>::new();
+        };
+        if !spawner.tick() {
+            return Vec::<GameObject>::new();
+        }
+        // Head for the aggressor, or for wherever it was last seen
+        let Some(wave_target) = spawner
+            .aggressor()
+            .and_then(|aggressor| world_info.get_ent_rect_center_poisition_by_id(aggressor))
+            .or(spawner.aggressor_position())
+        else {
+            return Vec::<GameObject>::new();
+        };
+        let wave_order = Order::new(OrderType::ActionMove, wave_target, empty_ent_target());
+        let (wave_size, wave_unit_type) = (spawner.wave_size, spawner.wave_unit_type);
+
+        let mut wave: Vec<GameObject> = Vec::<GameObject>::new();
+        for _ in 0..wave_size {
+            let mut new_unit = self.spawn_unit(ent, wave_unit_type, world_info, ent_ids);
+            if let GameObject::Unit(new_ent, _) = &mut new_unit {
+                new_ent.add_order(wave_order, true);
+            }
+            wave.push(new_unit);
+        }
+        wave
+    }
+
+    fn spawn_unit(
+        &self,
+        ent: &Ent,
+        unit_type: UnitParentType,
+        world_info: &mut WorldInfo,
+        ent_ids: &mut EntIDAllocator,
+    ) -> GameObject {
+        let position = self.find_spawn_position(ent, unit_type, world_info);
+        new_unit(
+            world_info,
+            ent_ids.next_id(),
+            unit_type,
+            ent.owner,
+            position,
+        )
+    }
+
+    // Looks for a free spot right outside this structure, starting below it and circling outwards
+    // If everything around is taken, the unit just pops out below the structure
+    fn find_spawn_position(
+        &self,
+        ent: &Ent,
+        unit_type: UnitParentType,
+        world_info: &WorldInfo,
+    ) -> Vector2D<f32> {
+        let rect = ent.get_rect();
+        let unit_size = unit_rect_size(unit_type);
+        let step = unit_size + PRODUCTION_SPAWN_GAP;
+        let center_x = rect.center().x - unit_size / 2;
+        let center_y = rect.center().y - unit_size / 2;
+
+        let mut candidates: Vec<(i32, i32)> = Vec::<(i32, i32)>::new();
+        for ring in 0..PRODUCTION_SPAWN_SEARCH_RINGS {
+            let below = rect.bottom() + PRODUCTION_SPAWN_GAP + ring * step;
+            let above = rect.top() - PRODUCTION_SPAWN_GAP - unit_size - ring * step;
+            let right = rect.right() + PRODUCTION_SPAWN_GAP + ring * step;
+            let left = rect.left() - PRODUCTION_SPAWN_GAP - unit_size - ring * step;
+            candidates.extend([
+                (center_x, below),
+                (right, below),
+                (left, below),
+                (right, center_y),
+                (left, center_y),
+                (right, above),
+                (left, above),
+                (center_x, above),
+            ]);
+        }
+
+        let in_bounds = |(x, y): &(i32, i32)| {
+            *x >= 0
+                && *y >= 0
+                && *x + unit_size <= MAP_WIDTH as i32
+                && *y + unit_size <= MAP_HEIGHT as i32
+        };
+        let (x, y) = candidates
+            .iter()
+            .copied()
+            .filter(in_bounds)
+            .find(|(x, y)| {
+                world_info
+                    .get_ents_in_rect(Rect::new(*x, *y, unit_size as u32, unit_size as u32))
+                    .is_empty()
+            })
+            .unwrap_or(candidates[0]);
+        Vector2D::<f32>::new(x as f32, y as f32)
+    }
+
+    // Puts one tick of work into this structure; HP rises along with it, up to max hp once built
+    // Once done, world_info gets to know what kind of structure this is
+    pub fn construct(&mut self, ent: &mut Ent, world_info: &mut WorldInfo) {
+        let Some(construction_progress) = self.construction_progress else {
+            return;
+        };
+        let Some(hp) = world_info.get_ent_hp(ent) else {
+            return;
+        };
+        let build_ticks = structure_build_ticks(self.parent_type);
+        let hp_per_tick =
+            ent.max_hp as f32 * (1.0 - CONSTRUCTION_START_HP_RATIO) / build_ticks as f32;
+        ent.hp = (hp + hp_per_tick).min(ent.max_hp as f32);
+        if construction_progress + 1 >= build_ticks {
+            self.construction_progress = None;
+            world_info.complete_structure(ent.id, self.parent_type);
+        } else {
+            self.construction_progress = Some(construction_progress + 1);
+        }
+    }
+
+    pub fn is_under_construction(&self) -> bool {
+        self.construction_progress.is_some()
+    }
+
+    // How far along (0.0 to 1.0) construction is; Built structures are all the way there
+    pub fn construction_fraction(&self) -> f32 {
+        match self.construction_progress {
+            Some(construction_progress) => {
+                construction_progress as f32 / structure_build_ticks(self.parent_type) as f32
+            }
+            None => 1.0,
+        }
+    }
+
+    pub fn can_produce(&self, unit_type: UnitParentType) -> bool {
+        match self.parent_type {
+            StructureParentType::Mainframe => matches!(
+                unit_type,
+                UnitParentType::Scout | UnitParentType::Miner | UnitParentType::Collector
+            ),
+            StructureParentType::Spawner => false,
+        }
+    }
+
+    // Whether a unit could be added to the queue right now
+    pub fn can_enqueue(&self, unit_type: UnitParentType) -> Result<(), String> {
+        if self.is_under_construction() {
+            return Err("structure is still under construction".to_owned());
+        }
+        if !self.can_produce(unit_type) {
+            return Err("structure cannot produce this unit".to_owned());
+        }
+        if self.production_queue.len() >= MAX_PRODUCTION_QUEUE_LENGTH {
+            return Err("production queue is full".to_owned());
+        }
+        Ok(())
+    }
+
+    // Adds a unit to the back of the queue; Paying for it is up to the caller
+    pub fn enqueue(&mut self, unit_type: UnitParentType) -> Result<(), String> {
+        self.can_enqueue(unit_type)?;
+        self.production_queue.push(unit_type);
+        Ok(())
+    }
+
+    // Takes a unit out of the queue, returning it so that the caller can refund it
+    // Cancelling the unit in production throws away its progress
+    pub fn cancel(&mut self, index: usize) -> Option<UnitParentType> {
+        if index >= self.production_queue.len() {
+            return None;
+        }
+        if index == 0 {
+            self.production_progress = 0;
+        }
+        Some(self.production_queue.remove(index))
+    }
+
+    pub fn production_queue(&self) -> &[UnitParentType] {
+        &self.production_queue
+    }
+
+    // How far along (0.0 to 1.0) the unit in production is
+    pub fn production_fraction(&self) -> f32 {
+        match self.production_queue.first() {
+            Some(unit_type) => {
+                self.production_progress as f32 / unit_build_ticks(*unit_type) as f32
+            }
+            None => 0.0,
+        }
+    }
+
+    pub fn rally_point(&self) -> Option<Order> {
+        self.rally_point
+    }
+
+    pub fn set_rally_point(&mut self, rally_point: Option<Order>) {
+        self.rally_point = rally_point;
+    }
+
+    pub fn spawner(&self) -> Option<&Spawner> {
+        self.spawner.as_ref()
+    }
+
+    pub fn spawner_mut(&mut self) -> Option<&mut Spawner> {
+        self.spawner.as_mut()
+    }
+
+    pub fn parent_type(&self) -> StructureParentType {
+        self.parent_type
+    }
+
+    pub fn hash_state(&self, hasher: &mut StateHasher) {
+        self.parent_type.hash(hasher);
+        self.production_queue.hash(hasher);
+        hasher.write_u32(self.production_progress);
+        self.rally_point.is_some().hash(hasher);
+        if let Some(rally_point) = &self.rally_point {
+            rally_point.hash_state(hasher);
+        }
+        self.construction_progress.hash(hasher);
+        self.spawner.is_some().hash(hasher);
+        if let Some(spawner) = &self.spawner {
+            spawner.hash_state(hasher);
+        }
+    }
+}
+
+persist_enum!(StructureParentType { Mainframe, Spawner });
+
+impl Persist for Structure {
+    fn save(&self, writer: &mut SaveWriter) {
+        writer.write_label("structure");
+        self.parent_type.save(writer);
+        self.production_queue.save(writer);
+        self.production_progress.save(writer);
+        self.rally_point.save(writer);
+        self.construction_progress.save(writer);
+        self.spawner.save(writer);
+    }
+
+    fn load(reader: &mut SaveReader) -> Result<Self, String> {
+        reader.expect_label("structure")?;
+        Ok(Self {
+            parent_type: StructureParentType::load(reader)?,
+            production_queue: Vec::<UnitParentType>::load(reader)?,
+            production_progress: u32::load(reader)?,
+            rally_point: Option::<Order>::load(reader)?,
+            construction_progress: Option::<u32>::load(reader)?,
+            spawner: Option::<Spawner>::load(reader)?,
+        })
+    }
 }
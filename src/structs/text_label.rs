@@ -10,6 +10,12 @@ use super::{camera::Camera, ui_element::UIElement};
 #[derive(Clone, PartialEq, Eq, Hash)]
 pub struct TextLabel {}
 
+impl Default for TextLabel {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl TextLabel {
     pub fn new() -> Self {
         Self {}
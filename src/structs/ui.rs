@@ -8,7 +8,10 @@ use sdl2::{
 
 use crate::enums::ui_object::UIObject;
 
-use super::{camera::Camera, ui_element::UIElementID, world::World, world_info::WorldInfo};
+use super::{
+    camera::Camera, ent::Owner, ore_patch::OreType, ui_element::UIElementID, world::World,
+    world_info::WorldInfo,
+};
 
 pub struct UI {
     texture_creator: TextureCreator<WindowContext>,
@@ -35,6 +38,17 @@ impl UI {
                                 "Ents: ".to_owned() + world.game_objects.len().to_string().as_str(),
                             );
                         }
+                        UIElementID::PlayerResources => {
+                            if let Some(player_state) = world.player_state(Owner::Player) {
+                                ui_element.set_label(format!(
+                                    "Ore: {:.0} (+{:.0}/min) Supply: {}/{}",
+                                    player_state.resources(OreType::Blue),
+                                    player_state.income_per_minute,
+                                    player_state.supply_used,
+                                    player_state.supply_cap
+                                ));
+                            }
+                        }
                     }
                 }
             }
@@ -3,6 +3,7 @@ use sdl2::{pixels::Color, rect::Rect};
 #[derive(Copy, Clone, PartialEq, Eq, Hash)]
 pub enum UIElementID {
     DEBUG_EntCount,
+    PlayerResources,
 }
 
 #[derive(Clone, PartialEq, Eq, Hash)]
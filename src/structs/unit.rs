@@ -1,30 +1,33 @@
+use std::hash::{Hash, Hasher};
 use std::ops::{Index, IndexMut};
 
 use rand::Rng;
 use vector2d::Vector2D;
 
-use sdl2::pixels::Color;
 use sdl2::rect::{Point, Rect};
-use sdl2::render::Canvas;
-use sdl2::video::Window;
 
-use crate::consts::helper::{
-    draw_circle_selection_border, draw_rect_selection_border, draw_waypoint, empty_ent_target,
-    get_direction_from_to,
-};
+use crate::consts::helper::{empty_ent_target, get_direction_from_to, new_ore};
 use crate::consts::values::{
-    ATTACKER_SPEED_PENALTY, BASE_UNIT_DAMAGE, BASE_UNIT_MASS, BASE_UNIT_RANGE, BASE_UNIT_SPEED,
-    BLACK_RGB, FOLLOW_ORDER_HOVER_DISTANCE, GREY_RGB, MAX_MOVE_ORDER_ERROR, ORANGE_RGB,
-    RED_RGBA_WEAK, SELECTION_ATTACK_TARGET_BORDER_COLOR, SELECTION_BORDER_COLOR,
-    SELECTION_COLLECT_TARGET_BORDER_COLOR, SELECTION_FOLLOW_TARGET_BORDER_COLOR,
-    SELECTION_MINE_TARGET_BORDER_COLOR, TIME_STEP,
+    ATTACKER_SPEED_PENALTY, BASE_COLLECTOR_COLLECT_RATE, BASE_COLLECTOR_DEPOSIT_RATE,
+    BASE_UNIT_MASS, BASE_UNIT_RANGE, BASE_UNIT_SPEED, FOLLOW_ORDER_HOVER_DISTANCE,
+    MAX_MOVE_ORDER_ERROR, MINER_WEAPON_COOLDOWN_TICKS, MINER_WEAPON_DAMAGE,
+    MINER_WEAPON_WINDUP_TICKS, NAV_REPLAN_DISTANCE, NAV_WAYPOINT_REACHED_DISTANCE,
+    SCOUT_PROJECTILE_SPEED, SCOUT_WEAPON_COOLDOWN_TICKS, SCOUT_WEAPON_DAMAGE,
+    SCOUT_WEAPON_SPLASH_RADIUS, SCOUT_WEAPON_WINDUP_TICKS, SOFT_COLLISION_SEPARATION_SPEED,
+    TIME_STEP,
 };
-use crate::ent::Ent;
-
-use crate::order::{Order, OrderType};
-
-use super::ent::{EntID, EntParentType, Owner, State};
-use super::order::EntTarget;
+use crate::enums::game_object::GameObject;
+
+use super::damage::DamageType;
+use super::ent::{Ent, EntID, EntIDAllocator, EntParentType, State};
+use super::order::{EntTarget, Order, OrderType};
+use super::ore_patch::OreType;
+use super::projectile::{ProjectileKind, ProjectileMotion};
+use super::save_file::{persist_enum, Persist, SaveReader, SaveWriter};
+use super::sim_rng::SimRng;
+use super::state_hash::StateHasher;
+use super::structure::StructureParentType;
+use super::weapon::{Weapon, WeaponTargets};
 use super::world_info::WorldInfo;
 
 #[derive(Copy, Clone, PartialEq, Eq, Hash)]
@@ -34,18 +37,33 @@ pub enum UnitParentType {
     Collector,
 }
 
+// How a unit deals with bumping into other units
+// Hard: gets clamped against anything it runs into
+// Soft: moves freely through friendly units, and gets gently pushed apart from overlapping units when at rest
+//       Still gets clamped against static obstacles (ore patches, structures)
+#[derive(Copy, Clone, PartialEq, Eq, Hash)]
+pub enum CollisionModel {
+    Hard,
+    Soft,
+}
+
 #[derive(Copy, Clone, PartialEq, Eq, Hash)]
 pub enum Action {
     None,
     Attacking,
     Mining,
     Collecting,
+    Depositing,
+    Building,
 }
 
 pub struct Unit {
     pub speed: f32,
-    pub damage: f32,
-    pub range: f32,
+    pub collect_rate: f32, // How much ore it picks up per second (collectors only)
+    pub range: f32, // How far away it can interact with things; Shooting uses its weapon's range instead
+    pub weapon: Option<Weapon>,
+    pub move_fire: bool, // Whether it can keep shooting while carrying out a move order
+    anchored_target: Option<EntID>, // What it is shooting at while on the move
     current_action: Action,
     interaction_line_render_latch_point_delta: Option<Point>,
     velocity: Vector2D<f32>,
@@ -53,7 +71,12 @@ pub struct Unit {
     mass: f32,
     storage: f32,
     max_storage: f32,
+    storage_ore_type: Option<OreType>, // Type of the ore in storage; Collectors carry one type of ore at a time
+    last_ore_position: Option<Vector2D<f32>>, // Where this unit last collected ore; It heads back there after depositing
     parent_type: UnitParentType,
+    collision_model: CollisionModel,
+    path: Vec<Vector2D<f32>>, // Waypoints left to reach the current destination; The last one is the destination itself
+    path_destination: Option<Vector2D<f32>>, // Destination the current path was planned for
 }
 
 impl Unit {
@@ -61,8 +84,21 @@ impl Unit {
         match parent_type {
             UnitParentType::Miner => Self {
                 speed: BASE_UNIT_SPEED,
-                damage: BASE_UNIT_DAMAGE,
+                collect_rate: 0.0,
                 range: BASE_UNIT_RANGE,
+                weapon: Some(Weapon::new(
+                    MINER_WEAPON_DAMAGE,
+                    DamageType::Mining,
+                    MINER_WEAPON_COOLDOWN_TICKS,
+                    BASE_UNIT_RANGE,
+                    MINER_WEAPON_WINDUP_TICKS,
+                    WeaponTargets {
+                        units: false,
+                        structures: false,
+                        ore: true,
+                    },
+                )),
+                anchored_target: None,
                 current_action: Action::None,
                 interaction_line_render_latch_point_delta: None,
                 velocity: Vector2D::<f32>::new(0.0, 0.0),
@@ -70,12 +106,39 @@ impl Unit {
                 mass: BASE_UNIT_MASS,
                 storage: 0.0,
                 max_storage: 0.0,
+                storage_ore_type: None,
+                last_ore_position: None,
                 parent_type,
+                move_fire: false,
+                collision_model: CollisionModel::Hard,
+                path: Vec::<Vector2D<f32>>::new(),
+                path_destination: None,
             },
             UnitParentType::Scout => Self {
                 speed: BASE_UNIT_SPEED,
-                damage: BASE_UNIT_DAMAGE,
+                collect_rate: 0.0,
                 range: BASE_UNIT_RANGE,
+                weapon: Some(
+                    Weapon::new(
+                        SCOUT_WEAPON_DAMAGE,
+                        DamageType::Kinetic,
+                        SCOUT_WEAPON_COOLDOWN_TICKS,
+                        BASE_UNIT_RANGE,
+                        SCOUT_WEAPON_WINDUP_TICKS,
+                        WeaponTargets {
+                            units: true,
+                            structures: true,
+                            ore: false,
+                        },
+                    )
+                    .with_splash(SCOUT_WEAPON_SPLASH_RADIUS)
+                    .with_projectile(ProjectileKind {
+                        speed: SCOUT_PROJECTILE_SPEED,
+                        motion: ProjectileMotion::Homing,
+                    }),
+                ),
+                move_fire: true,
+                anchored_target: None,
                 current_action: Action::None,
                 interaction_line_render_latch_point_delta: None,
                 velocity: Vector2D::<f32>::new(0.0, 0.0),
@@ -83,12 +146,20 @@ impl Unit {
                 mass: BASE_UNIT_MASS,
                 storage: 0.0,
                 max_storage: 0.0,
+                storage_ore_type: None,
+                last_ore_position: None,
                 parent_type,
+                collision_model: CollisionModel::Soft,
+                path: Vec::<Vector2D<f32>>::new(),
+                path_destination: None,
             },
             UnitParentType::Collector => Self {
                 speed: BASE_UNIT_SPEED,
-                damage: BASE_UNIT_DAMAGE,
+                collect_rate: BASE_COLLECTOR_COLLECT_RATE,
                 range: BASE_UNIT_RANGE,
+                weapon: None,
+                move_fire: false,
+                anchored_target: None,
                 current_action: Action::None,
                 interaction_line_render_latch_point_delta: None,
                 velocity: Vector2D::<f32>::new(0.0, 0.0),
@@ -96,24 +167,37 @@ impl Unit {
                 mass: BASE_UNIT_MASS,
                 storage: 0.0,
                 max_storage: 100.0,
+                storage_ore_type: None,
+                last_ore_position: None,
                 parent_type,
+                collision_model: CollisionModel::Soft,
+                path: Vec::<Vector2D<f32>>::new(),
+                path_destination: None,
             },
         }
     }
 
-    pub fn tick(&mut self, ent: &mut Ent, world_info: &mut WorldInfo) {
+    pub fn tick(&mut self, ent: &mut Ent, world_info: &mut WorldInfo, rng: &mut SimRng) {
         // Update local HP based on world_info data
         // If not found there, then unit is dead
         ent.hp = world_info.get_ent_hp(ent).unwrap_or(0.0);
+        ent.shield = world_info.get_ent_shield(ent).unwrap_or(0.0);
 
         // If dead, return early
         if ent.hp <= 0.0 {
             return;
         }
 
+        // Cool weapon down (if any)
+        if let Some(weapon) = &mut self.weapon {
+            weapon.tick();
+        }
+
         // Apply steering
         // Steering allows a unit to go from its current velocity to target velocity, if needed
-        self.apply_steering();
+        // Soft colliding units also get steered away from the units they overlap with
+        let separation_velocity = self.get_separation_velocity(ent, world_info);
+        self.apply_steering(separation_velocity);
 
         // Apply velocity (if any)
         // Also handles collision detection
@@ -143,17 +227,24 @@ impl Unit {
 
         // If no orders, return early
         if ent.orders.is_empty() {
+            self.drop_anchored_target();
             return;
         }
 
         // Try to grab next order
-        let (next_order_option, next_order_direction_option) = self.grab_next_order(ent);
+        let (next_order_option, next_order_direction_option) =
+            self.grab_next_order(ent, world_info);
 
         // Execute current order
         if let Some(next_order) = next_order_option {
             // Keep a flag to mark completion
-            let did_complete_order =
-                self.execute_next_order(ent, next_order, next_order_direction_option, world_info);
+            let did_complete_order = self.execute_next_order(
+                ent,
+                next_order,
+                next_order_direction_option,
+                world_info,
+                rng,
+            );
 
             // Check if we can complete the order
             if did_complete_order {
@@ -166,180 +257,6 @@ impl Unit {
         }
     }
 
-    pub fn draw(&self, ent: &mut Ent, canvas: &mut Canvas<Window>) {
-        // If dead, return early
-        if ent.hp <= 0.0 {
-            return {};
-        }
-        // If selected, draw selection border
-        if ent.selected() {
-            let border_color = if ent.owner == Owner::Player {
-                SELECTION_BORDER_COLOR
-            } else {
-                RED_RGBA_WEAK
-            };
-            draw_rect_selection_border(canvas, &ent.get_rect(), border_color);
-        }
-
-        // Draw self (if alive)
-        canvas.set_draw_color(ent.color);
-        if ent.owner == Owner::Cpu {
-            canvas.set_draw_color(BLACK_RGB);
-        }
-        let rect = ent.get_rect();
-        canvas.fill_rect(rect).ok();
-        canvas.set_draw_color(BLACK_RGB);
-        canvas.draw_rect(rect).ok();
-        if ent.state == State::Stop {
-            canvas.set_draw_color(GREY_RGB);
-            canvas.draw_point(ent.get_rect().center()).ok();
-        }
-        if ent.state == State::Hold {
-            canvas.set_draw_color(ORANGE_RGB);
-            canvas.draw_point(ent.get_rect().center()).ok();
-        }
-    }
-
-    pub fn draw_orders(&self, ent: &mut Ent, canvas: &mut Canvas<Window>) {
-        // Draw order waypoints, if selected
-        if !ent.selected() {
-            // Not selected, return early
-            return;
-        }
-
-        canvas.set_draw_color(Color::RGB(0, 150, 0));
-        for (i, order) in ent.orders.iter().enumerate() {
-            // Draw lines connecting order waypoints
-            // Set colors according to order type
-            match order.order_type {
-                OrderType::Move => canvas.set_draw_color(Color::RGB(0, 150, 0)),
-                OrderType::Attack | OrderType::LazyAttack => {
-                    canvas.set_draw_color(SELECTION_ATTACK_TARGET_BORDER_COLOR)
-                }
-                OrderType::Follow => canvas.set_draw_color(SELECTION_FOLLOW_TARGET_BORDER_COLOR),
-                OrderType::HoldPosition => canvas.set_draw_color(ORANGE_RGB),
-                OrderType::Mine => canvas.set_draw_color(SELECTION_MINE_TARGET_BORDER_COLOR),
-                OrderType::Collect => canvas.set_draw_color(SELECTION_COLLECT_TARGET_BORDER_COLOR),
-                OrderType::ActionMove => match self.parent_type {
-                    UnitParentType::Miner => {
-                        canvas.set_draw_color(SELECTION_MINE_TARGET_BORDER_COLOR)
-                    }
-                    UnitParentType::Scout => {
-                        canvas.set_draw_color(SELECTION_ATTACK_TARGET_BORDER_COLOR)
-                    }
-                    UnitParentType::Collector => {
-                        canvas.set_draw_color(SELECTION_COLLECT_TARGET_BORDER_COLOR)
-                    }
-                },
-            }
-            if i == 0 {
-                // If this is the next order, draw  a line from unit to waypoint
-                canvas
-                    .draw_line(
-                        ent.get_rect().center(),
-                        Point::new(
-                            order.current_move_target.x as i32,
-                            order.current_move_target.y as i32,
-                        ),
-                    )
-                    .ok();
-            }
-            // Else, draw line from last waypoint to this one
-            else {
-                let previous_order_target = ent.orders.index(i - 1).current_move_target;
-                canvas
-                    .draw_line(
-                        Point::new(
-                            previous_order_target.x as i32,
-                            previous_order_target.y as i32,
-                        ),
-                        Point::new(
-                            order.current_move_target.x as i32,
-                            order.current_move_target.y as i32,
-                        ),
-                    )
-                    .ok();
-            }
-            // Draw waypoint, if needed
-            match order.order_type {
-                // In case of attack order, draw red selection border on attacked ent
-                // (if target is still alive)
-                OrderType::Attack | OrderType::LazyAttack => {
-                    if let Some(attack_target_rect) = &order.ent_target.ent_rect {
-                        draw_rect_selection_border(
-                            canvas,
-                            attack_target_rect,
-                            SELECTION_ATTACK_TARGET_BORDER_COLOR,
-                        )
-                    }
-                }
-                // In case of move or attack move order, draw waypoint
-                OrderType::Move | OrderType::ActionMove => {
-                    draw_waypoint(*order, canvas);
-                }
-                // In case of follow order, draw yellow selection border on followed ent
-                // (if target is still alive)
-                OrderType::Follow => {
-                    if let Some(follow_target_rect) = &order.ent_target.ent_rect {
-                        draw_rect_selection_border(
-                            canvas,
-                            follow_target_rect,
-                            SELECTION_FOLLOW_TARGET_BORDER_COLOR,
-                        )
-                    }
-                }
-                // In case of hold position, draw waypoint
-                OrderType::HoldPosition => draw_waypoint(*order, canvas),
-                // In case of mining, draw white selection border on mine target
-                OrderType::Mine => {
-                    if let Some(mine_target_rect) = &order.ent_target.ent_rect {
-                        draw_rect_selection_border(
-                            canvas,
-                            mine_target_rect,
-                            SELECTION_MINE_TARGET_BORDER_COLOR,
-                        )
-                    }
-                }
-                OrderType::Collect => {
-                    if let Some(collect_target_rect) = &order.ent_target.ent_rect {
-                        draw_circle_selection_border(
-                            canvas,
-                            Vector2D::<f32>::new(
-                                collect_target_rect.center().x as f32,
-                                collect_target_rect.center().y as f32,
-                            ),
-                            collect_target_rect.width() as i16, // TODO: Get ent_target.radius up and going
-                            SELECTION_COLLECT_TARGET_BORDER_COLOR,
-                        )
-                    }
-                }
-            }
-        }
-    }
-
-    pub fn draw_attack_lines(&self, ent: &mut Ent, canvas: &mut Canvas<Window>) {
-        // Draw attack lines (if attacking)
-        if self.current_action == Action::Attacking {
-            let possible_attack_order = ent.orders.get(0);
-            if let Some(attack_order) = possible_attack_order {
-                if let Some(attack_target_rect) = attack_order.ent_target.ent_rect {
-                    canvas.set_draw_color(ent.color);
-                    if let Some(interaction_line_render_latch_point_delta) =
-                        self.interaction_line_render_latch_point_delta
-                    {
-                        canvas
-                            .draw_line(
-                                ent.get_rect().center(),
-                                attack_target_rect.center()
-                                    + interaction_line_render_latch_point_delta,
-                            )
-                            .ok();
-                    }
-                }
-            }
-        }
-    }
-
     pub fn clear_velocity(&mut self) {
         self.desired_velocity = Vector2D::<f32>::new(0.0, 0.0);
     }
@@ -369,16 +286,16 @@ impl Unit {
         // Aply velocity component
         ent.position.x += x_velocity;
         // Resolve collisions to the sides
-        for (ent_id, ent_rect) in world_info.ent_rect.iter_mut() {
-            if *ent_id == ent.id {
+        for (ent_id, ent_rect) in world_info.get_ents_in_rect(ent.get_rect()) {
+            if ent_id == ent.id || !self.collides_with(world_info, ent_id) {
                 continue;
             }
-            if !ent.get_rect().has_intersection(*ent_rect) {
+            if !ent.get_rect().has_intersection(ent_rect) {
                 continue;
             }
             // NO PUSH
 
-            if ent.get_rect().has_intersection(*ent_rect) {
+            if ent.get_rect().has_intersection(ent_rect) {
                 if self.velocity.x > 0.0 {
                     ent.position.x = (ent_rect.left() - ent.rect_size.x) as f32;
                 } else {
@@ -392,14 +309,14 @@ impl Unit {
         // Aply velocity component
         ent.position.y += y_velocity;
         // Resolve collisions to top/bottom
-        for (ent_id, ent_rect) in world_info.ent_rect.iter_mut() {
-            if *ent_id == ent.id {
+        for (ent_id, ent_rect) in world_info.get_ents_in_rect(ent.get_rect()) {
+            if ent_id == ent.id || !self.collides_with(world_info, ent_id) {
                 continue;
             }
-            if !ent.get_rect().has_intersection(*ent_rect) {
+            if !ent.get_rect().has_intersection(ent_rect) {
                 continue;
             }
-            if ent.get_rect().has_intersection(*ent_rect) {
+            if ent.get_rect().has_intersection(ent_rect) {
                 if self.velocity.y > 0.0 {
                     ent.position.y = (ent_rect.top() - ent.rect_size.y) as f32;
                 } else {
@@ -409,28 +326,167 @@ impl Unit {
         }
     }
 
+    // Whether running into an ent stops this unit in its tracks
+    // Soft colliding units only get stopped by static obstacles; Other units get pushed apart instead
+    fn collides_with(&self, world_info: &WorldInfo, ent_id: EntID) -> bool {
+        match self.collision_model {
+            CollisionModel::Hard => true,
+            CollisionModel::Soft => world_info
+                .get_ent_parent_type_by_id(ent_id)
+                .is_some_and(|parent_type| parent_type.is_static_obstacle()),
+        }
+    }
+
+    // Velocity pushing a soft colliding unit away from every unit it overlaps with
+    // Moving units pass through friendly units freely, so they only get pushed by enemies
+    fn get_separation_velocity(&self, ent: &Ent, world_info: &WorldInfo) -> Vector2D<f32> {
+        let mut separation_velocity = Vector2D::<f32>::new(0.0, 0.0);
+        if self.collision_model == CollisionModel::Hard {
+            return separation_velocity;
+        }
+        let is_moving = self.desired_velocity != Vector2D::<f32>::new(0.0, 0.0);
+        let rect = ent.get_rect();
+        let rect_center = rect.center();
+        for (ent_id, ent_rect) in world_info.get_ents_in_rect(rect) {
+            if ent_id == ent.id
+                || world_info.get_ent_parent_type_by_id(ent_id) != Some(EntParentType::Unit)
+            {
+                continue;
+            }
+            if is_moving && world_info.get_ent_owner_by_id(ent_id) == Some(ent.owner) {
+                continue;
+            }
+            let mut push_direction = Vector2D::<f32>::new(
+                (rect_center.x - ent_rect.center().x) as f32,
+                (rect_center.y - ent_rect.center().y) as f32,
+            );
+            // Units stacked right on top of each other split up along the x axis, older ent to the left
+            if push_direction == Vector2D::<f32>::new(0.0, 0.0) {
+                push_direction.x = if ent.id < ent_id { -1.0 } else { 1.0 };
+            }
+            separation_velocity += push_direction.normalise() * SOFT_COLLISION_SEPARATION_SPEED;
+        }
+        if separation_velocity.length() > SOFT_COLLISION_SEPARATION_SPEED {
+            separation_velocity = separation_velocity.normalise() * SOFT_COLLISION_SEPARATION_SPEED;
+        }
+        separation_velocity
+    }
+
     // If there is an order in the vector, grab it
-    pub fn grab_next_order(&self, ent: &mut Ent) -> (Option<Order>, Option<Vector2D<f32>>) {
+    // Along with the direction to steer in, to carry it out
+    pub fn grab_next_order(
+        &mut self,
+        ent: &mut Ent,
+        world_info: &mut WorldInfo,
+    ) -> (Option<Order>, Option<Vector2D<f32>>) {
         if !ent.orders.is_empty() {
-            let next_order = ent.orders.index(0);
+            let next_order = *ent.orders.index(0);
             let copy_of_target = next_order.current_move_target;
             let rect_center = ent.get_rect().center();
-            let new_velocity = get_direction_from_to(
-                Vector2D::<f32>::new(rect_center.x as f32, rect_center.y as f32),
-                copy_of_target,
-                self.speed,
-            );
-            return (Some(*next_order), Some(new_velocity));
+            let position = Vector2D::<f32>::new(rect_center.x as f32, rect_center.y as f32);
+            let steering_target = if next_order.order_type.uses_flow_field() {
+                self.clear_path();
+                match next_order.formation_anchor {
+                    // Formation members follow the field towards the formation as a whole,
+                    // and only break off for their own spot once nothing is in the way
+                    Some(formation_anchor) => {
+                        if world_info
+                            .nav_grid
+                            .has_line_of_sight(position, copy_of_target)
+                        {
+                            copy_of_target
+                        } else {
+                            world_info.flow_field_steering_target(position, formation_anchor)
+                        }
+                    }
+                    None => world_info.flow_field_steering_target(position, copy_of_target),
+                }
+            } else if next_order.order_type.uses_pathfinding() {
+                self.next_waypoint(position, copy_of_target, world_info)
+            } else {
+                copy_of_target
+            };
+            let speed = next_order
+                .speed_limit
+                .map_or(self.speed, |speed_limit| speed_limit.min(self.speed));
+            let new_velocity = get_direction_from_to(position, steering_target, speed);
+            return (Some(next_order), Some(new_velocity));
         }
         (None, None)
     }
 
+    // Picks the point to steer towards on the way to a destination
+    // Straight to it when nothing is in the way, else through the waypoints of an A* path
+    // The path gets planned again if the destination moves, or if the way to the next waypoint gets blocked
+    fn next_waypoint(
+        &mut self,
+        position: Vector2D<f32>,
+        destination: Vector2D<f32>,
+        world_info: &WorldInfo,
+    ) -> Vector2D<f32> {
+        let nav_grid = &world_info.nav_grid;
+        let destination_moved = match self.path_destination {
+            Some(path_destination) => {
+                (path_destination - destination).length() > NAV_REPLAN_DISTANCE
+            }
+            None => true,
+        };
+        if destination_moved {
+            self.path_destination = Some(destination);
+            self.plan_path(position, destination, world_info);
+        }
+
+        // Move on from any waypoint already reached
+        while let Some(waypoint) = self.path.first() {
+            if (*waypoint - position).length() > NAV_WAYPOINT_REACHED_DISTANCE {
+                break;
+            }
+            self.path.remove(0);
+        }
+
+        if let Some(waypoint) = self.path.first() {
+            if !nav_grid.has_line_of_sight(position, *waypoint) {
+                self.plan_path(position, destination, world_info);
+            }
+        }
+
+        // The last leg always heads for the live destination
+        match self.path.len() {
+            0 | 1 => destination,
+            _ => self.path[0],
+        }
+    }
+
+    fn plan_path(
+        &mut self,
+        position: Vector2D<f32>,
+        destination: Vector2D<f32>,
+        world_info: &WorldInfo,
+    ) {
+        let nav_grid = &world_info.nav_grid;
+        let goal = nav_grid.closest_free_point(destination);
+        self.path = if nav_grid.has_line_of_sight(position, goal) {
+            vec![goal]
+        } else {
+            // If there is no path at all, the path stays empty and the unit just heads straight for it
+            nav_grid
+                .find_path(position, destination)
+                .unwrap_or_default()
+        };
+    }
+
+    pub fn clear_path(&mut self) {
+        self.path.clear();
+        self.path_destination = None;
+    }
+
     // This method checks the current executed order for completion
     // If its completed, marks it as so, and processes results
     fn check_orders(&mut self, ent: &mut Ent) {
         if !ent.orders.is_empty() {
             let next_order = ent.orders.index(0);
             let mut did_complete_order = false;
+            let mut did_finish_patrol_leg = false;
 
             if !next_order.completed && next_order.executed {
                 match next_order.order_type {
@@ -446,6 +502,14 @@ impl Unit {
                         }
                     }
 
+                    // A patrol order can never be completed either!
+                    // Once a leg is walked, it goes to the back of the patrol route for another round
+                    OrderType::Patrol => {
+                        if self.has_arrived_at(ent, next_order.current_move_target) {
+                            did_finish_patrol_leg = true;
+                        }
+                    }
+
                     // A follow order can never be completed!
                     // It can only get cleard or canceled (if the followed unit dies)
                     // To complete an attack or lazy attack order, the target must be DEAD!
@@ -460,12 +524,25 @@ impl Unit {
                     | OrderType::Follow
                     | OrderType::HoldPosition
                     | OrderType::Mine
-                    | OrderType::Collect => (),
+                    | OrderType::Collect
+                    | OrderType::Deposit
+                    | OrderType::Build => (),
                 }
             }
             if did_complete_order {
                 ent.orders.index_mut(0).complete();
             }
+            if did_finish_patrol_leg {
+                let mut patrol_leg = ent.orders.remove(0);
+                patrol_leg.executed = false;
+                // The patrol route is every patrol order at the front of the queue
+                let route_end = ent
+                    .orders
+                    .iter()
+                    .take_while(|order| order.order_type == OrderType::Patrol)
+                    .count();
+                ent.orders.insert(route_end, patrol_leg);
+            }
         }
     }
 
@@ -504,30 +581,42 @@ impl Unit {
         ent.state = State::Hold;
     }
 
-    pub fn start_attacking(&mut self, ent: &mut Ent, attack_target_rect: Rect) {
+    pub fn start_attacking(&mut self, ent: &mut Ent, attack_target_rect: Rect, rng: &mut SimRng) {
         self.current_action = Action::Attacking;
-        self.start_interacting(ent, attack_target_rect);
+        self.start_interacting(ent, attack_target_rect, rng);
     }
 
-    pub fn start_mining(&mut self, ent: &mut Ent, attack_target_rect: Rect) {
+    pub fn start_mining(&mut self, ent: &mut Ent, attack_target_rect: Rect, rng: &mut SimRng) {
         self.current_action = Action::Mining;
-        self.start_interacting(ent, attack_target_rect);
+        self.start_interacting(ent, attack_target_rect, rng);
     }
 
-    pub fn start_collecting(&mut self, ent: &mut Ent, attack_target_rect: Rect) {
+    pub fn start_collecting(&mut self, ent: &mut Ent, attack_target_rect: Rect, rng: &mut SimRng) {
         self.current_action = Action::Collecting;
-        self.start_interacting(ent, attack_target_rect);
+        self.start_interacting(ent, attack_target_rect, rng);
+    }
+
+    pub fn start_depositing(&mut self, ent: &mut Ent, deposit_target_rect: Rect, rng: &mut SimRng) {
+        self.current_action = Action::Depositing;
+        self.start_interacting(ent, deposit_target_rect, rng);
+    }
+
+    pub fn start_building(&mut self, ent: &mut Ent, build_target_rect: Rect, rng: &mut SimRng) {
+        self.current_action = Action::Building;
+        self.start_interacting(ent, build_target_rect, rng);
     }
 
     pub fn stop_interacting(&mut self) {
         self.current_action = Action::None;
         self.interaction_line_render_latch_point_delta = None;
+        if let Some(weapon) = &mut self.weapon {
+            weapon.cancel_windup();
+        }
     }
 
-    pub fn start_interacting(&mut self, ent: &mut Ent, attack_target_rect: Rect) {
+    pub fn start_interacting(&mut self, ent: &mut Ent, attack_target_rect: Rect, rng: &mut SimRng) {
         self.clear_velocity();
         ent.state = State::Busy;
-        let mut rng = rand::thread_rng();
         self.interaction_line_render_latch_point_delta = Some(Point::new(
             rng.gen_range(
                 (-(attack_target_rect.width() as f32 + 2.0) / 2.0) as i32
@@ -558,11 +647,95 @@ impl Unit {
                 (Vector2D::<f32>::new(self_rect_center.x as f32, self_rect_center.y as f32)
                     - target_pos)
                     .length();
-            return (distance <= self.range, distance);
+            return (distance <= self.weapon_range(), distance);
         }
         (false, -1.0)
     }
 
+    // How far away it can shoot; Units without a weapon fall back to their interaction range
+    pub fn weapon_range(&self) -> f32 {
+        self.weapon
+            .as_ref()
+            .map_or(self.range, |weapon| weapon.range)
+    }
+
+    // Pulls the trigger on a target; Deals damage (or launches a projectile) whenever a shot goes off
+    fn shoot_at(&mut self, ent: &Ent, target_id: EntID, world_info: &mut WorldInfo) {
+        if let Some(weapon) = &mut self.weapon {
+            if weapon.try_fire() {
+                weapon.hit(ent, target_id, world_info);
+            }
+        }
+    }
+
+    // Keeps shooting while on the move, without slowing down for it
+    // The anchored target is kept for as long as it stays alive and in range, then the closest one in range takes over
+    fn fire_on_the_move(&mut self, ent: &mut Ent, world_info: &mut WorldInfo) {
+        if !self.move_fire || self.weapon.is_none() {
+            return;
+        }
+        let still_anchored = self.anchored_target.filter(|target_id| {
+            world_info.has_ent_by_id(*target_id)
+                && self
+                    .has_target_in_range_from_id(ent, world_info, *target_id)
+                    .0
+        });
+        let anchored_target = match still_anchored {
+            Some(target_id) => Some(target_id),
+            None => self.get_closest_target_in_range(ent, world_info).1.ent_id,
+        };
+        if anchored_target != self.anchored_target {
+            // A shot aimed at the old target is lost
+            self.drop_anchored_target();
+            self.anchored_target = anchored_target;
+        }
+        if let Some(target_id) = self.anchored_target {
+            self.shoot_at(ent, target_id, world_info);
+        }
+    }
+
+    fn drop_anchored_target(&mut self) {
+        if self.anchored_target.take().is_some() {
+            if let Some(weapon) = &mut self.weapon {
+                weapon.cancel_windup();
+            }
+        }
+    }
+
+    // Gets run once, right after this unit died, before it gets cleaned up
+    // Returns anything it leaves behind (loot, wrecks, ...)
+    pub fn on_death(
+        &mut self,
+        ent: &Ent,
+        world_info: &mut WorldInfo,
+        ent_ids: &mut EntIDAllocator,
+    ) -> Vec<GameObject> {
+        let mut left_behind = Vec::<GameObject>::new();
+        match self.parent_type {
+            // Collectors spill whatever they were carrying, centered on where they went down
+            UnitParentType::Collector => {
+                if let Some(ore_type) = self.storage_ore_type.filter(|_| self.storage > 0.0) {
+                    let rect_center = ent.get_rect().center();
+                    let ore_size = self.storage as i32;
+                    left_behind.push(new_ore(
+                        world_info,
+                        ent_ids.next_id(),
+                        ore_type,
+                        self.storage,
+                        Vector2D::<f32>::new(
+                            (rect_center.x - ore_size / 2) as f32,
+                            (rect_center.y - ore_size / 2) as f32,
+                        ),
+                    ));
+                }
+                self.storage = 0.0;
+                self.storage_ore_type = None;
+            }
+            UnitParentType::Miner | UnitParentType::Scout => (),
+        }
+        left_behind
+    }
+
     pub fn has_target_in_range_from_rect_center(
         &self,
         ent: &mut Ent,
@@ -583,13 +756,18 @@ impl Unit {
         // Check if any other unit is in range; if so, issue attack order to the closest one
         let mut closest_ent_in_range = empty_ent_target();
         let mut has_target_in_range = false;
-        let mut closest_ent_distance = self.range;
-        for (ent_id, ent_rect_center) in &world_info.ent_rect_center {
-            if *ent_id == ent.id {
+        let mut closest_ent_distance = self.weapon_range();
+        let rect_center = ent.get_rect().center();
+        let ents_in_range = world_info.get_ents_in_radius(
+            Vector2D::<f32>::new(rect_center.x as f32, rect_center.y as f32),
+            closest_ent_distance,
+        );
+        for (ent_id, distance) in ents_in_range {
+            if ent_id == ent.id {
                 // Cannot target self; return early
                 continue;
             }
-            if let Some(ent_owner) = world_info.get_ent_owner_by_id(*ent_id) {
+            if let Some(ent_owner) = world_info.get_ent_owner_by_id(ent_id) {
                 if ent_owner == ent.owner {
                     // Cannot targert an ent on the same team; return early
                     continue;
@@ -597,25 +775,16 @@ impl Unit {
             }
 
             // Check if target_ent_type is a valid target type for this ent
-            match world_info.get_ent_parent_type_by_id(*ent_id) {
-                Some(ent_parent_type) => match self.parent_type {
-                    UnitParentType::Miner => {
-                        // Check if target is an ore patch
-                        if !(ent_parent_type == EntParentType::OrePatch) {
+            // Armed units go by what their weapon can shoot at, collectors only care about ore
+            match world_info.get_ent_parent_type_by_id(ent_id) {
+                Some(ent_parent_type) => match &self.weapon {
+                    Some(weapon) => {
+                        if !weapon.can_target(ent_parent_type) {
                             // If not, continue
                             continue;
                         }
                     }
-                    UnitParentType::Scout => {
-                        // Check if target is unit or structure
-                        if !(vec![EntParentType::Unit, EntParentType::Structure]
-                            .contains(&ent_parent_type))
-                        {
-                            // If not, continue
-                            continue;
-                        }
-                    }
-                    UnitParentType::Collector => {
+                    None => {
                         // Check if target is an ore
                         if !(ent_parent_type == EntParentType::Ore) {
                             // If not, continue
@@ -627,24 +796,16 @@ impl Unit {
                 None => continue,
             }
 
-            let (is_in_range, distance) =
-                self.has_target_in_range_from_rect_center(ent, *ent_rect_center);
-
-            if !is_in_range {
-                // Too far away; return early
-                continue;
-            }
-
             // Only return the closest possible target
             if distance < closest_ent_distance {
                 // At this point, we know there is at least one target in range
                 has_target_in_range = true;
                 closest_ent_distance = distance;
                 closest_ent_in_range = EntTarget {
-                    ent_id: Some(*ent_id),
-                    ent_rect: world_info.get_ent_rect_by_id(*ent_id),
-                    ent_owner: world_info.get_ent_owner_by_id(*ent_id),
-                    ent_parent_type: world_info.get_ent_parent_type_by_id(*ent_id),
+                    ent_id: Some(ent_id),
+                    ent_rect: world_info.get_ent_rect_by_id(ent_id),
+                    ent_owner: world_info.get_ent_owner_by_id(ent_id),
+                    ent_parent_type: world_info.get_ent_parent_type_by_id(ent_id),
                 };
             }
         }
@@ -727,14 +888,22 @@ impl Unit {
         next_order: Order,
         next_order_direction_option: Option<Vector2D<f32>>,
         world_info: &mut WorldInfo,
+        rng: &mut SimRng,
     ) -> bool {
+        // Only move orders get to shoot on the move
+        if next_order.order_type != OrderType::Move {
+            self.drop_anchored_target();
+        }
         match next_order.order_type {
             OrderType::Move => {
                 ent.state = State::Busy;
-                self.stop_interacting();
+                if self.current_action != Action::None {
+                    self.stop_interacting();
+                }
                 if let Some(desired_velocity) = next_order_direction_option {
                     self.set_desired_velocity(desired_velocity);
                 }
+                self.fire_on_the_move(ent, world_info);
             }
             OrderType::Attack | OrderType::LazyAttack => {
                 let possible_attack_target = &next_order.ent_target;
@@ -753,11 +922,11 @@ impl Unit {
                     {
                         // If target is in range, check if already attacking
                         if self.current_action == Action::Attacking {
-                            world_info.damage_ent(attack_target_id, self.damage * TIME_STEP);
+                            self.shoot_at(ent, attack_target_id, world_info);
                         } else {
                             // Else, start attacking
                             if let Some(ent_rect) = possible_attack_target.ent_rect {
-                                self.start_attacking(ent, ent_rect);
+                                self.start_attacking(ent, ent_rect, rng);
                             }
                         }
                     } else {
@@ -779,7 +948,7 @@ impl Unit {
                     ent.state = State::Busy;
                 }
             }
-            OrderType::ActionMove => {
+            OrderType::ActionMove | OrderType::Patrol => {
                 ent.state = State::Alert;
                 self.stop_interacting();
                 if let Some(desired_velocity) = next_order_direction_option {
@@ -829,16 +998,16 @@ impl Unit {
                         return true;
                     }
                     if self
-                        .has_target_in_range_from_rect_center(ent, next_order.current_move_target)
+                        .has_target_in_range_from_id(ent, world_info, mine_target_id)
                         .0
                     {
                         // If target is in range, check if already mining
                         if self.current_action == Action::Mining {
-                            world_info.damage_ent(mine_target_id, self.damage * TIME_STEP);
+                            self.shoot_at(ent, mine_target_id, world_info);
                         } else {
                             // Else, start mining
                             if let Some(ent_rect) = next_order.ent_target.ent_rect {
-                                self.start_mining(ent, ent_rect);
+                                self.start_mining(ent, ent_rect, rng);
                             }
                         }
                     } else if let Some(desired_velocity) = next_order_direction_option {
@@ -852,14 +1021,23 @@ impl Unit {
             }
 
             OrderType::Collect => {
-                //  TODO: ADD DEPOSIT ORDER
-                //      => 3. Unit mining stores this ore on itself (maybe up to a certain carry_capacity, and with some increase to its mass  so its more clumsy (?)
-                //                                                  or just a speed debuff for carrying (maybe no attacking att full cap, must drop to attack?))
-                //      => 4. Unit must beam ore back into mainframe for collection and later use
-
-                // If full, can't collect!
-                println!("storage: {}", self.storage);
-                if self.storage >= self.max_storage {
+                // If full (or carrying some other type of ore), go beam the ore into the closest mainframe first
+                // The collect order stays queued up behind the deposit, so collecting picks back up afterwards
+                let target_ore_type = next_order
+                    .ent_target
+                    .ent_id
+                    .and_then(|ent_id| world_info.get_ent_ore_type_by_id(ent_id));
+                let carrying_other_ore = self.storage > 0.0
+                    && target_ore_type.is_some()
+                    && target_ore_type != self.storage_ore_type;
+                if self.storage >= self.max_storage || carrying_other_ore {
+                    if let Some(deposit_order) = self.new_deposit_order(ent, world_info) {
+                        self.stop_interacting();
+                        ent.bump_order(deposit_order);
+                        // Return false for an uncompleted order
+                        return false;
+                    }
+                    // Nowhere to deposit, can't collect!
                     // Return true for a completed order
                     return true;
                 }
@@ -882,21 +1060,30 @@ impl Unit {
                         .0
                     {
                         // If target is in range, check if already collecting
+                        self.last_ore_position = Some(next_order.current_move_target);
                         if self.current_action == Action::Collecting {
-                            let mut amount_to_collect = self.damage * TIME_STEP;
+                            let mut amount_to_collect = self.collect_rate * TIME_STEP;
                             if amount_to_collect > self.max_storage - self.storage {
                                 amount_to_collect = self.max_storage - self.storage;
                             }
-                            let potential_ore_gathered =
-                                world_info.damage_ent(collect_target_id, amount_to_collect);
-                            if let Some(ore_gathered) = potential_ore_gathered {
+                            let ore_left = world_info
+                                .get_ent_hp_by_id(collect_target_id)
+                                .unwrap_or(0.0);
+                            let potential_ore_left = world_info.damage_ent(
+                                collect_target_id,
+                                amount_to_collect,
+                                DamageType::Mining,
+                                ent.id,
+                            );
+                            if let Some(new_ore_left) = potential_ore_left {
                                 // TODO: increase collectors mass when carrying heavy load
-                                self.storage += ore_gathered / 100.0;
+                                self.storage += ore_left - new_ore_left;
+                                self.storage_ore_type = target_ore_type;
                             }
                         } else {
                             // Else, start collecting
                             if let Some(ent_rect) = next_order.ent_target.ent_rect {
-                                self.start_collecting(ent, ent_rect);
+                                self.start_collecting(ent, ent_rect, rng);
                             }
                         }
                     } else if let Some(desired_velocity) = next_order_direction_option {
@@ -908,10 +1095,139 @@ impl Unit {
                     ent.state = State::Busy;
                 }
             }
+
+            OrderType::Deposit => {
+                // Nothing left to deposit, order completed!
+                let Some(ore_type) = self.storage_ore_type.filter(|_| self.storage > 0.0) else {
+                    self.storage = 0.0;
+                    self.storage_ore_type = None;
+                    self.stop_interacting();
+                    self.return_to_last_ore(ent, world_info);
+                    // Return true for a completed order
+                    return true;
+                };
+                // No target, order completed!
+                let Some(deposit_target_id) = next_order.ent_target.ent_id else {
+                    return true;
+                };
+                if !world_info.has_ent_by_id(deposit_target_id) {
+                    // Target is dead! Head for the next closest mainframe instead (if any)
+                    self.stop_interacting();
+                    return match self.new_deposit_order(ent, world_info) {
+                        Some(deposit_order) => {
+                            *ent.orders.index_mut(0) = deposit_order;
+                            false
+                        }
+                        None => true,
+                    };
+                }
+                if self
+                    .has_target_in_range_from_rect_center(ent, next_order.current_move_target)
+                    .0
+                {
+                    // If target is in range, check if already depositing
+                    if self.current_action == Action::Depositing {
+                        let amount_to_deposit =
+                            (BASE_COLLECTOR_DEPOSIT_RATE * TIME_STEP).min(self.storage);
+                        self.storage -= amount_to_deposit;
+                        world_info.deposit_ore(ent.owner, ore_type, amount_to_deposit);
+                    } else {
+                        // Else, start depositing
+                        if let Some(ent_rect) = next_order.ent_target.ent_rect {
+                            self.start_depositing(ent, ent_rect, rng);
+                        }
+                    }
+                } else if let Some(desired_velocity) = next_order_direction_option {
+                    if self.current_action != Action::None {
+                        self.stop_interacting();
+                    }
+                    self.set_desired_velocity(desired_velocity);
+                }
+                ent.state = State::Busy;
+            }
+            OrderType::Build => {
+                // No target, order completed!
+                let Some(build_target_id) = next_order.ent_target.ent_id else {
+                    return true;
+                };
+                // Construction site got cancelled, destroyed, or it is done; Order completed!
+                // Only finished structures have a structure type
+                if !world_info.has_ent_by_id(build_target_id)
+                    || world_info
+                        .get_ent_structure_type_by_id(build_target_id)
+                        .is_some()
+                {
+                    self.stop_interacting();
+                    return true;
+                }
+                if self
+                    .has_target_in_range_from_rect_center(ent, next_order.current_move_target)
+                    .0
+                {
+                    // If site is in range, check if already building
+                    if self.current_action == Action::Building {
+                        world_info.work_on_construction(build_target_id);
+                    } else {
+                        // Else, start building
+                        if let Some(ent_rect) = next_order.ent_target.ent_rect {
+                            self.start_building(ent, ent_rect, rng);
+                        }
+                    }
+                } else if let Some(desired_velocity) = next_order_direction_option {
+                    if self.current_action != Action::None {
+                        self.stop_interacting();
+                    }
+                    self.set_desired_velocity(desired_velocity);
+                }
+                ent.state = State::Busy;
+            }
         }
         false
     }
 
+    // Builds an order to beam this unit's ore into the closest mainframe its owner has (if any)
+    fn new_deposit_order(&self, ent: &Ent, world_info: &WorldInfo) -> Option<Order> {
+        let rect_center = ent.get_rect().center();
+        let mainframe_id = world_info.get_closest_structure(
+            Vector2D::<f32>::new(rect_center.x as f32, rect_center.y as f32),
+            ent.owner,
+            StructureParentType::Mainframe,
+        )?;
+        let mainframe_position = world_info.get_ent_rect_center_poisition_by_id(mainframe_id)?;
+        Some(Order::new(
+            OrderType::Deposit,
+            mainframe_position,
+            EntTarget {
+                ent_id: Some(mainframe_id),
+                ent_rect: world_info.get_ent_rect_by_id(mainframe_id),
+                ent_owner: world_info.get_ent_owner_by_id(mainframe_id),
+                ent_parent_type: world_info.get_ent_parent_type_by_id(mainframe_id),
+            },
+        ))
+    }
+
+    // Once done depositing with nothing else to do, go back to where the ore was
+    // Action moving there picks up any ore still lying around
+    fn return_to_last_ore(&self, ent: &mut Ent, world_info: &WorldInfo) {
+        // Collect orders on ore that got used up in the meantime don't count
+        let has_orders_left = ent.orders.iter().skip(1).any(|order| {
+            order.order_type != OrderType::Collect
+                || order
+                    .ent_target
+                    .ent_id
+                    .is_some_and(|ent_id| world_info.has_ent_by_id(ent_id))
+        });
+        if has_orders_left {
+            return;
+        }
+        if let Some(last_ore_position) = self.last_ore_position {
+            ent.add_order(
+                Order::new(OrderType::ActionMove, last_ore_position, empty_ent_target()),
+                false,
+            );
+        }
+    }
+
     fn cancel_attack_order(&mut self, ent: &mut Ent, next_order: Order) -> bool {
         // Check if this is actualy an attack order
         // Note: If new attack order types are added, this vec macro needs updating...
@@ -936,9 +1252,10 @@ impl Unit {
         self.desired_velocity = target;
     }
 
-    fn apply_steering(&mut self) {
-        if self.velocity != self.desired_velocity {
-            let steering = self.desired_velocity - self.velocity;
+    fn apply_steering(&mut self, separation_velocity: Vector2D<f32>) {
+        let target_velocity = self.desired_velocity + separation_velocity;
+        if self.velocity != target_velocity {
+            let steering = target_velocity - self.velocity;
             self.velocity += steering / self.mass;
         }
     }
@@ -946,4 +1263,118 @@ impl Unit {
     pub fn parent_type(&self) -> UnitParentType {
         self.parent_type
     }
+
+    pub const fn current_action(&self) -> Action {
+        self.current_action
+    }
+
+    pub const fn interaction_line_render_latch_point_delta(&self) -> Option<Point> {
+        self.interaction_line_render_latch_point_delta
+    }
+
+    pub fn hash_state(&self, hasher: &mut StateHasher) {
+        self.parent_type.hash(hasher);
+        self.collision_model.hash(hasher);
+        self.current_action.hash(hasher);
+        hasher.write_f32(self.speed);
+        hasher.write_f32(self.collect_rate);
+        hasher.write_f32(self.range);
+        self.move_fire.hash(hasher);
+        self.anchored_target.hash(hasher);
+        self.weapon.is_some().hash(hasher);
+        if let Some(weapon) = &self.weapon {
+            weapon.hash_state(hasher);
+        }
+        self.interaction_line_render_latch_point_delta
+            .is_some()
+            .hash(hasher);
+        if let Some(latch_point_delta) = self.interaction_line_render_latch_point_delta {
+            hasher.write_point(latch_point_delta);
+        }
+        hasher.write_vector(self.velocity);
+        hasher.write_vector(self.desired_velocity);
+        hasher.write_f32(self.mass);
+        hasher.write_f32(self.storage);
+        hasher.write_f32(self.max_storage);
+        self.storage_ore_type.hash(hasher);
+        self.last_ore_position.is_some().hash(hasher);
+        if let Some(last_ore_position) = self.last_ore_position {
+            hasher.write_vector(last_ore_position);
+        }
+        hasher.write_usize(self.path.len());
+        for waypoint in &self.path {
+            hasher.write_vector(*waypoint);
+        }
+        self.path_destination.is_some().hash(hasher);
+        if let Some(path_destination) = self.path_destination {
+            hasher.write_vector(path_destination);
+        }
+    }
+}
+
+persist_enum!(UnitParentType {
+    Miner,
+    Scout,
+    Collector
+});
+persist_enum!(CollisionModel { Hard, Soft });
+persist_enum!(Action {
+    None,
+    Attacking,
+    Mining,
+    Collecting,
+    Depositing,
+    Building
+});
+
+impl Persist for Unit {
+    fn save(&self, writer: &mut SaveWriter) {
+        writer.write_label("unit");
+        self.parent_type.save(writer);
+        self.collision_model.save(writer);
+        self.speed.save(writer);
+        self.collect_rate.save(writer);
+        self.range.save(writer);
+        self.weapon.save(writer);
+        self.move_fire.save(writer);
+        self.anchored_target.save(writer);
+        self.current_action.save(writer);
+        self.interaction_line_render_latch_point_delta.save(writer);
+        self.velocity.save(writer);
+        self.desired_velocity.save(writer);
+        self.mass.save(writer);
+        self.storage.save(writer);
+        self.max_storage.save(writer);
+        self.storage_ore_type.save(writer);
+        self.last_ore_position.save(writer);
+        self.path.save(writer);
+        self.path_destination.save(writer);
+    }
+
+    fn load(reader: &mut SaveReader) -> Result<Self, String> {
+        reader.expect_label("unit")?;
+        let parent_type = UnitParentType::load(reader)?;
+        let collision_model = CollisionModel::load(reader)?;
+        Ok(Self {
+            speed: f32::load(reader)?,
+            collect_rate: f32::load(reader)?,
+            range: f32::load(reader)?,
+            weapon: Option::<Weapon>::load(reader)?,
+            move_fire: bool::load(reader)?,
+            anchored_target: Option::<EntID>::load(reader)?,
+            current_action: Action::load(reader)?,
+            interaction_line_render_latch_point_delta: Option::<Point>::load(reader)?,
+            velocity: Vector2D::<f32>::load(reader)?,
+            desired_velocity: Vector2D::<f32>::load(reader)?,
+            mass: f32::load(reader)?,
+            storage: f32::load(reader)?,
+            max_storage: f32::load(reader)?,
+            storage_ore_type: Option::<OreType>::load(reader)?,
+            last_ore_position: Option::<Vector2D<f32>>::load(reader)?,
+            parent_type,
+            collision_model,
+            path: Vec::<Vector2D<f32>>::load(reader)?,
+            path_destination: Option::<Vector2D<f32>>::load(reader)?,
+        })
+    }
 }
@@ -0,0 +1,304 @@
+use std::hash::{Hash, Hasher};
+
+use vector2d::Vector2D;
+
+use crate::consts::values::SPLASH_EDGE_DAMAGE_RATIO;
+
+use super::{
+    damage::DamageType,
+    ent::{Ent, EntID, EntParentType, Owner},
+    projectile::{Projectile, ProjectileKind},
+    save_file::{Persist, SaveReader, SaveWriter},
+    state_hash::StateHasher,
+    world_info::WorldInfo,
+};
+
+// Which kinds of ents a weapon is allowed to shoot at
+#[derive(Copy, Clone, PartialEq, Eq, Hash)]
+pub struct WeaponTargets {
+    pub units: bool,
+    pub structures: bool,
+    pub ore: bool, // Ore patches
+}
+
+impl WeaponTargets {
+    pub const fn allows(&self, parent_type: EntParentType) -> bool {
+        match parent_type {
+            EntParentType::Unit => self.units,
+            EntParentType::Structure => self.structures,
+            EntParentType::OrePatch => self.ore,
+            EntParentType::Ore => false,
+        }
+    }
+}
+
+// A single shot's worth of damage, along with who shot it
+// Outlives the shooter, so projectiles still land after their attacker died
+#[derive(Copy, Clone)]
+pub struct Shot {
+    pub shooter_id: EntID,
+    pub owner: Owner,
+    pub damage: f32,
+    pub damage_type: DamageType,
+    pub splash_radius: Option<f32>,
+    pub targets: WeaponTargets,
+}
+
+impl Shot {
+    // Deals damage to the target (if any), along with anything caught in the splash around the impact
+    // Splash never hurts the shooter's own team, and gets weaker the further away from the impact it hits
+    pub fn land(
+        &self,
+        target_id: Option<EntID>,
+        impact_position: Option<Vector2D<f32>>,
+        world_info: &mut WorldInfo,
+    ) {
+        if let Some(target_id) = target_id {
+            world_info.damage_ent(target_id, self.damage, self.damage_type, self.shooter_id);
+        }
+
+        let (Some(splash_radius), Some(impact_position)) = (self.splash_radius, impact_position)
+        else {
+            return;
+        };
+        for (ent_id, distance) in world_info.get_ents_in_radius(impact_position, splash_radius) {
+            if Some(ent_id) == target_id
+                || ent_id == self.shooter_id
+                || world_info.get_ent_owner_by_id(ent_id) == Some(self.owner)
+                || !world_info
+                    .get_ent_parent_type_by_id(ent_id)
+                    .is_some_and(|parent_type| self.targets.allows(parent_type))
+            {
+                continue;
+            }
+            let falloff =
+                1.0 - (1.0 - SPLASH_EDGE_DAMAGE_RATIO) * (distance / splash_radius).min(1.0);
+            world_info.damage_ent(
+                ent_id,
+                self.damage * falloff,
+                self.damage_type,
+                self.shooter_id,
+            );
+        }
+    }
+
+    pub fn hash_state(&self, hasher: &mut StateHasher) {
+        self.shooter_id.hash(hasher);
+        self.owner.hash(hasher);
+        hasher.write_f32(self.damage);
+        self.damage_type.hash(hasher);
+        self.splash_radius.is_some().hash(hasher);
+        if let Some(splash_radius) = self.splash_radius {
+            hasher.write_f32(splash_radius);
+        }
+        self.targets.hash(hasher);
+    }
+}
+
+// Something a unit shoots with
+// Shots go off in discrete steps: wind up, fire, then cool down, all counted in ticks so it stays in line with the fixed timestep
+pub struct Weapon {
+    pub damage: f32, // How much damage a single shot deals
+    pub damage_type: DamageType,
+    pub cooldown_ticks: u32, // How long (in ticks) it takes to shoot again after firing
+    pub range: f32,          // How far away it can shoot
+    pub windup_ticks: u32,   // How long (in ticks) it takes to aim before a shot goes off
+    pub targets: WeaponTargets, // What it can shoot at
+    pub splash_radius: Option<f32>, // Shots also hit everything it can target this close to the target
+    pub projectile: Option<ProjectileKind>, // Shots fly over as projectiles, instead of hitting right away
+    cooldown_left: u32,
+    windup_left: Option<u32>, // Some while winding up a shot
+}
+
+impl Weapon {
+    pub fn new(
+        damage: f32,
+        damage_type: DamageType,
+        cooldown_ticks: u32,
+        range: f32,
+        windup_ticks: u32,
+        targets: WeaponTargets,
+    ) -> Self {
+        Self {
+            damage,
+            damage_type,
+            cooldown_ticks,
+            range,
+            windup_ticks,
+            targets,
+            splash_radius: None,
+            projectile: None,
+            cooldown_left: 0,
+            windup_left: None,
+        }
+    }
+
+    pub fn with_splash(mut self, splash_radius: f32) -> Self {
+        self.splash_radius = Some(splash_radius);
+        self
+    }
+
+    pub fn with_projectile(mut self, projectile: ProjectileKind) -> Self {
+        self.projectile = Some(projectile);
+        self
+    }
+
+    // Should be called once at the start of every tick, whether shooting or not
+    pub fn tick(&mut self) {
+        self.cooldown_left = self.cooldown_left.saturating_sub(1);
+    }
+
+    // Keeps pulling the trigger; Returns whether a shot went off this tick
+    // Every shot goes off windup_ticks after the trigger got pulled, and the cooldown only starts once it did,
+    // so shots go off exactly windup_ticks + cooldown_ticks apart
+    pub fn try_fire(&mut self) -> bool {
+        if let Some(windup_left) = self.windup_left {
+            if windup_left > 0 {
+                self.windup_left = Some(windup_left - 1);
+                return false;
+            }
+            self.windup_left = None;
+            self.cooldown_left = self.cooldown_ticks;
+            return true;
+        }
+        if self.cooldown_left > 0 {
+            return false;
+        }
+        if self.windup_ticks == 0 {
+            self.cooldown_left = self.cooldown_ticks;
+            return true;
+        }
+        self.windup_left = Some(self.windup_ticks - 1);
+        false
+    }
+
+    // Lets go of the trigger; A shot that was being aimed is lost, and has to be aimed all over again
+    pub fn cancel_windup(&mut self) {
+        self.windup_left = None;
+    }
+
+    pub fn can_target(&self, parent_type: EntParentType) -> bool {
+        self.targets.allows(parent_type)
+    }
+
+    pub fn shot(&self, shooter: &Ent) -> Shot {
+        Shot {
+            shooter_id: shooter.id,
+            owner: shooter.owner,
+            damage: self.damage,
+            damage_type: self.damage_type,
+            splash_radius: self.splash_radius,
+            targets: self.targets,
+        }
+    }
+
+    // Fires a shot at the target; It either hits right away, or gets launched as a projectile
+    pub fn hit(&self, shooter: &Ent, target_id: EntID, world_info: &mut WorldInfo) {
+        let target_position = world_info.get_ent_rect_center_poisition_by_id(target_id);
+        let Some(projectile_kind) = self.projectile else {
+            self.shot(shooter)
+                .land(Some(target_id), target_position, world_info);
+            return;
+        };
+        if let Some(target_position) = target_position {
+            let shooter_center = shooter.get_rect().center();
+            world_info.launch_projectile(Projectile::new(
+                self.shot(shooter),
+                projectile_kind,
+                target_id,
+                Vector2D::<f32>::new(shooter_center.x as f32, shooter_center.y as f32),
+                target_position,
+            ));
+        }
+    }
+
+    pub fn hash_state(&self, hasher: &mut StateHasher) {
+        hasher.write_f32(self.damage);
+        self.damage_type.hash(hasher);
+        hasher.write_u32(self.cooldown_ticks);
+        hasher.write_f32(self.range);
+        hasher.write_u32(self.windup_ticks);
+        self.targets.hash(hasher);
+        self.splash_radius.is_some().hash(hasher);
+        if let Some(splash_radius) = self.splash_radius {
+            hasher.write_f32(splash_radius);
+        }
+        self.projectile.is_some().hash(hasher);
+        if let Some(projectile) = self.projectile {
+            hasher.write_f32(projectile.speed);
+            projectile.motion.hash(hasher);
+        }
+        hasher.write_u32(self.cooldown_left);
+        self.windup_left.hash(hasher);
+    }
+}
+
+impl Persist for WeaponTargets {
+    fn save(&self, writer: &mut SaveWriter) {
+        self.units.save(writer);
+        self.structures.save(writer);
+        self.ore.save(writer);
+    }
+
+    fn load(reader: &mut SaveReader) -> Result<Self, String> {
+        Ok(Self {
+            units: bool::load(reader)?,
+            structures: bool::load(reader)?,
+            ore: bool::load(reader)?,
+        })
+    }
+}
+
+impl Persist for Shot {
+    fn save(&self, writer: &mut SaveWriter) {
+        self.shooter_id.save(writer);
+        self.owner.save(writer);
+        self.damage.save(writer);
+        self.damage_type.save(writer);
+        self.splash_radius.save(writer);
+        self.targets.save(writer);
+    }
+
+    fn load(reader: &mut SaveReader) -> Result<Self, String> {
+        Ok(Self {
+            shooter_id: EntID::load(reader)?,
+            owner: Owner::load(reader)?,
+            damage: f32::load(reader)?,
+            damage_type: DamageType::load(reader)?,
+            splash_radius: Option::<f32>::load(reader)?,
+            targets: WeaponTargets::load(reader)?,
+        })
+    }
+}
+
+impl Persist for Weapon {
+    fn save(&self, writer: &mut SaveWriter) {
+        writer.write_label("weapon");
+        self.damage.save(writer);
+        self.damage_type.save(writer);
+        self.cooldown_ticks.save(writer);
+        self.range.save(writer);
+        self.windup_ticks.save(writer);
+        self.targets.save(writer);
+        self.splash_radius.save(writer);
+        self.projectile.save(writer);
+        self.cooldown_left.save(writer);
+        self.windup_left.save(writer);
+    }
+
+    fn load(reader: &mut SaveReader) -> Result<Self, String> {
+        reader.expect_label("weapon")?;
+        Ok(Self {
+            damage: f32::load(reader)?,
+            damage_type: DamageType::load(reader)?,
+            cooldown_ticks: u32::load(reader)?,
+            range: f32::load(reader)?,
+            windup_ticks: u32::load(reader)?,
+            targets: WeaponTargets::load(reader)?,
+            splash_radius: Option::<f32>::load(reader)?,
+            projectile: Option::<ProjectileKind>::load(reader)?,
+            cooldown_left: u32::load(reader)?,
+            windup_left: Option::<u32>::load(reader)?,
+        })
+    }
+}
@@ -1,32 +1,478 @@
-use sdl2::{rect::Rect, render::Canvas, video::Window};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    hash::{Hash, Hasher},
+};
+
+use sdl2::rect::Rect;
+use vector2d::Vector2D;
 
 use crate::{
-    consts::values::{MAP_HEIGHT, MAP_PADDING, MAP_WIDTH, SCREEN_BACKGROUND_COLOR},
-    enums::{game_object::GameObject, unit_type::UnitType},
+    consts::{
+        debug_flags::DEBUG_CAN_CONTROL_CPU,
+        helper::{
+            empty_ent_target, new_construction_site, structure_cost, structure_rect_size, unit_cost,
+        },
+        values::{
+            CONSTRUCTION_CANCEL_REFUND_RATIO, DEFAULT_SEED, MAINFRAME_SUPPLY,
+            PLAYER_INCOME_WINDOW_TICKS, UNIT_SUPPLY_COST,
+        },
+    },
+    enums::{
+        command::Command, game_object::GameObject, structure_type::StructureType,
+        unit_type::UnitType,
+    },
 };
 
-use super::{camera::Camera, ent::EntID, selection::Selection, world_info::WorldInfo};
+use super::{
+    ai_player::AiPlayer,
+    damage::DamageEvent,
+    ent::{Ent, EntID, EntIDAllocator, Owner},
+    formation::formation_spots,
+    order::{EntTarget, Order, OrderType},
+    ore_patch::OreType,
+    player_state::PlayerState,
+    projectile::Projectile,
+    save_file::{Persist, SaveReader, SaveWriter},
+    selection::Selection,
+    sim_rng::SimRng,
+    state_hash::StateHasher,
+    world_info::WorldInfo,
+};
 
 pub struct World {
     pub game_objects: Vec<GameObject>,
     pub selection: Selection,
+    pub rng: SimRng,             // All simulation randomness must come from here
+    pub ent_ids: EntIDAllocator, // All new ents must grab their EntID from here
+    pub current_tick: u64,       // How many ticks have been simulated so far
+    pub players: BTreeMap<Owner, PlayerState>, // Resources, income and supply of everyone playing
+    pub ai_players: BTreeMap<Owner, AiPlayer>, // Owners played by the computer
+    pub projectiles: Vec<Projectile>, // Shots currently in flight
+    pub damage_events: Vec<DamageEvent>, // Every hit landed during the last tick; Only there to be looked at, the simulation never reads it
+}
+
+impl Default for World {
+    fn default() -> Self {
+        Self::new(DEFAULT_SEED)
+    }
 }
 
 impl World {
-    pub fn new() -> Self {
+    pub fn new(seed: u64) -> Self {
         Self {
             game_objects: Vec::<GameObject>::new(),
             selection: Selection::new(),
+            rng: SimRng::new(seed),
+            ent_ids: EntIDAllocator::new(),
+            current_tick: 0,
+            players: BTreeMap::from([
+                (Owner::Player, PlayerState::new()),
+                (Owner::Cpu, PlayerState::new()),
+            ]),
+            ai_players: BTreeMap::new(),
+            projectiles: Vec::<Projectile>::new(),
+            damage_events: Vec::<DamageEvent>::new(),
+        }
+    }
+
+    pub fn player_state(&self, owner: Owner) -> Option<&PlayerState> {
+        self.players.get(&owner)
+    }
+
+    pub fn player_state_mut(&mut self, owner: Owner) -> Option<&mut PlayerState> {
+        self.players.get_mut(&owner)
+    }
+
+    // Applies a command, skipping whatever the issuer is not allowed to do
+    // Everyone (input, AI, replays, ...) goes through here, so all validation lives here
+    pub fn apply_command(&mut self, command: &Command, world_info: &mut WorldInfo) {
+        match command {
+            Command::IssueOrder {
+                issuer,
+                units,
+                order,
+                queued,
+            } => {
+                for (ent, unit_type) in
+                    Self::commanded_units(&mut self.game_objects, *issuer, units)
+                {
+                    // Cannot target yourself.. for now!
+                    if order.ent_target.ent_id == Some(ent.id) {
+                        continue;
+                    }
+                    // Cannot attack, mine or collect from an ent on the same team!
+                    if !matches!(
+                        order.order_type,
+                        OrderType::Follow | OrderType::Deposit | OrderType::Build
+                    ) && order.ent_target.ent_owner == Some(ent.owner)
+                    {
+                        continue;
+                    }
+                    // Check if the unit can actually perform this order
+                    if !unit_type.can_perform(order.order_type) {
+                        continue;
+                    }
+                    // A new patrol route loops back to where the unit sets off from
+                    // Patrol points queued onto an existing route go in right before its way back
+                    if order.order_type == OrderType::Patrol {
+                        let route_return = ent
+                            .orders
+                            .iter()
+                            .rposition(|order| order.order_type == OrderType::Patrol)
+                            .filter(|_| *queued);
+                        if let Some(route_return) = route_return {
+                            ent.orders.insert(route_return, *order);
+                            continue;
+                        }
+                        let ent_rect_center = ent.get_rect().center();
+                        let patrol_start: Vector2D<f32> = match ent.orders.last() {
+                            Some(order) if *queued => order.current_move_target,
+                            _ => Vector2D::<f32>::new(
+                                ent_rect_center.x as f32,
+                                ent_rect_center.y as f32,
+                            ),
+                        };
+                        ent.add_order(*order, !queued);
+                        ent.add_order(
+                            Order::new(OrderType::Patrol, patrol_start, empty_ent_target()),
+                            false,
+                        );
+                        continue;
+                    }
+                    ent.add_order(*order, !queued);
+                }
+            }
+            Command::FormationMove {
+                issuer,
+                units,
+                destination,
+                facing,
+                queued,
+            } => {
+                // Units set off from where they are, or from where their last order leaves them if queueing
+                let mut members: Vec<(EntID, Vector2D<f32>)> = Vec::<(EntID, Vector2D<f32>)>::new();
+                let mut slowest_speed = f32::MAX;
+                for (ent, unit_type) in
+                    Self::commanded_units(&mut self.game_objects, *issuer, units)
+                {
+                    if !unit_type.can_perform(OrderType::Move) {
+                        continue;
+                    }
+                    let ent_rect_center = ent.get_rect().center();
+                    let start = match ent.orders.last() {
+                        Some(order) if *queued => order.current_move_target,
+                        _ => {
+                            Vector2D::<f32>::new(ent_rect_center.x as f32, ent_rect_center.y as f32)
+                        }
+                    };
+                    members.push((ent.id, start));
+                    match unit_type {
+                        UnitType::Scout(unit)
+                        | UnitType::Miner(unit)
+                        | UnitType::Collector(unit) => {
+                            slowest_speed = slowest_speed.min(unit.speed);
+                        }
+                    }
+                }
+                let spots = formation_spots(&members, *destination, *facing);
+                // A lone unit has nobody to keep pace with
+                let speed_limit = (members.len() > 1).then_some(slowest_speed);
+                for (ent, _) in Self::commanded_units(&mut self.game_objects, *issuer, units) {
+                    if let Some(spot) = spots.get(&ent.id) {
+                        let mut order = Order::new(OrderType::Move, *spot, empty_ent_target());
+                        order.speed_limit = speed_limit;
+                        order.formation_anchor = Some(*destination);
+                        ent.add_order(order, !queued);
+                    }
+                }
+            }
+            Command::Stop { issuer, units } => {
+                for (ent, unit_type) in
+                    Self::commanded_units(&mut self.game_objects, *issuer, units)
+                {
+                    match unit_type {
+                        UnitType::Scout(unit)
+                        | UnitType::Miner(unit)
+                        | UnitType::Collector(unit) => unit.stop(ent),
+                    }
+                }
+            }
+            Command::HoldPosition {
+                issuer,
+                units,
+                queued,
+            } => {
+                for (ent, _) in Self::commanded_units(&mut self.game_objects, *issuer, units) {
+                    // If queueing, the unit will hold wherever its last order leaves it
+                    let hold_position_spot: Vector2D<f32> = match ent.orders.last() {
+                        Some(order) if *queued => order.current_move_target,
+                        _ => ent.position,
+                    };
+                    let hold_position_order = Order::new(
+                        OrderType::HoldPosition,
+                        hold_position_spot,
+                        empty_ent_target(),
+                    );
+                    ent.add_order(hold_position_order, !queued);
+                }
+            }
+            Command::Select { issuer, ents, add } => {
+                // The selection belongs to the local player
+                if *issuer != Owner::Player || ents.is_empty() {
+                    return;
+                }
+                let ents: BTreeSet<EntID> = ents.iter().copied().collect();
+                for game_object in &mut self.game_objects {
+                    match game_object {
+                        GameObject::Unit(ent, _)
+                        | GameObject::Structure(ent, _)
+                        | GameObject::OrePatch(ent, _)
+                        | GameObject::Ore(ent, _) => {
+                            if ents.contains(&ent.id) {
+                                ent.select();
+                            } else if !add {
+                                ent.deselect();
+                            }
+                        }
+                    }
+                }
+            }
+            Command::ClearSelection { issuer } => {
+                if *issuer != Owner::Player {
+                    return;
+                }
+                for game_object in &mut self.game_objects {
+                    match game_object {
+                        GameObject::Unit(ent, _)
+                        | GameObject::Structure(ent, _)
+                        | GameObject::OrePatch(ent, _)
+                        | GameObject::Ore(ent, _) => ent.deselect(),
+                    }
+                }
+            }
+            Command::EnqueueProduction {
+                issuer,
+                structure,
+                unit_type,
+            } => {
+                let Some(player_state) = self.players.get_mut(issuer) else {
+                    return;
+                };
+                for (_, structure_type) in
+                    Self::commanded_structures(&mut self.game_objects, *issuer, &[*structure])
+                {
+                    match structure_type {
+                        StructureType::Mainframe(structure) | StructureType::Spawner(structure) => {
+                            // Only pay for what actually fits in the queue
+                            if structure.can_enqueue(*unit_type).is_err()
+                                || player_state.spend(&unit_cost(*unit_type)).is_err()
+                            {
+                                continue;
+                            }
+                            structure.enqueue(*unit_type).ok();
+                        }
+                    }
+                }
+            }
+            Command::CancelProduction {
+                issuer,
+                structure,
+                index,
+            } => {
+                let Some(player_state) = self.players.get_mut(issuer) else {
+                    return;
+                };
+                for (_, structure_type) in
+                    Self::commanded_structures(&mut self.game_objects, *issuer, &[*structure])
+                {
+                    match structure_type {
+                        StructureType::Mainframe(structure) | StructureType::Spawner(structure) => {
+                            if let Some(unit_type) = structure.cancel(*index) {
+                                player_state.refund(&unit_cost(unit_type));
+                            }
+                        }
+                    }
+                }
+            }
+            Command::SetRallyPoint {
+                issuer,
+                structures,
+                rally_point,
+            } => {
+                for (ent, structure_type) in
+                    Self::commanded_structures(&mut self.game_objects, *issuer, structures)
+                {
+                    // Rallying onto itself would leave new units following a building around
+                    if rally_point.is_some_and(|order| order.ent_target.ent_id == Some(ent.id)) {
+                        continue;
+                    }
+                    match structure_type {
+                        StructureType::Mainframe(structure) | StructureType::Spawner(structure) => {
+                            structure.set_rally_point(*rally_point)
+                        }
+                    }
+                }
+            }
+            Command::PlaceStructure {
+                issuer,
+                builder,
+                structure_type,
+                position,
+                queued,
+            } => {
+                let can_build = Self::commanded_units(&mut self.game_objects, *issuer, &[*builder])
+                    .iter()
+                    .any(|(_, unit_type)| unit_type.can_perform(OrderType::Build));
+                let rect_size = structure_rect_size(*structure_type) as u32;
+                let site_rect =
+                    Rect::new(position.x as i32, position.y as i32, rect_size, rect_size);
+                if !can_build || !structure_type.is_buildable() || !world_info.can_place(site_rect)
+                {
+                    return;
+                }
+                let Some(player_state) = self.players.get_mut(issuer) else {
+                    return;
+                };
+                if player_state
+                    .spend(&structure_cost(*structure_type))
+                    .is_err()
+                {
+                    return;
+                }
+
+                let site = new_construction_site(
+                    world_info,
+                    self.ent_ids.next_id(),
+                    *structure_type,
+                    *issuer,
+                    *position,
+                );
+                let build_order = match &site {
+                    GameObject::Structure(site_ent, _) => Order::new(
+                        OrderType::Build,
+                        Vector2D::<f32>::new(
+                            site_rect.center().x as f32,
+                            site_rect.center().y as f32,
+                        ),
+                        EntTarget {
+                            ent_id: Some(site_ent.id),
+                            ent_rect: Some(site_rect),
+                            ent_owner: Some(site_ent.owner),
+                            ent_parent_type: Some(site_ent.parent_type()),
+                        },
+                    ),
+                    _ => return,
+                };
+                self.game_objects.push(site);
+                for (ent, _) in Self::commanded_units(&mut self.game_objects, *issuer, &[*builder])
+                {
+                    ent.add_order(build_order, !queued);
+                }
+            }
+            Command::CancelConstruction { issuer, structure } => {
+                let Some(player_state) = self.players.get_mut(issuer) else {
+                    return;
+                };
+                for (ent, structure_type) in
+                    Self::commanded_structures(&mut self.game_objects, *issuer, &[*structure])
+                {
+                    match structure_type {
+                        StructureType::Mainframe(structure) | StructureType::Spawner(structure) => {
+                            if !structure.is_under_construction() {
+                                continue;
+                            }
+                            let refund: Vec<(OreType, f32)> =
+                                structure_cost(structure.parent_type())
+                                    .into_iter()
+                                    .map(|(ore_type, amount)| {
+                                        (ore_type, amount * CONSTRUCTION_CANCEL_REFUND_RATIO)
+                                    })
+                                    .collect();
+                            player_state.refund(&refund);
+                            // Gets cleaned up next tick, like anything else that died
+                            world_info.remove_ent_by_id(ent.id);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    // Grabs the units listed in a command that the issuer is allowed to control
+    fn commanded_units<'a>(
+        game_objects: &'a mut [GameObject],
+        issuer: Owner,
+        units: &[EntID],
+    ) -> Vec<(&'a mut Ent, &'a mut UnitType)> {
+        let units: BTreeSet<EntID> = units.iter().copied().collect();
+        game_objects
+            .iter_mut()
+            .filter_map(|game_object| match game_object {
+                GameObject::Unit(ent, unit_type)
+                    if units.contains(&ent.id)
+                        && (ent.owner == issuer || DEBUG_CAN_CONTROL_CPU) =>
+                {
+                    Some((ent, unit_type))
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    // Grabs the structures listed in a command that the issuer is allowed to control
+    fn commanded_structures<'a>(
+        game_objects: &'a mut [GameObject],
+        issuer: Owner,
+        structures: &[EntID],
+    ) -> Vec<(&'a mut Ent, &'a mut StructureType)> {
+        let structures: BTreeSet<EntID> = structures.iter().copied().collect();
+        game_objects
+            .iter_mut()
+            .filter_map(|game_object| match game_object {
+                GameObject::Structure(ent, structure_type)
+                    if structures.contains(&ent.id)
+                        && (ent.owner == issuer || DEBUG_CAN_CONTROL_CPU) =>
+                {
+                    Some((ent, structure_type))
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    // Lets every computer player look at the world, then applies whatever they came up with
+    // Runs before anything else ticks, just like commands coming from input
+    fn tick_ai_players(&mut self, world_info: &mut WorldInfo) {
+        let mut ai_players = std::mem::take(&mut self.ai_players);
+        let mut ai_commands: Vec<Command> = Vec::<Command>::new();
+        for ai_player in ai_players.values_mut() {
+            ai_commands.append(&mut ai_player.think(self, world_info));
+        }
+        self.ai_players = ai_players;
+        for command in &ai_commands {
+            self.apply_command(command, world_info);
         }
     }
 
     // TODO: Breakup this method into smaller methods
     pub fn tick(&mut self, world_info: &mut WorldInfo) {
+        self.tick_ai_players(world_info);
+        world_info.regenerate_shields();
+
         // Tick units
         // Store a list of any new gameobjects that are to be spawned after this tick
         let mut game_object_spawn_list: Vec<GameObject> = Vec::<GameObject>::new();
         // Also, store the index of any units that are to be removed after this tick
         let mut ent_cleanup_list: Vec<EntID> = Vec::<EntID>::new();
+        // Keep track of where every ent was before this tick, so rendering can interpolate
+        for game_object in &mut self.game_objects {
+            match game_object {
+                GameObject::Unit(ent, _)
+                | GameObject::Structure(ent, _)
+                | GameObject::OrePatch(ent, _)
+                | GameObject::Ore(ent, _) => ent.previous_position = ent.position,
+            }
+        }
         for game_object in &mut self.game_objects {
             match game_object {
                 GameObject::Unit(ent, unit) => {
@@ -37,7 +483,7 @@ impl World {
                             | UnitType::Miner(unit)
                             | UnitType::Collector(unit) => {
                                 // If so, tick and update world_info
-                                unit.tick(ent, world_info);
+                                unit.tick(ent, world_info, &mut self.rng);
                                 world_info.update_ent(ent);
                             }
                         }
@@ -51,7 +497,9 @@ impl World {
                 {
                     if world_info.has_ent(ent) {
                         // If so, tick and update world_info
-                        if let Some(new_ore) = ore_patch.tick(ent, world_info) {
+                        if let Some(new_ore) =
+                            ore_patch.tick(ent, world_info, &mut self.rng, &mut self.ent_ids)
+                        {
                             game_object_spawn_list.push(new_ore);
                         }
                         world_info.update_ent(ent);
@@ -72,7 +520,112 @@ impl World {
                         ent_cleanup_list.push(ent.id);
                     }
                 }
-                GameObject::Structure(_ent, _structure) => (),
+                GameObject::Structure(ent, structure) =>
+                // Check if this structure's entity still exists in the world
+                {
+                    if world_info.has_ent(ent) {
+                        match structure {
+                            StructureType::Mainframe(structure)
+                            | StructureType::Spawner(structure) => {
+                                // If so, tick and update world_info
+                                game_object_spawn_list.append(&mut structure.tick(
+                                    ent,
+                                    world_info,
+                                    &mut self.ent_ids,
+                                    self.players.get_mut(&ent.owner),
+                                ));
+                                world_info.update_ent(ent);
+                            }
+                        }
+                    } else {
+                        // If not, add to cleanup list
+                        ent_cleanup_list.push(ent.id);
+                    }
+                }
+            }
+        }
+
+        // Fly projectiles, landing the ones that arrived, then put in the ones fired this tick
+        self.projectiles
+            .retain_mut(|projectile| projectile.tick(world_info));
+        self.projectiles
+            .append(&mut world_info.take_projectile_launches());
+
+        // Put in any construction work that got done this tick
+        let construction_work = world_info.take_construction_work();
+        for game_object in &mut self.game_objects {
+            if let GameObject::Structure(ent, structure_type) = game_object {
+                if !construction_work.contains(&ent.id) || !world_info.has_ent(ent) {
+                    continue;
+                }
+                match structure_type {
+                    StructureType::Mainframe(structure) | StructureType::Spawner(structure) => {
+                        structure.construct(ent, world_info);
+                        world_info.update_ent(ent);
+                    }
+                }
+            }
+        }
+
+        // Let spawners know about any damage dealt to the ore patches around them
+        // Only built spawners pay attention, and never to their own team
+        for (ore_patch_position, attacker_id, damage) in world_info.take_ore_patch_damage() {
+            let (Some(attacker_position), Some(attacker_owner)) = (
+                world_info.get_ent_rect_center_poisition_by_id(attacker_id),
+                world_info.get_ent_owner_by_id(attacker_id),
+            ) else {
+                continue;
+            };
+            for game_object in &mut self.game_objects {
+                if let GameObject::Structure(ent, StructureType::Spawner(structure)) = game_object {
+                    if ent.owner == attacker_owner
+                        || structure.is_under_construction()
+                        || !world_info.has_ent(ent)
+                    {
+                        continue;
+                    }
+                    let spawner_center = ent.get_rect().center();
+                    let distance =
+                        (Vector2D::<f32>::new(spawner_center.x as f32, spawner_center.y as f32)
+                            - ore_patch_position)
+                            .length();
+                    if let Some(spawner) = structure.spawner_mut() {
+                        if distance <= spawner.aggro_radius {
+                            spawner.provoke(damage, attacker_id, attacker_position);
+                        }
+                    }
+                }
+            }
+        }
+
+        // Hand this tick's hits over to whoever wants to know about them
+        self.damage_events = world_info.take_damage_events();
+
+        // Bank any ore that got beamed in this tick
+        for (owner, ore_type, amount) in world_info.take_deposits() {
+            if let Some(player_state) = self.players.get_mut(&owner) {
+                player_state.deposit(ore_type, amount);
+            }
+        }
+
+        // Run death hooks for units that died this tick, so they get cleaned up right away
+        for game_object in &mut self.game_objects {
+            if let GameObject::Unit(ent, unit_type) = game_object {
+                if world_info.has_ent(ent) {
+                    continue;
+                }
+                if !ent_cleanup_list.contains(&ent.id) {
+                    ent_cleanup_list.push(ent.id);
+                }
+                match unit_type {
+                    UnitType::Scout(unit) | UnitType::Miner(unit) | UnitType::Collector(unit) => {
+                        game_object_spawn_list.append(&mut unit.on_death(
+                            ent,
+                            world_info,
+                            &mut self.ent_ids,
+                        ));
+                    }
+                }
             }
         }
 
@@ -80,7 +633,6 @@ impl World {
         self.game_objects.append(&mut game_object_spawn_list);
 
         // Remove dead game objects
-        // TODO: collectors must drop all storage on death on an ore ball
         self.game_objects.retain(|game_object| match game_object {
             GameObject::Unit(ent, _)
             | GameObject::Structure(ent, _)
@@ -88,6 +640,9 @@ impl World {
             | GameObject::Ore(ent, _) => !ent_cleanup_list.contains(&ent.id),
         });
 
+        // Recount supply, now that this tick's dead are gone
+        self.update_supply();
+
         // Tick orders
         for game_object in &mut self.game_objects {
             match game_object {
@@ -118,83 +673,154 @@ impl World {
                 _ => (),
             }
         }
-    }
 
-    pub fn draw(
-        &mut self,
-        canvas: &mut Canvas<Window>,
-        world_info: &mut WorldInfo,
-        camera: &mut Camera,
-    ) {
-        // Clear screen
-        canvas.set_draw_color(SCREEN_BACKGROUND_COLOR);
-        canvas.set_scale(camera.scale.x, camera.scale.y).ok();
-
-        // Set viewport to cover whole map
-        canvas.set_viewport(Rect::new(
-            0 - MAP_PADDING as i32,
-            0 - MAP_PADDING as i32,
-            MAP_WIDTH + MAP_PADDING * 2,
-            MAP_HEIGHT + MAP_PADDING * 2,
-        ));
-
-        // Clear it
-        canvas.fill_rect(camera.get_scaled_screen_area()).ok();
-
-        // Set viewport back to where the camera is
-        canvas.set_viewport(Rect::new(
-            camera.position.x,
-            camera.position.y,
-            canvas.viewport().width(),
-            canvas.viewport().height(),
-        ));
-
-        // Draw unit orders
-        for game_object in &mut self.game_objects {
-            match game_object {
-                GameObject::Unit(ent, unit) => match unit {
-                    UnitType::Scout(unit) | UnitType::Miner(unit) | UnitType::Collector(unit) => {
-                        unit.draw_orders(ent, canvas)
+        // Evict flow fields that no order heads for anymore
+        let mut flow_field_destinations: Vec<Vector2D<f32>> = Vec::<Vector2D<f32>>::new();
+        for game_object in &self.game_objects {
+            if let GameObject::Unit(ent, _) = game_object {
+                for order in &ent.orders {
+                    if order.order_type.uses_flow_field() {
+                        flow_field_destinations
+                            .push(order.formation_anchor.unwrap_or(order.current_move_target));
                     }
-                },
-                GameObject::Structure(_ent, _structure) => todo!(),
-                GameObject::OrePatch(_ent, _ore) => (),
-                GameObject::Ore(_ent, _ore) => (),
+                }
             }
         }
+        world_info.evict_unused_flow_fields(&flow_field_destinations);
 
-        // Draw game_objects
-        for game_object in &mut self.game_objects {
+        self.current_tick += 1;
+
+        // Roll income windows over
+        if self.current_tick.is_multiple_of(PLAYER_INCOME_WINDOW_TICKS) {
+            for player_state in self.players.values_mut() {
+                player_state.update_income_rate();
+            }
+        }
+    }
+
+    // Supply used comes from living units, supply cap from structures
+    fn update_supply(&mut self) {
+        for player_state in self.players.values_mut() {
+            player_state.supply_used = 0;
+            player_state.supply_cap = 0;
+        }
+        for game_object in &self.game_objects {
             match game_object {
-                GameObject::Unit(ent, unit) => match unit {
-                    UnitType::Scout(unit) | UnitType::Miner(unit) | UnitType::Collector(unit) => {
-                        unit.draw(ent, canvas)
+                GameObject::Unit(ent, _) => {
+                    if let Some(player_state) = self.players.get_mut(&ent.owner) {
+                        player_state.supply_used += UNIT_SUPPLY_COST;
+                    }
+                }
+                GameObject::Structure(ent, StructureType::Mainframe(structure))
+                    if !structure.is_under_construction() =>
+                {
+                    if let Some(player_state) = self.players.get_mut(&ent.owner) {
+                        player_state.supply_cap += MAINFRAME_SUPPLY;
                     }
-                },
-                GameObject::OrePatch(ent, ore_patch) => ore_patch.draw(ent, canvas),
-                GameObject::Ore(ent, ore) => ore.draw(ent, canvas),
-                GameObject::Structure(_ent, _structure) => todo!(),
+                }
+                GameObject::Structure(_, _)
+                | GameObject::OrePatch(_, _)
+                | GameObject::Ore(_, _) => (),
             }
         }
+    }
 
-        // Draw attack lines
-        for game_object in &mut self.game_objects {
+    // Fingerprints the whole simulation state
+    // Same seed plus same input stream must always produce the same hash after N ticks
+    pub fn state_hash(&self, world_info: &WorldInfo) -> u64 {
+        let mut hasher = StateHasher::new();
+        hasher.write_u64(self.current_tick);
+        hasher.write_u64(self.rng.state());
+        self.ent_ids.hash(&mut hasher);
+        hasher.write_usize(self.players.len());
+        for (owner, player_state) in &self.players {
+            owner.hash(&mut hasher);
+            player_state.hash_state(&mut hasher);
+        }
+        hasher.write_usize(self.ai_players.len());
+        for ai_player in self.ai_players.values() {
+            ai_player.hash_state(&mut hasher);
+        }
+        hasher.write_usize(self.projectiles.len());
+        for projectile in &self.projectiles {
+            projectile.hash_state(&mut hasher);
+        }
+        hasher.write_usize(self.game_objects.len());
+        for game_object in &self.game_objects {
             match game_object {
-                GameObject::Unit(ent, unit) => match unit {
-                    UnitType::Scout(unit) | UnitType::Miner(unit) | UnitType::Collector(unit) => {
-                        unit.draw_attack_lines(ent, canvas)
+                GameObject::Unit(ent, unit_type) => {
+                    ent.hash_state(&mut hasher);
+                    match unit_type {
+                        UnitType::Scout(unit)
+                        | UnitType::Miner(unit)
+                        | UnitType::Collector(unit) => unit.hash_state(&mut hasher),
                     }
-                },
-                GameObject::Structure(_ent, _structure) => todo!(),
-                GameObject::OrePatch(_ent, _ore) => (),
-                GameObject::Ore(_ent, _) => (),
+                }
+                GameObject::Structure(ent, structure_type) => {
+                    ent.hash_state(&mut hasher);
+                    match structure_type {
+                        StructureType::Mainframe(structure) | StructureType::Spawner(structure) => {
+                            structure.hash_state(&mut hasher)
+                        }
+                    }
+                }
+                GameObject::OrePatch(ent, ore_patch) => {
+                    ent.hash_state(&mut hasher);
+                    ore_patch.hash_state(&mut hasher);
+                }
+                GameObject::Ore(ent, ore) => {
+                    ent.hash_state(&mut hasher);
+                    ore.hash_state(&mut hasher);
+                }
             }
         }
+        world_info.hash_state(&mut hasher);
+        hasher.finish()
+    }
 
-        // Draw Health Bars
-        world_info.draw_health_bars(canvas);
+    // Writes this world to a save file
+    // Ent hp lives in world_info first, so it gets saved from there;
+    // Ents that world_info no longer knows about are dead and would be cleaned up next tick anyways
+    pub fn save_with_info(&self, writer: &mut SaveWriter, world_info: &WorldInfo) {
+        writer.write_label("world");
+        self.current_tick.save(writer);
+        self.rng.save(writer);
+        self.ent_ids.save(writer);
+        self.players.save(writer);
+        self.ai_players.save(writer);
+        self.projectiles.save(writer);
+        let live_game_objects: Vec<&GameObject> = self
+            .game_objects
+            .iter()
+            .filter(|game_object| match game_object {
+                GameObject::Unit(ent, _)
+                | GameObject::Structure(ent, _)
+                | GameObject::OrePatch(ent, _)
+                | GameObject::Ore(ent, _) => world_info.has_ent(ent),
+            })
+            .collect();
+        live_game_objects.len().save(writer);
+        for game_object in live_game_objects {
+            game_object.save(writer);
+        }
+        writer.write_label("world_info");
+        world_info.save_hp(writer);
+    }
 
-        // Draw selection box
-        self.selection.draw(canvas);
+    // Reads a world back from a save file, rebuilding its world_info along the way
+    pub fn load_with_info(reader: &mut SaveReader) -> Result<(Self, WorldInfo), String> {
+        reader.expect_label("world")?;
+        let mut world = World::new(0);
+        world.current_tick = u64::load(reader)?;
+        world.rng = SimRng::load(reader)?;
+        world.ent_ids = EntIDAllocator::load(reader)?;
+        world.players = BTreeMap::<Owner, PlayerState>::load(reader)?;
+        world.ai_players = BTreeMap::<Owner, AiPlayer>::load(reader)?;
+        world.projectiles = Vec::<Projectile>::load(reader)?;
+        world.game_objects = Vec::<GameObject>::load(reader)?;
+        reader.expect_label("world_info")?;
+        let mut world_info = WorldInfo::rebuild(&world.game_objects);
+        world_info.load_hp(reader)?;
+        Ok((world, world_info))
     }
 }
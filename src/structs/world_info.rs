@@ -1,37 +1,134 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet};
+use std::hash::{Hash, Hasher};
 
-use sdl2::{
-    rect::{Point, Rect},
-    render::Canvas,
-    video::Window,
-};
+use sdl2::rect::Rect;
 use vector2d::Vector2D;
 
-use crate::consts::values::{
-    BLACK_RGB, GREEN_RGB, HEALTH_BAR_HEIGHT, HEALTH_BAR_WIDTH, HEALTH_BAR_Y_FLOAT, RED_RGB,
-};
+use crate::consts::values::{MAP_HEIGHT, MAP_WIDTH, MIN_ARMORED_DAMAGE_RATIO, TIME_STEP};
+use crate::enums::{game_object::GameObject, structure_type::StructureType};
 
-use super::ent::{Ent, EntID, EntParentType, Owner};
+use super::{
+    damage::{DamageEvent, DamageType, Defense},
+    ent::{Ent, EntID, EntParentType, Owner},
+    flow_field::FlowFieldCache,
+    nav_grid::NavGrid,
+    ore_patch::OreType,
+    projectile::Projectile,
+    save_file::{Persist, SaveReader, SaveWriter},
+    spatial_hash::SpatialHash,
+    state_hash::StateHasher,
+    structure::StructureParentType,
+};
 
 pub struct WorldInfo {
-    ent_max_hp: HashMap<EntID, u32>, // Stores entity max hp,
-    ent_hp: HashMap<EntID, f32>,     // Stores entity hp
-    pub ent_rect_center: HashMap<EntID, Vector2D<f32>>, // Stores entity rect center
-    ent_team: HashMap<EntID, Owner>, // Stores entity team
-    ent_parent_type: HashMap<EntID, EntParentType>, // Stores entity parent type
-    pub ent_rect: HashMap<EntID, Rect>, // Stores entity rect
+    ent_max_hp: BTreeMap<EntID, u32>,            // Stores entity max hp,
+    ent_hp: BTreeMap<EntID, f32>,                // Stores entity hp
+    ent_defense: BTreeMap<EntID, Defense>, // Stores entity armor, resistances and shield stats
+    ent_shield: BTreeMap<EntID, f32>,      // Stores entity shield
+    ent_shield_regen_wait: BTreeMap<EntID, u32>, // Ticks left before a damaged shield starts coming back
+    pub ent_rect_center: BTreeMap<EntID, Vector2D<f32>>, // Stores entity rect center
+    ent_team: BTreeMap<EntID, Owner>,            // Stores entity team
+    ent_parent_type: BTreeMap<EntID, EntParentType>, // Stores entity parent type
+    ent_structure_type: BTreeMap<EntID, StructureParentType>, // Stores structure type, for structure ents
+    ent_ore_type: BTreeMap<EntID, OreType>,                   // Stores ore type, for ore ents
+    pub ent_rect: BTreeMap<EntID, Rect>,                      // Stores entity rect
+    pub nav_grid: NavGrid,                                    // Static obstacles, for pathfinding
+    pub flow_fields: FlowFieldCache, // Flow fields towards every destination currently moved to
+    spatial_hash: SpatialHash,       // Ent rects bucketed by area, for collision and range queries
+    deposits: Vec<(Owner, OreType, f32)>, // Ore beamed into structures this tick; Gets banked by the world after every tick
+    construction_work: BTreeSet<EntID>, // Construction sites a builder worked on this tick; Gets applied by the world after every tick
+    ore_patch_damage: Vec<(Vector2D<f32>, EntID, f32)>, // Damage dealt to ore patches this tick (where, by whom, how much); Gets passed on to spawners by the world after every tick
+    projectile_launches: Vec<Projectile>, // Projectiles fired this tick; Get put in flight by the world after every tick
+    damage_events: Vec<DamageEvent>, // Hits landed this tick; Get handed over to the world after every tick
+}
+
+impl Default for WorldInfo {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl WorldInfo {
     pub fn new() -> Self {
         Self {
-            ent_max_hp: HashMap::new(),
-            ent_hp: HashMap::new(),
-            ent_rect_center: HashMap::new(),
-            ent_rect: HashMap::new(),
-            ent_parent_type: HashMap::new(),
-            ent_team: HashMap::new(),
+            ent_max_hp: BTreeMap::new(),
+            ent_hp: BTreeMap::new(),
+            ent_defense: BTreeMap::new(),
+            ent_shield: BTreeMap::new(),
+            ent_shield_regen_wait: BTreeMap::new(),
+            ent_rect_center: BTreeMap::new(),
+            ent_rect: BTreeMap::new(),
+            ent_parent_type: BTreeMap::new(),
+            ent_team: BTreeMap::new(),
+            ent_structure_type: BTreeMap::new(),
+            ent_ore_type: BTreeMap::new(),
+            nav_grid: NavGrid::new(),
+            flow_fields: FlowFieldCache::new(),
+            spatial_hash: SpatialHash::new(),
+            deposits: Vec::<(Owner, OreType, f32)>::new(),
+            construction_work: BTreeSet::<EntID>::new(),
+            ore_patch_damage: Vec::<(Vector2D<f32>, EntID, f32)>::new(),
+            projectile_launches: Vec::<Projectile>::new(),
+            damage_events: Vec::<DamageEvent>::new(),
+        }
+    }
+
+    // Builds a world_info from scratch out of a list of game objects
+    pub fn rebuild(game_objects: &[GameObject]) -> Self {
+        let mut world_info = Self::new();
+        for game_object in game_objects {
+            match game_object {
+                GameObject::Structure(ent, structure_type) => match structure_type {
+                    StructureType::Mainframe(structure) | StructureType::Spawner(structure)
+                        if structure.is_under_construction() =>
+                    {
+                        world_info.add_ent(ent)
+                    }
+                    StructureType::Mainframe(structure) | StructureType::Spawner(structure) => {
+                        world_info.add_structure(ent, structure.parent_type())
+                    }
+                },
+                GameObject::Ore(ent, ore) => world_info.add_ore(ent, ore.ore_type()),
+                GameObject::Unit(ent, _) | GameObject::OrePatch(ent, _) => world_info.add_ent(ent),
+            }
         }
+        world_info
+    }
+
+    // Saves current ent hp and shields, since damage dealt this tick has not reached the ents yet
+    pub fn save_hp(&self, writer: &mut SaveWriter) {
+        self.ent_hp.len().save(writer);
+        for (ent_id, hp) in &self.ent_hp {
+            ent_id.save(writer);
+            hp.save(writer);
+        }
+        self.ent_shield.save(writer);
+        self.ent_shield_regen_wait.save(writer);
+    }
+
+    pub fn load_hp(&mut self, reader: &mut SaveReader) -> Result<(), String> {
+        let len = usize::load(reader)?;
+        for _ in 0..len {
+            let ent_id = EntID::load(reader)?;
+            let hp = f32::load(reader)?;
+            if !self.ent_hp.contains_key(&ent_id) {
+                return Err(format!("save file has hp for unknown ent {}", ent_id.0));
+            }
+            self.ent_hp.insert(ent_id, hp);
+        }
+        let ent_shield = BTreeMap::<EntID, f32>::load(reader)?;
+        let ent_shield_regen_wait = BTreeMap::<EntID, u32>::load(reader)?;
+        for ent_id in ent_shield.keys().chain(ent_shield_regen_wait.keys()) {
+            if !self.ent_defense.contains_key(ent_id) {
+                return Err(format!(
+                    "save file has a shield for unknown ent {}",
+                    ent_id.0
+                ));
+            }
+        }
+        self.ent_shield.extend(ent_shield);
+        self.ent_shield_regen_wait = ent_shield_regen_wait;
+        Ok(())
     }
 
     pub fn _get_ent_poisition(&self, ent: &Ent) -> Option<Vector2D<f32>> {
@@ -42,6 +139,10 @@ impl WorldInfo {
         return self.ent_hp.get(&ent.id).copied();
     }
 
+    pub fn get_ent_hp_by_id(&self, ent_id: EntID) -> Option<f32> {
+        self.ent_hp.get(&ent_id).copied()
+    }
+
     pub fn get_ent_rect_center_poisition_by_id(&self, ent_id: EntID) -> Option<Vector2D<f32>> {
         return self.ent_rect_center.get(&ent_id).copied();
     }
@@ -53,6 +154,7 @@ impl WorldInfo {
     pub fn update_ent(&mut self, ent: &Ent) {
         self.clear_ent_by_id(ent.id);
         let ent_rect = ent.get_rect();
+        self.spatial_hash.update(ent.id, ent_rect);
         let ent_rect_center = ent_rect.center();
         self.ent_hp.insert(ent.id, ent.hp);
         self.ent_rect_center.insert(
@@ -62,21 +164,90 @@ impl WorldInfo {
         self.ent_rect.insert(ent.id, ent_rect);
     }
 
-    pub fn damage_ent(&mut self, ent_id: EntID, dmg: f32) -> Option<f32> {
-        self.ent_hp.get_mut(&ent_id)?;
+    pub fn get_ent_shield(&self, ent: &Ent) -> Option<f32> {
+        self.ent_shield.get(&ent.id).copied()
+    }
+
+    // Runs a hit through the target's defense, then takes whatever is left off of its hp
+    // Resistances go first, then the shield soaks up what it can, then armor reduces the rest
+    // Returns the target's hp afterwards
+    pub fn damage_ent(
+        &mut self,
+        ent_id: EntID,
+        dmg: f32,
+        damage_type: DamageType,
+        attacker_id: EntID,
+    ) -> Option<f32> {
+        let hp = *self.ent_hp.get(&ent_id)?;
+        let defense = *self.ent_defense.get(&ent_id)?;
+
+        let mut dmg = dmg * (1.0 - defense.resistances.against(damage_type));
+        if dmg <= 0.0 {
+            return Some(hp);
+        }
+
+        // Shield first
+        let mut dealt = 0.0;
+        if let Some(shield) = self.ent_shield.get_mut(&ent_id) {
+            let absorbed = dmg.min(*shield);
+            *shield -= absorbed;
+            dmg -= absorbed;
+            dealt += absorbed;
+        }
+        if defense.max_shield > 0.0 {
+            self.ent_shield_regen_wait
+                .insert(ent_id, defense.shield_regen_delay_ticks);
+        }
+
+        // Then armor, for anything that got through
+        if dmg > 0.0 {
+            dmg = (dmg - defense.armor).max(dmg * MIN_ARMORED_DAMAGE_RATIO);
+        }
+        let new_hp = (hp - dmg).max(0.0);
+        dealt += hp - new_hp;
+
+        // Spawners keep an eye on ore patches
+        if self.ent_parent_type.get(&ent_id) == Some(&EntParentType::OrePatch) {
+            if let Some(ent_rect_center) = self.ent_rect_center.get(&ent_id) {
+                self.ore_patch_damage
+                    .push((*ent_rect_center, attacker_id, dealt));
+            }
+        }
+
+        if new_hp <= 0.0 {
+            self.remove_ent_by_id(ent_id);
+        } else {
+            self.ent_hp.insert(ent_id, new_hp);
+        }
+        self.damage_events.push(DamageEvent {
+            source: attacker_id,
+            target: ent_id,
+            amount: dealt,
+            damage_type,
+            killed: new_hp <= 0.0,
+        });
+        Some(new_hp)
+    }
 
-        if let Some(hp) = self.ent_hp.get_mut(&ent_id) {
-            let mut new_hp = *hp - dmg;
-            if new_hp < 0.0 {
-                new_hp = 0.0;
-                self.remove_ent_by_id(ent_id);
-            } else {
-                self.ent_hp.remove(&ent_id);
-                self.ent_hp.insert(ent_id, new_hp);
+    // Brings shields back, for ents that have not been hit in a while
+    pub fn regenerate_shields(&mut self) {
+        for (ent_id, shield) in &mut self.ent_shield {
+            let Some(defense) = self.ent_defense.get(ent_id) else {
+                continue;
+            };
+            if let Some(wait) = self.ent_shield_regen_wait.get_mut(ent_id) {
+                if *wait > 0 {
+                    *wait -= 1;
+                    continue;
+                }
+                self.ent_shield_regen_wait.remove(ent_id);
             }
-            return Some(new_hp);
+            *shield = (*shield + defense.shield_regen * TIME_STEP).min(defense.max_shield);
         }
-        None
+    }
+
+    pub fn take_damage_events(&mut self) -> Vec<DamageEvent> {
+        std::mem::take(&mut self.damage_events)
     }
 
     pub fn add_ent(&mut self, ent: &Ent) {
@@ -84,13 +255,35 @@ impl WorldInfo {
         let ent_rect_center = ent_rect.center();
         self.ent_max_hp.insert(ent.id, ent.max_hp);
         self.ent_hp.insert(ent.id, ent.hp);
+        self.ent_defense.insert(ent.id, ent.defense);
+        self.ent_shield.insert(ent.id, ent.shield);
         self.ent_rect_center.insert(
             ent.id,
             Vector2D::new(ent_rect_center.x as f32, ent_rect_center.y as f32),
         );
         self.ent_rect.insert(ent.id, ent_rect);
+        self.spatial_hash.insert(ent.id, ent_rect);
         self.ent_team.insert(ent.id, ent.owner);
         self.ent_parent_type.insert(ent.id, ent.parent_type());
+        if ent.parent_type().is_static_obstacle() {
+            self.nav_grid.block_rect(ent_rect);
+            self.flow_fields.clear();
+        }
+    }
+
+    pub fn add_structure(&mut self, ent: &Ent, structure_type: StructureParentType) {
+        self.add_ent(ent);
+        self.ent_structure_type.insert(ent.id, structure_type);
+    }
+
+    // A construction site just got done; From now on it counts as a structure of its type
+    pub fn complete_structure(&mut self, ent_id: EntID, structure_type: StructureParentType) {
+        self.ent_structure_type.insert(ent_id, structure_type);
+    }
+
+    pub fn add_ore(&mut self, ent: &Ent, ore_type: OreType) {
+        self.add_ent(ent);
+        self.ent_ore_type.insert(ent.id, ore_type);
     }
 
     pub fn clear_ent_by_id(&mut self, ent_id: EntID) {
@@ -101,9 +294,148 @@ impl WorldInfo {
 
     pub fn remove_ent_by_id(&mut self, ent_id: EntID) {
         self.clear_ent_by_id(ent_id);
+        self.spatial_hash.remove(ent_id);
         self.ent_max_hp.remove(&ent_id);
+        self.ent_defense.remove(&ent_id);
+        self.ent_shield.remove(&ent_id);
+        self.ent_shield_regen_wait.remove(&ent_id);
         self.ent_team.remove(&ent_id);
-        self.ent_parent_type.remove(&ent_id);
+        self.ent_structure_type.remove(&ent_id);
+        self.ent_ore_type.remove(&ent_id);
+        if let Some(parent_type) = self.ent_parent_type.remove(&ent_id) {
+            if parent_type.is_static_obstacle() {
+                self.rebuild_nav_grid();
+            }
+        }
+    }
+
+    // Re-blocks the nav grid from scratch, out of the remaining static obstacles
+    pub fn rebuild_nav_grid(&mut self) {
+        self.nav_grid.clear();
+        self.flow_fields.clear();
+        for (ent_id, ent_rect) in &self.ent_rect {
+            if let Some(parent_type) = self.ent_parent_type.get(ent_id) {
+                if parent_type.is_static_obstacle() {
+                    self.nav_grid.block_rect(*ent_rect);
+                }
+            }
+        }
+    }
+
+    // Point to steer towards on the way to a fixed destination, sampled from the destination's flow field
+    pub fn flow_field_steering_target(
+        &mut self,
+        position: Vector2D<f32>,
+        destination: Vector2D<f32>,
+    ) -> Vector2D<f32> {
+        self.flow_fields
+            .steering_target(&self.nav_grid, position, destination)
+    }
+
+    // Drops the flow fields that none of the given destinations lead to
+    pub fn evict_unused_flow_fields(&mut self, destinations: &[Vector2D<f32>]) {
+        let goals: BTreeSet<(i32, i32)> = destinations
+            .iter()
+            .filter_map(|destination| FlowFieldCache::goal_cell(&self.nav_grid, *destination))
+            .collect();
+        self.flow_fields.retain_goals(&goals);
+    }
+
+    // Every ent whose rect intersects a given rect, along with that rect, ordered by EntID
+    pub fn get_ents_in_rect(&self, rect: Rect) -> Vec<(EntID, Rect)> {
+        self.spatial_hash
+            .query(rect)
+            .into_iter()
+            .filter_map(|ent_id| {
+                let ent_rect = self.ent_rect.get(&ent_id)?;
+                ent_rect
+                    .has_intersection(rect)
+                    .then_some((ent_id, *ent_rect))
+            })
+            .collect()
+    }
+
+    // Every ent whose rect center is within a radius of a position, along with its distance, ordered by EntID
+    pub fn get_ents_in_radius(&self, position: Vector2D<f32>, radius: f32) -> Vec<(EntID, f32)> {
+        let query_rect = Rect::new(
+            (position.x - radius).floor() as i32,
+            (position.y - radius).floor() as i32,
+            (radius * 2.0).ceil() as u32 + 1,
+            (radius * 2.0).ceil() as u32 + 1,
+        );
+        self.spatial_hash
+            .query(query_rect)
+            .into_iter()
+            .filter_map(|ent_id| {
+                let distance = (*self.ent_rect_center.get(&ent_id)? - position).length();
+                (distance <= radius).then_some((ent_id, distance))
+            })
+            .collect()
+    }
+
+    // Closest structure of a given type owned by someone, from a position
+    // Ties go to the oldest structure (lowest EntID)
+    pub fn get_closest_structure(
+        &self,
+        position: Vector2D<f32>,
+        owner: Owner,
+        structure_type: StructureParentType,
+    ) -> Option<EntID> {
+        let mut closest_structure: Option<(EntID, f32)> = None;
+        for (ent_id, ent_structure_type) in &self.ent_structure_type {
+            if *ent_structure_type != structure_type
+                || self.get_ent_owner_by_id(*ent_id) != Some(owner)
+            {
+                continue;
+            }
+            let Some(ent_rect_center) = self.ent_rect_center.get(ent_id) else {
+                continue;
+            };
+            let distance = (*ent_rect_center - position).length();
+            if closest_structure.is_none_or(|(_, closest_distance)| distance < closest_distance) {
+                closest_structure = Some((*ent_id, distance));
+            }
+        }
+        closest_structure.map(|(ent_id, _)| ent_id)
+    }
+
+    // Beams some ore into the owner's bank; It gets credited once the current tick is over
+    pub fn deposit_ore(&mut self, owner: Owner, ore_type: OreType, amount: f32) {
+        self.deposits.push((owner, ore_type, amount));
+    }
+
+    pub fn take_deposits(&mut self) -> Vec<(Owner, OreType, f32)> {
+        std::mem::take(&mut self.deposits)
+    }
+
+    // Puts a tick of work into a construction site; It gets applied once the current tick is over
+    pub fn work_on_construction(&mut self, ent_id: EntID) {
+        self.construction_work.insert(ent_id);
+    }
+
+    pub fn take_construction_work(&mut self) -> BTreeSet<EntID> {
+        std::mem::take(&mut self.construction_work)
+    }
+
+    pub fn take_ore_patch_damage(&mut self) -> Vec<(Vector2D<f32>, EntID, f32)> {
+        std::mem::take(&mut self.ore_patch_damage)
+    }
+
+    pub fn launch_projectile(&mut self, projectile: Projectile) {
+        self.projectile_launches.push(projectile);
+    }
+
+    pub fn take_projectile_launches(&mut self) -> Vec<Projectile> {
+        std::mem::take(&mut self.projectile_launches)
+    }
+
+    // Whether a structure could be placed over a rect: fully on the map, and clear of every other ent
+    pub fn can_place(&self, rect: Rect) -> bool {
+        rect.left() >= 0
+            && rect.top() >= 0
+            && rect.right() <= MAP_WIDTH as i32
+            && rect.bottom() <= MAP_HEIGHT as i32
+            && self.get_ents_in_rect(rect).is_empty()
     }
 
     pub fn has_ent(&self, ent: &Ent) -> bool {
@@ -121,6 +453,15 @@ impl WorldInfo {
         self.ent_team.get(&ent_id).copied()
     }
 
+    // None for construction sites, and for anything that is not a structure
+    pub fn get_ent_structure_type_by_id(&self, ent_id: EntID) -> Option<StructureParentType> {
+        self.ent_structure_type.get(&ent_id).copied()
+    }
+
+    pub fn get_ent_ore_type_by_id(&self, ent_id: EntID) -> Option<OreType> {
+        self.ent_ore_type.get(&ent_id).copied()
+    }
+
     pub fn get_ent_parent_type_by_id(&self, ent_id: EntID) -> Option<EntParentType> {
         self.ent_parent_type.get(&ent_id).copied()
     }
@@ -129,42 +470,25 @@ impl WorldInfo {
         self.ent_rect_center.get(&ent_id).copied()
     }
 
-    pub fn draw_health_bars(&self, canvas: &mut Canvas<Window>) {
-        for ent_id in self.ent_hp.keys() {
-            if let Some(health) = self.ent_hp.get(ent_id) {
-                if let Some(max_health) = self.ent_max_hp.get(ent_id) {
-                    if let Some(pos) = self.ent_rect_center.get(ent_id) {
-                        if let Some(parent_type) = self.ent_parent_type.get(ent_id) {
-                            // No health bars for ore
-                            if *parent_type == EntParentType::Ore {
-                                continue;
-                            }
-                        }
-                        let empty_health_bar_rec = Rect::from_center(
-                            Point::new(pos.x as i32, (pos.y - HEALTH_BAR_Y_FLOAT) as i32),
-                            HEALTH_BAR_WIDTH as u32,
-                            HEALTH_BAR_HEIGHT as u32,
-                        );
-                        let full_health_bar_rec = Rect::from_center(
-                            Point::new(
-                                (pos.x
-                                    - ((1.0 - (health / (*max_health as f32))) * HEALTH_BAR_WIDTH
-                                        / 2.0)) as i32,
-                                (pos.y - HEALTH_BAR_Y_FLOAT) as i32,
-                            ),
-                            ((health / (*max_health as f32)) * HEALTH_BAR_WIDTH) as u32,
-                            HEALTH_BAR_HEIGHT as u32,
-                        );
-
-                        canvas.set_draw_color(RED_RGB);
-                        canvas.fill_rect(empty_health_bar_rec).ok();
-                        canvas.set_draw_color(GREEN_RGB);
-                        canvas.fill_rect(full_health_bar_rec).ok();
-                        canvas.set_draw_color(BLACK_RGB);
-                        canvas.draw_rect(empty_health_bar_rec).ok();
-                    }
-                }
-            }
+    // Ent hp lives here first (damage gets dealt through world_info), so it is part of the state
+    // Maps are ordered by EntID, so iteration order is always the same
+    pub fn hash_state(&self, hasher: &mut StateHasher) {
+        hasher.write_usize(self.ent_hp.len());
+        for (ent_id, hp) in &self.ent_hp {
+            ent_id.hash(hasher);
+            hasher.write_f32(*hp);
+        }
+        for (ent_id, shield) in &self.ent_shield {
+            ent_id.hash(hasher);
+            hasher.write_f32(*shield);
+        }
+        for (ent_id, wait) in &self.ent_shield_regen_wait {
+            ent_id.hash(hasher);
+            hasher.write_u32(*wait);
+        }
+        for (ent_id, ent_rect) in &self.ent_rect {
+            ent_id.hash(hasher);
+            hasher.write_rect(*ent_rect);
         }
     }
 }
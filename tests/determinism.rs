@@ -0,0 +1,76 @@
+use micron::{
+    consts::{helper::empty_ent_target, setup::Scenario},
+    enums::{command::Command, game_object::GameObject},
+    structs::{
+        ent::{EntID, Owner},
+        order::{Order, OrderType},
+        world::World,
+        world_info::WorldInfo,
+    },
+};
+use vector2d::Vector2D;
+
+const SEED: u64 = 0xD1CE;
+const DEBUG_ENT_COUNT: i32 = 200;
+const TICKS: u64 = 600;
+
+fn player_units(world: &World) -> Vec<EntID> {
+    world
+        .game_objects
+        .iter()
+        .filter_map(|game_object| match game_object {
+            GameObject::Unit(ent, _) if ent.owner == Owner::Player => Some(ent.id),
+            _ => None,
+        })
+        .collect()
+}
+
+// Builds a world from a seed, feeds it a fixed stream of commands, and returns its state hash every 60 ticks
+fn run(seed: u64) -> Vec<u64> {
+    let (mut world, mut world_info): (World, WorldInfo) =
+        Scenario::new(seed, DEBUG_ENT_COUNT, None).setup();
+    assert!(!player_units(&world).is_empty());
+    let mut hashes: Vec<u64> = Vec::<u64>::new();
+    while world.current_tick < TICKS {
+        if world.current_tick.is_multiple_of(120) {
+            let units = player_units(&world);
+            let step = world.current_tick as f32;
+            world.apply_command(
+                &Command::IssueOrder {
+                    issuer: Owner::Player,
+                    units: units.iter().copied().step_by(2).collect(),
+                    order: Order::new(
+                        OrderType::Move,
+                        Vector2D::<f32>::new(1000.0 + step, 800.0 + step / 2.0),
+                        empty_ent_target(),
+                    ),
+                    queued: false,
+                },
+                &mut world_info,
+            );
+            world.apply_command(
+                &Command::HoldPosition {
+                    issuer: Owner::Player,
+                    units: units.iter().copied().skip(1).step_by(3).collect(),
+                    queued: true,
+                },
+                &mut world_info,
+            );
+        }
+        world.tick(&mut world_info);
+        if world.current_tick.is_multiple_of(60) {
+            hashes.push(world.state_hash(&world_info));
+        }
+    }
+    hashes
+}
+
+#[test]
+fn same_seed_and_commands_give_the_same_state_hash() {
+    assert_eq!(run(SEED), run(SEED));
+}
+
+#[test]
+fn different_seeds_give_different_state_hashes() {
+    assert_ne!(run(SEED).last(), run(SEED + 1).last());
+}
@@ -0,0 +1,110 @@
+use micron::{
+    consts::{helper::unit_cost, setup::Scenario},
+    enums::{command::Command, game_object::GameObject},
+    headless::{self, HeadlessReport},
+    structs::{
+        ai_player::AiDifficulty,
+        ent::{EntID, Owner},
+        ore_patch::OreType,
+        player_state::PlayerState,
+        unit::UnitParentType,
+    },
+};
+
+const SEED: u64 = 0xEC0;
+const TICKS: u32 = 3600;
+const STARTING_ORE: f32 = 500.0;
+
+// Totals (gathered, spent, refunded, resources) of one owner's Blue ore
+type Ledger = (f32, f32, f32, f32);
+
+fn ledger(player_state: &PlayerState) -> Ledger {
+    (
+        player_state.gathered(OreType::Blue),
+        player_state.spent(OreType::Blue),
+        player_state.refunded(OreType::Blue),
+        player_state.resources(OreType::Blue),
+    )
+}
+
+// The player banks some ore, queues up two scouts and cancels one of them,
+// while the computer player runs its own economy next door
+fn run() -> (HeadlessReport, Ledger, Ledger) {
+    let (mut world, mut world_info) = Scenario::new(SEED, 0, Some(AiDifficulty::Normal)).setup();
+    world
+        .player_state_mut(Owner::Player)
+        .expect("player state")
+        .deposit(OreType::Blue, STARTING_ORE);
+    let mainframe: EntID = world
+        .game_objects
+        .iter()
+        .find_map(|game_object| match game_object {
+            GameObject::Structure(ent, _) if ent.owner == Owner::Player => Some(ent.id),
+            _ => None,
+        })
+        .expect("player mainframe");
+    for _ in 0..2 {
+        world.apply_command(
+            &Command::EnqueueProduction {
+                issuer: Owner::Player,
+                structure: mainframe,
+                unit_type: UnitParentType::Scout,
+            },
+            &mut world_info,
+        );
+    }
+    world.apply_command(
+        &Command::CancelProduction {
+            issuer: Owner::Player,
+            structure: mainframe,
+            index: 1,
+        },
+        &mut world_info,
+    );
+
+    let report = headless::run_world(&mut world, &mut world_info, TICKS);
+    (
+        report,
+        ledger(world.player_state(Owner::Player).expect("player state")),
+        ledger(world.player_state(Owner::Cpu).expect("cpu state")),
+    )
+}
+
+#[test]
+fn player_ledger_tracks_production_spending_and_refunds() {
+    let scout_cost: f32 = unit_cost(UnitParentType::Scout)
+        .iter()
+        .filter(|(ore_type, _)| *ore_type == OreType::Blue)
+        .map(|(_, amount)| amount)
+        .sum();
+    let (report, player, _) = run();
+    let (gathered, spent, refunded, resources) = player;
+    assert_eq!(gathered, STARTING_ORE);
+    assert_eq!(spent, 2.0 * scout_cost);
+    assert_eq!(refunded, scout_cost);
+    assert_eq!(resources, STARTING_ORE - scout_cost);
+    assert_eq!(report.player_gathered, gathered);
+    assert_eq!(report.player_spent, spent);
+    assert_eq!(report.player_refunded, refunded);
+    assert_eq!(report.player_resources, resources);
+}
+
+#[test]
+fn computer_ledger_balances() {
+    let (_, _, cpu) = run();
+    let (gathered, spent, refunded, resources) = cpu;
+    assert!(
+        gathered > 0.0,
+        "the computer player never gathered anything"
+    );
+    assert!((resources - (gathered + refunded - spent)).abs() < 0.01);
+}
+
+#[test]
+fn same_seed_gives_the_same_ledgers() {
+    let (first_report, first_player, first_cpu) = run();
+    let (second_report, second_player, second_cpu) = run();
+    assert_eq!(first_player, second_player);
+    assert_eq!(first_cpu, second_cpu);
+    assert_eq!(first_report.state_hash, second_report.state_hash);
+}
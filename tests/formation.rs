@@ -0,0 +1,82 @@
+use micron::{
+    consts::helper::{new_structure, new_unit},
+    enums::{command::Command, game_object::GameObject},
+    structs::{
+        ent::{EntID, Owner},
+        structure::StructureParentType,
+        unit::UnitParentType,
+        world::World,
+        world_info::WorldInfo,
+    },
+};
+use vector2d::Vector2D;
+
+const GROUP_SIZE: u64 = 6;
+
+// A group of scouts on one side of a mainframe gets sent in formation to the other side,
+// so nobody can see their own spot from where they start
+fn setup() -> (World, WorldInfo, Vec<EntID>) {
+    let mut world = World::new(1);
+    let mut world_info = WorldInfo::new();
+    world.game_objects.push(new_structure(
+        &mut world_info,
+        EntID(1),
+        StructureParentType::Mainframe,
+        Owner::Player,
+        Vector2D::<f32>::new(450.0, 400.0),
+    ));
+    let mut units = Vec::<EntID>::new();
+    for index in 0..GROUP_SIZE {
+        let ent_id = EntID(2 + index);
+        world.game_objects.push(new_unit(
+            &mut world_info,
+            ent_id,
+            UnitParentType::Scout,
+            Owner::Player,
+            Vector2D::<f32>::new(250.0, 400.0 + index as f32 * 20.0),
+        ));
+        units.push(ent_id);
+    }
+    (world, world_info, units)
+}
+
+fn move_in_formation(world: &mut World, world_info: &mut WorldInfo, units: Vec<EntID>) {
+    world.apply_command(
+        &Command::FormationMove {
+            issuer: Owner::Player,
+            units,
+            destination: Vector2D::<f32>::new(750.0, 450.0),
+            facing: None,
+            queued: false,
+        },
+        world_info,
+    );
+}
+
+#[test]
+fn formation_members_share_one_flow_field() {
+    let (mut world, mut world_info, units) = setup();
+    move_in_formation(&mut world, &mut world_info, units);
+
+    for _ in 0..60 {
+        world.tick(&mut world_info);
+        assert!(world_info.flow_fields.len() <= 1);
+    }
+    assert_eq!(world_info.flow_fields.len(), 1);
+}
+
+#[test]
+fn formation_members_still_reach_their_own_spots() {
+    let (mut world, mut world_info, units) = setup();
+    move_in_formation(&mut world, &mut world_info, units);
+
+    for _ in 0..1200 {
+        world.tick(&mut world_info);
+    }
+    for game_object in &world.game_objects {
+        if let GameObject::Unit(ent, _) = game_object {
+            assert!(ent.orders.is_empty());
+            assert!(ent.position.x > 600.0);
+        }
+    }
+}
@@ -0,0 +1,158 @@
+use micron::{
+    consts::{
+        helper::{empty_ent_target, new_unit},
+        values::TIME_STEP,
+    },
+    enums::command::Command,
+    structs::{
+        damage::DamageType,
+        ent::{EntID, Owner},
+        order::{Order, OrderType},
+        projectile::{Projectile, ProjectileKind, ProjectileMotion},
+        unit::UnitParentType,
+        weapon::{Shot, WeaponTargets},
+        world::World,
+        world_info::WorldInfo,
+    },
+};
+use vector2d::Vector2D;
+
+const SHOOTER: EntID = EntID(99);
+const TARGET: EntID = EntID(1);
+const DAMAGE: f32 = 10.0;
+const SPEED: f32 = 300.0;
+const ORIGIN: Vector2D<f32> = Vector2D { x: 200.0, y: 512.0 };
+
+// A collector (no armor, no shield) standing around, with a projectile already on its way over
+fn setup(motion: ProjectileMotion) -> (World, WorldInfo, f32) {
+    let mut world = World::new(1);
+    let mut world_info = WorldInfo::new();
+    world.game_objects.push(new_unit(
+        &mut world_info,
+        TARGET,
+        UnitParentType::Collector,
+        Owner::Cpu,
+        Vector2D::<f32>::new(500.0, 500.0),
+    ));
+    let target_position = world_info
+        .get_ent_rect_center_poisition_by_id(TARGET)
+        .expect("target position");
+    let max_hp = world_info.get_ent_hp_by_id(TARGET).expect("target hp");
+    world.projectiles.push(Projectile::new(
+        Shot {
+            shooter_id: SHOOTER,
+            owner: Owner::Player,
+            damage: DAMAGE,
+            damage_type: DamageType::Kinetic,
+            splash_radius: None,
+            targets: WeaponTargets {
+                units: true,
+                structures: true,
+                ore: false,
+            },
+        },
+        ProjectileKind {
+            speed: SPEED,
+            motion,
+        },
+        TARGET,
+        ORIGIN,
+        target_position,
+    ));
+    (world, world_info, max_hp)
+}
+
+// How many ticks it takes to fly over to where the target stood
+fn flight_ticks(world_info: &WorldInfo) -> u32 {
+    let target_position = world_info
+        .get_ent_rect_center_poisition_by_id(TARGET)
+        .expect("target position");
+    ((target_position - ORIGIN).length() / (SPEED * TIME_STEP)).ceil() as u32
+}
+
+fn walk_off(world: &mut World, world_info: &mut WorldInfo) {
+    world.apply_command(
+        &Command::IssueOrder {
+            issuer: Owner::Cpu,
+            units: vec![TARGET],
+            order: Order::new(
+                OrderType::Move,
+                Vector2D::<f32>::new(500.0, 900.0),
+                empty_ent_target(),
+            ),
+            queued: false,
+        },
+        world_info,
+    );
+}
+
+#[test]
+fn ballistic_shell_travels_over_time_and_hits_a_target_that_stayed_put() {
+    let (mut world, mut world_info, max_hp) = setup(ProjectileMotion::Ballistic);
+    let flight_ticks = flight_ticks(&world_info);
+
+    for tick in 1..flight_ticks {
+        world.tick(&mut world_info);
+        let projectile = world.projectiles.first().expect("still in the air");
+        assert!(
+            ((projectile.position - ORIGIN).length() - tick as f32 * SPEED * TIME_STEP).abs()
+                < 0.01
+        );
+        assert_eq!(world_info.get_ent_hp_by_id(TARGET), Some(max_hp));
+    }
+    world.tick(&mut world_info);
+
+    assert!(world.projectiles.is_empty());
+    assert_eq!(world_info.get_ent_hp_by_id(TARGET), Some(max_hp - DAMAGE));
+    assert!(world
+        .damage_events
+        .iter()
+        .any(|damage_event| damage_event.source == SHOOTER && damage_event.target == TARGET));
+}
+
+#[test]
+fn ballistic_shell_misses_a_target_that_walked_off() {
+    let (mut world, mut world_info, max_hp) = setup(ProjectileMotion::Ballistic);
+    let flight_ticks = flight_ticks(&world_info);
+    walk_off(&mut world, &mut world_info);
+
+    for _ in 0..flight_ticks {
+        world.tick(&mut world_info);
+    }
+
+    assert!(world.projectiles.is_empty());
+    assert_eq!(world_info.get_ent_hp_by_id(TARGET), Some(max_hp));
+}
+
+#[test]
+fn homing_shot_follows_a_target_that_walked_off() {
+    let (mut world, mut world_info, max_hp) = setup(ProjectileMotion::Homing);
+    walk_off(&mut world, &mut world_info);
+
+    for _ in 0..2 * flight_ticks(&world_info) {
+        world.tick(&mut world_info);
+    }
+
+    assert!(world.projectiles.is_empty());
+    assert_eq!(world_info.get_ent_hp_by_id(TARGET), Some(max_hp - DAMAGE));
+}
+
+#[test]
+fn shots_miss_a_target_that_died_first() {
+    for motion in [ProjectileMotion::Ballistic, ProjectileMotion::Homing] {
+        let (mut world, mut world_info, max_hp) = setup(motion);
+        let flight_ticks = flight_ticks(&world_info);
+        world.tick(&mut world_info);
+        world_info.damage_ent(TARGET, 2.0 * max_hp, DamageType::Kinetic, EntID(98));
+
+        for _ in 1..flight_ticks {
+            world.tick(&mut world_info);
+        }
+
+        assert!(world.projectiles.is_empty());
+        assert!(!world
+            .damage_events
+            .iter()
+            .any(|damage_event| damage_event.source == SHOOTER));
+    }
+}
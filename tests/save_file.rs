@@ -0,0 +1,70 @@
+use std::collections::BTreeSet;
+
+use micron::{
+    consts::setup::Scenario,
+    enums::game_object::GameObject,
+    structs::{
+        ai_player::AiDifficulty,
+        camera::Camera,
+        ent::EntID,
+        save_file::{load_game, save_game},
+        world::World,
+        world_info::WorldInfo,
+    },
+};
+
+const SEED: u64 = 0x5A7E;
+const DEBUG_ENT_COUNT: i32 = 200;
+const TICKS_BEFORE_SAVE: u32 = 900;
+const TICKS_AFTER_LOAD: u32 = 900;
+
+fn ent_ids(world: &World) -> BTreeSet<EntID> {
+    world
+        .game_objects
+        .iter()
+        .map(|game_object| match game_object {
+            GameObject::Unit(ent, _)
+            | GameObject::Structure(ent, _)
+            | GameObject::OrePatch(ent, _)
+            | GameObject::Ore(ent, _) => ent.id,
+        })
+        .collect()
+}
+
+// Plays a while, saves, loads, and checks that the loaded world is the same game and keeps playing out the same
+#[test]
+fn saved_and_loaded_worlds_play_out_the_same() {
+    let (mut world, mut world_info): (World, WorldInfo) =
+        Scenario::new(SEED, DEBUG_ENT_COUNT, Some(AiDifficulty::Normal)).setup();
+    for _ in 0..TICKS_BEFORE_SAVE {
+        world.tick(&mut world_info);
+    }
+
+    let path = std::env::temp_dir().join(format!("micron-save-test-{}.micron", std::process::id()));
+    let path = path.to_str().expect("temp path");
+    save_game(path, &world, &world_info, &Camera::new()).expect("save");
+    let loaded = load_game(path);
+    std::fs::remove_file(path).expect("remove save file");
+    let (mut loaded_world, mut loaded_world_info, _) = loaded.expect("load");
+
+    assert_eq!(
+        world.state_hash(&world_info),
+        loaded_world.state_hash(&loaded_world_info)
+    );
+
+    // Fresh EntIDs must not collide with anything that came out of the save
+    let loaded_ent_ids = ent_ids(&loaded_world);
+    assert!(!loaded_ent_ids.is_empty());
+    let mut ent_id_allocator = loaded_world.ent_ids;
+    let next_id = ent_id_allocator.next_id();
+    assert!(!loaded_ent_ids.contains(&next_id));
+
+    for _ in 0..TICKS_AFTER_LOAD {
+        world.tick(&mut world_info);
+        loaded_world.tick(&mut loaded_world_info);
+    }
+    assert_eq!(
+        world.state_hash(&world_info),
+        loaded_world.state_hash(&loaded_world_info)
+    );
+}
@@ -0,0 +1,228 @@
+use micron::{
+    consts::{
+        helper::{empty_ent_target, new_unit},
+        values::{BASE_UNIT_RANGE, SCOUT_WEAPON_COOLDOWN_TICKS, SCOUT_WEAPON_WINDUP_TICKS},
+    },
+    enums::{command::Command, game_object::GameObject},
+    structs::{
+        damage::DamageType,
+        ent::{Ent, EntID, Owner},
+        order::{Order, OrderType},
+        unit::UnitParentType,
+        weapon::{Shot, WeaponTargets},
+        world::World,
+        world_info::WorldInfo,
+    },
+};
+use vector2d::Vector2D;
+
+const DAMAGE: f32 = 40.0;
+const SPLASH_RADIUS: f32 = 60.0;
+
+fn spawn_collector(world_info: &mut WorldInfo, id: u64, owner: Owner, x: f32) -> Ent {
+    // Collectors have neither armor nor shields, so they take exactly what gets dealt
+    match new_unit(
+        world_info,
+        EntID(id),
+        UnitParentType::Collector,
+        owner,
+        Vector2D::<f32>::new(x, 500.0),
+    ) {
+        GameObject::Unit(ent, _) => ent,
+        _ => unreachable!(),
+    }
+}
+
+fn damage_taken(world_info: &WorldInfo, ent: &Ent) -> f32 {
+    ent.max_hp as f32 - world_info.get_ent_hp(ent).unwrap_or(0.0)
+}
+
+#[test]
+fn splash_damage_falls_off_with_distance() {
+    let mut world_info = WorldInfo::new();
+    let target = spawn_collector(&mut world_info, 1, Owner::Cpu, 500.0);
+    let near = spawn_collector(&mut world_info, 2, Owner::Cpu, 520.0);
+    let far = spawn_collector(&mut world_info, 3, Owner::Cpu, 550.0);
+    let outside = spawn_collector(&mut world_info, 4, Owner::Cpu, 600.0);
+    let friendly = spawn_collector(&mut world_info, 5, Owner::Player, 480.0);
+
+    let shot = Shot {
+        shooter_id: EntID(99),
+        owner: Owner::Player,
+        damage: DAMAGE,
+        damage_type: DamageType::Kinetic,
+        splash_radius: Some(SPLASH_RADIUS),
+        targets: WeaponTargets {
+            units: true,
+            structures: true,
+            ore: false,
+        },
+    };
+    let target_center = target.get_rect().center();
+    shot.land(
+        Some(target.id),
+        Some(Vector2D::<f32>::new(
+            target_center.x as f32,
+            target_center.y as f32,
+        )),
+        &mut world_info,
+    );
+
+    assert_eq!(damage_taken(&world_info, &target), DAMAGE);
+    let near_damage = damage_taken(&world_info, &near);
+    let far_damage = damage_taken(&world_info, &far);
+    assert!(near_damage < DAMAGE);
+    assert!(far_damage > 0.0);
+    assert!(near_damage > far_damage);
+    assert_eq!(damage_taken(&world_info, &outside), 0.0);
+    assert_eq!(damage_taken(&world_info, &friendly), 0.0);
+}
+
+#[test]
+fn damage_down_to_exactly_zero_hp_kills() {
+    let mut world_info = WorldInfo::new();
+    let target = spawn_collector(&mut world_info, 1, Owner::Cpu, 500.0);
+    world_info.damage_ent(
+        target.id,
+        target.max_hp as f32,
+        DamageType::Kinetic,
+        EntID(99),
+    );
+    assert_eq!(world_info.get_ent_hp(&target), None);
+    let damage_events = world_info.take_damage_events();
+    assert_eq!(damage_events.len(), 1);
+    assert!(damage_events[0].killed);
+}
+
+const SCOUT: EntID = EntID(1);
+const TARGET: EntID = EntID(2);
+const SHOT_INTERVAL: u32 = SCOUT_WEAPON_WINDUP_TICKS + SCOUT_WEAPON_COOLDOWN_TICKS;
+
+// When a scout on hold fired and landed its shots, and when its target left and came back into its range (if it did)
+struct Duel {
+    launches: Vec<u32>,
+    landings: Vec<u32>,
+    target_left_range: Option<u32>,
+    target_came_back: Option<u32>,
+}
+
+// A scout holding position shoots at a collector standing well within its range,
+// which walks off and then back once told to (if ever)
+fn duel(walk_off_at: Option<u32>, ticks: u32) -> Duel {
+    let mut world = World::new(1);
+    let mut world_info = WorldInfo::new();
+    world.game_objects.push(new_unit(
+        &mut world_info,
+        SCOUT,
+        UnitParentType::Scout,
+        Owner::Player,
+        Vector2D::<f32>::new(500.0, 500.0),
+    ));
+    world.game_objects.push(new_unit(
+        &mut world_info,
+        TARGET,
+        UnitParentType::Collector,
+        Owner::Cpu,
+        Vector2D::<f32>::new(600.0, 500.0),
+    ));
+    world.apply_command(
+        &Command::HoldPosition {
+            issuer: Owner::Player,
+            units: vec![SCOUT],
+            queued: false,
+        },
+        &mut world_info,
+    );
+
+    let mut duel = Duel {
+        launches: Vec::<u32>::new(),
+        landings: Vec::<u32>::new(),
+        target_left_range: None,
+        target_came_back: None,
+    };
+    for tick in 0..ticks {
+        if walk_off_at == Some(tick) {
+            for (destination, queued) in [(800.0, false), (600.0, true)] {
+                world.apply_command(
+                    &Command::IssueOrder {
+                        issuer: Owner::Cpu,
+                        units: vec![TARGET],
+                        order: Order::new(
+                            OrderType::Move,
+                            Vector2D::<f32>::new(destination, 500.0),
+                            empty_ent_target(),
+                        ),
+                        queued,
+                    },
+                    &mut world_info,
+                );
+            }
+        }
+        world.tick(&mut world_info);
+
+        // Freshly launched projectiles have not moved yet
+        if world.projectiles.iter().any(|projectile| {
+            projectile.position.x == projectile.origin.x
+                && projectile.position.y == projectile.origin.y
+        }) {
+            duel.launches.push(tick);
+        }
+        if world
+            .damage_events
+            .iter()
+            .any(|damage_event| damage_event.source == SCOUT && damage_event.target == TARGET)
+        {
+            duel.landings.push(tick);
+        }
+        let distance = match (
+            world_info.get_ent_rect_center_poisition_by_id(SCOUT),
+            world_info.get_ent_rect_center_poisition_by_id(TARGET),
+        ) {
+            (Some(scout), Some(target)) => (target - scout).length(),
+            _ => 0.0,
+        };
+        if duel.target_left_range.is_none() && distance > BASE_UNIT_RANGE {
+            duel.target_left_range = Some(tick);
+        } else if duel.target_left_range.is_some()
+            && duel.target_came_back.is_none()
+            && distance <= BASE_UNIT_RANGE
+        {
+            duel.target_came_back = Some(tick);
+        }
+    }
+    duel
+}
+
+#[test]
+fn shots_land_every_windup_plus_cooldown_ticks() {
+    let duel = duel(None, 6 * SHOT_INTERVAL);
+    assert!(duel.landings.len() >= 5);
+    assert_eq!(duel.launches.len(), duel.landings.len());
+    for interval in duel.launches.windows(2) {
+        assert_eq!(interval[1] - interval[0], SHOT_INTERVAL);
+    }
+    for interval in duel.landings.windows(2) {
+        assert_eq!(interval[1] - interval[0], SHOT_INTERVAL);
+    }
+}
+
+#[test]
+fn leaving_range_mid_windup_cancels_the_shot() {
+    // The collector walks the same way no matter what the scout does, so a first run
+    // tells how long it takes to get out of range once told to walk off
+    let first_launch = duel(None, SHOT_INTERVAL).launches[0];
+    let walk_off_at = first_launch + 1;
+    let calibration = duel(Some(walk_off_at), 2 * SHOT_INTERVAL);
+    let time_to_leave = calibration.target_left_range.expect("target left range") - walk_off_at;
+
+    // Walk off so that it leaves range halfway through aiming the second shot
+    let second_windup_start = first_launch + SCOUT_WEAPON_COOLDOWN_TICKS;
+    let leave_at = second_windup_start + SCOUT_WEAPON_WINDUP_TICKS / 2;
+    let duel = duel(Some(leave_at - time_to_leave), 6 * SHOT_INTERVAL);
+    assert_eq!(duel.target_left_range, Some(leave_at));
+
+    // Once it is back, the second shot gets aimed all over again, rather than picking up where it left off
+    let came_back = duel.target_came_back.expect("target came back");
+    assert_eq!(duel.launches[0], first_launch);
+    assert!(duel.launches[1] >= came_back + SCOUT_WEAPON_WINDUP_TICKS);
+}